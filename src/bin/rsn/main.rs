@@ -0,0 +1,528 @@
+//! The `rsn` command line tool.
+//!
+//! ```text
+//! rsn check [--color] [FILE...]
+//! rsn fmt [--check] [--indent N] [FILE...]
+//! rsn to-json [--pretty] [--annotated] [FILE]
+//! rsn from-json [--compact] [--annotated] [FILE]
+//! rsn get PATH [FILE]
+//! ```
+//!
+//! When no file is given, or the file is `-`, standard input is read. The exit
+//! status is `0` on success, `1` when the input is invalid, unformatted, or
+//! doesn't contain the requested path, and `2` when the arguments are invalid
+//! or a file can't be read or written.
+
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::{env, fs};
+
+use rsn::diagnostics::SourceMap;
+use rsn::parser::{Config, Parser};
+use rsn::value::{JsonConfig, Value};
+use rsn::writer;
+
+const USAGE: &str = "\
+Usage: rsn <COMMAND> [OPTIONS] [FILE...]
+
+Commands:
+  check [--color] [FILE...]                    Report every error in each file
+  fmt [--check] [--indent N] [FILE...]         Format files in place, or standard input to standard output
+  to-json [--pretty] [--annotated] [FILE]      Convert Rsn to JSON
+  from-json [--compact] [--annotated] [FILE]   Convert JSON to Rsn
+  get PATH [FILE]                              Print the first value matching PATH, such as `servers[2].tls.cert`
+
+When FILE is omitted or is `-`, standard input is read. `--annotated` uses JSON
+objects describing Rsn-only types, allowing conversions without losing data.";
+
+/// The result of a command that didn't succeed.
+enum Failure {
+    /// The input was invalid, unformatted, or didn't contain the requested
+    /// value. Any details have already been reported.
+    Input,
+    /// The arguments were invalid.
+    Usage(String),
+    /// A file couldn't be read or written.
+    Io(String),
+}
+
+impl From<io::Error> for Failure {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+/// The standard streams used by a command.
+struct Streams<'a> {
+    stdin: &'a mut dyn Read,
+    stdout: &'a mut dyn Write,
+    stderr: &'a mut dyn Write,
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    ExitCode::from(run(
+        &args,
+        &mut Streams {
+            stdin: &mut io::stdin(),
+            stdout: &mut io::stdout(),
+            stderr: &mut io::stderr(),
+        },
+    ))
+}
+
+/// Runs the command described by `args`, returning the exit status.
+fn run(args: &[String], streams: &mut Streams<'_>) -> u8 {
+    let Some((command, args)) = args.split_first() else {
+        let _ = writeln!(streams.stderr, "{USAGE}");
+        return 2;
+    };
+    let result = match command.as_str() {
+        "check" => check(args, streams),
+        "fmt" => fmt(args, streams),
+        "to-json" => to_json(args, streams),
+        "from-json" => from_json(args, streams),
+        "get" => get(args, streams),
+        "help" | "-h" | "--help" => writeln!(streams.stdout, "{USAGE}").map_err(Failure::from),
+        other => Err(Failure::Usage(format!("unknown command `{other}`"))),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(Failure::Input) => 1,
+        Err(Failure::Usage(message)) => {
+            let _ = writeln!(streams.stderr, "error: {message}\n\n{USAGE}");
+            2
+        }
+        Err(Failure::Io(message)) => {
+            let _ = writeln!(streams.stderr, "error: {message}");
+            2
+        }
+    }
+}
+
+/// The flags and file arguments passed to a command.
+struct Arguments<'a> {
+    flags: Vec<&'a str>,
+    indent: Option<usize>,
+    files: Vec<&'a str>,
+}
+
+impl<'a> Arguments<'a> {
+    fn parse(args: &'a [String], allowed_flags: &[&str]) -> Result<Self, Failure> {
+        let mut parsed = Self {
+            flags: Vec::new(),
+            indent: None,
+            files: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--indent" if allowed_flags.contains(&"--indent") => {
+                    let indent = args
+                        .next()
+                        .and_then(|indent| indent.parse().ok())
+                        .ok_or_else(|| {
+                            Failure::Usage(String::from("`--indent` requires a number"))
+                        })?;
+                    parsed.indent = Some(indent);
+                }
+                flag if allowed_flags.contains(&flag) => parsed.flags.push(flag),
+                flag if flag.starts_with("--") => {
+                    return Err(Failure::Usage(format!("unknown option `{flag}`")));
+                }
+                file => parsed.files.push(file),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /// Returns the single file argument, or `-` if none was given.
+    fn single_file(&self) -> Result<&'a str, Failure> {
+        match self.files.as_slice() {
+            [] => Ok("-"),
+            [file] => Ok(file),
+            _ => Err(Failure::Usage(String::from("expected at most one file"))),
+        }
+    }
+
+    /// Returns the file arguments, or `-` if none were given.
+    fn files(&self) -> Vec<&'a str> {
+        if self.files.is_empty() {
+            vec!["-"]
+        } else {
+            self.files.clone()
+        }
+    }
+}
+
+fn read(file: &str, streams: &mut Streams<'_>) -> Result<String, Failure> {
+    let contents = if file == "-" {
+        let mut contents = String::new();
+        streams.stdin.read_to_string(&mut contents)?;
+        contents
+    } else {
+        fs::read_to_string(file).map_err(|err| Failure::Io(format!("{file}: {err}")))?
+    };
+    Ok(contents)
+}
+
+fn display_name(file: &str) -> &str {
+    if file == "-" {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+fn parser_config() -> Config {
+    Config::default().allow_implicit_map_at_root(true)
+}
+
+/// Parses `source`, reporting any errors to standard error.
+fn parse<'s>(file: &str, source: &'s str, streams: &mut Streams<'_>) -> Result<Value<'s>, Failure> {
+    match Value::from_str(source, parser_config()) {
+        Ok(value) => Ok(value),
+        Err(err) => {
+            let map = SourceMap::new(source);
+            write!(
+                streams.stderr,
+                "{}",
+                map.snippet(&err).origin(display_name(file))
+            )?;
+            Err(Failure::Input)
+        }
+    }
+}
+
+fn check(args: &[String], streams: &mut Streams<'_>) -> Result<(), Failure> {
+    let args = Arguments::parse(args, &["--color"])?;
+    let mut valid = true;
+    for file in args.files() {
+        let source = read(file, streams)?;
+        let (_, errors) = Parser::parse_recovering(&source, parser_config());
+        let map = SourceMap::new(&source);
+        for err in &errors {
+            write!(
+                streams.stderr,
+                "{}",
+                map.snippet(err)
+                    .origin(display_name(file))
+                    .ansi(args.has("--color"))
+            )?;
+        }
+        valid &= errors.is_empty();
+    }
+    if valid {
+        Ok(())
+    } else {
+        Err(Failure::Input)
+    }
+}
+
+fn fmt(args: &[String], streams: &mut Streams<'_>) -> Result<(), Failure> {
+    let args = Arguments::parse(args, &["--check", "--indent"])?;
+    let config = writer::Config::Pretty {
+        indentation: Cow::Owned(" ".repeat(args.indent.unwrap_or(2))),
+        newline: Cow::Borrowed("\n"),
+        max_width: None,
+        align_map_values: false,
+    };
+    let mut succeeded = true;
+    for file in args.files() {
+        let source = read(file, streams)?;
+        let formatted = match rsn::format(&source, &config) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                let map = SourceMap::new(&source);
+                write!(
+                    streams.stderr,
+                    "{}",
+                    map.snippet(&err).origin(display_name(file))
+                )?;
+                succeeded = false;
+                continue;
+            }
+        };
+        if args.has("--check") {
+            if formatted != source {
+                writeln!(streams.stdout, "{}", display_name(file))?;
+                succeeded = false;
+            }
+        } else if file == "-" {
+            streams.stdout.write_all(formatted.as_bytes())?;
+        } else if formatted != source {
+            fs::write(file, formatted).map_err(|err| Failure::Io(format!("{file}: {err}")))?;
+        }
+    }
+    if succeeded {
+        Ok(())
+    } else {
+        Err(Failure::Input)
+    }
+}
+
+fn to_json(args: &[String], streams: &mut Streams<'_>) -> Result<(), Failure> {
+    let args = Arguments::parse(args, &["--pretty", "--annotated"])?;
+    let file = args.single_file()?;
+    let source = read(file, streams)?;
+    let value = parse(file, &source, streams)?;
+    let config = JsonConfig::default()
+        .pretty(args.has("--pretty"))
+        .annotated(args.has("--annotated"));
+    writeln!(streams.stdout, "{}", value.to_json_string(config))?;
+    Ok(())
+}
+
+fn from_json(args: &[String], streams: &mut Streams<'_>) -> Result<(), Failure> {
+    let args = Arguments::parse(args, &["--compact", "--annotated"])?;
+    let file = args.single_file()?;
+    let source = read(file, streams)?;
+    let config = JsonConfig::default().annotated(args.has("--annotated"));
+    let value = match Value::from_json(&source, config) {
+        Ok(value) => value,
+        Err(err) => {
+            let map = SourceMap::new(&source);
+            write!(
+                streams.stderr,
+                "{}",
+                map.snippet(&err).origin(display_name(file))
+            )?;
+            return Err(Failure::Input);
+        }
+    };
+    if args.has("--compact") {
+        writeln!(streams.stdout, "{value}")?;
+    } else {
+        writeln!(streams.stdout, "{value:#}")?;
+    }
+    Ok(())
+}
+
+fn get(args: &[String], streams: &mut Streams<'_>) -> Result<(), Failure> {
+    let args = Arguments::parse(args, &[])?;
+    let Some((path, files)) = args.files.split_first() else {
+        return Err(Failure::Usage(String::from("expected a path")));
+    };
+    let file = Arguments {
+        files: files.to_vec(),
+        ..args
+    }
+    .single_file()?;
+    let source = read(file, streams)?;
+    let value = parse(file, &source, streams)?;
+    let found = value
+        .get_path(path)
+        .map_err(|err| Failure::Usage(format!("`{path}` is not a valid path: {err}")))?;
+    if let Some(value) = found {
+        writeln!(streams.stdout, "{value:#}")?;
+        Ok(())
+    } else {
+        writeln!(
+            streams.stderr,
+            "error: {}: `{path}` not found",
+            display_name(file)
+        )?;
+        Err(Failure::Input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Runs `rsn` with `args` and `stdin`, returning the exit status, standard
+    /// output, and standard error.
+    fn rsn(args: &[&str], stdin: &str) -> (u8, String, String) {
+        let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let status = run(
+            &args,
+            &mut Streams {
+                stdin: &mut stdin.as_bytes(),
+                stdout: &mut stdout,
+                stderr: &mut stderr,
+            },
+        );
+        (
+            status,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    /// Writes `contents` to a new file in the temporary directory.
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("rsn-cli-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn usage() {
+        let (status, stdout, stderr) = rsn(&[], "");
+        assert_eq!((status, stdout.as_str()), (2, ""));
+        assert!(stderr.starts_with("Usage: rsn"), "{stderr}");
+
+        let (status, _, stderr) = rsn(&["frobnicate"], "");
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: unknown command `frobnicate`"),
+            "{stderr}"
+        );
+
+        let (status, stdout, _) = rsn(&["help"], "");
+        assert_eq!(status, 0);
+        assert!(stdout.starts_with("Usage: rsn"), "{stdout}");
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(
+            rsn(&["check"], "a: [1, 2]\nb: {}"),
+            (0, String::new(), String::new())
+        );
+
+        let (status, stdout, stderr) = rsn(&["check", "-"], "a: [1,\nb: )");
+        assert_eq!((status, stdout.as_str()), (1, ""));
+        assert!(stderr.contains("<stdin>:"), "{stderr}");
+
+        let (status, _, stderr) = rsn(&["check", "--colour"], "");
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: unknown option `--colour`"),
+            "{stderr}"
+        );
+
+        let missing = env::temp_dir().join("rsn-cli-missing.rsn");
+        let (status, _, stderr) = rsn(&["check", missing.to_str().unwrap()], "");
+        assert_eq!(status, 2);
+        assert!(stderr.starts_with("error: "), "{stderr}");
+    }
+
+    #[test]
+    fn fmt() {
+        let unformatted = "a:[1,2]";
+        let formatted = "a: [\n    1,\n    2\n]\n";
+        assert_eq!(
+            rsn(&["fmt", "--indent", "4"], unformatted),
+            (0, String::from(formatted), String::new())
+        );
+        assert_eq!(
+            rsn(&["fmt", "--check", "--indent", "4"], unformatted),
+            (1, String::from("<stdin>\n"), String::new())
+        );
+        assert_eq!(
+            rsn(&["fmt", "--check", "--indent", "4"], formatted),
+            (0, String::new(), String::new())
+        );
+
+        let (status, _, stderr) = rsn(&["fmt", "--indent"], "");
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: `--indent` requires a number"),
+            "{stderr}"
+        );
+
+        let (status, _, stderr) = rsn(&["fmt", "--check"], "a: [");
+        assert_eq!(status, 1);
+        assert!(stderr.contains("<stdin>:"), "{stderr}");
+
+        let path = temp_file("fmt.rsn", unformatted);
+        let file = path.to_str().unwrap();
+        assert_eq!(
+            rsn(&["fmt", "--check", "--indent", "4", file], ""),
+            (1, format!("{file}\n"), String::new())
+        );
+        assert_eq!(
+            rsn(&["fmt", "--indent", "4", file], ""),
+            (0, String::new(), String::new())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), formatted);
+        assert_eq!(
+            rsn(&["fmt", "--check", "--indent", "4", file], ""),
+            (0, String::new(), String::new())
+        );
+
+        // Files after one that can't be parsed are still formatted.
+        let invalid = temp_file("fmt-invalid.rsn", "a: [");
+        fs::write(&path, unformatted).unwrap();
+        let (status, stdout, stderr) = rsn(
+            &["fmt", "--indent", "4", invalid.to_str().unwrap(), file],
+            "",
+        );
+        assert_eq!((status, stdout.as_str()), (1, ""));
+        assert!(stderr.contains("fmt-invalid.rsn:"), "{stderr}");
+        assert_eq!(fs::read_to_string(&path).unwrap(), formatted);
+        fs::remove_file(invalid).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            rsn(&["to-json"], "a: [1, 'x']"),
+            (0, String::from("{\"a\":[1,\"x\"]}\n"), String::new())
+        );
+        assert_eq!(
+            rsn(&["to-json", "--annotated"], "'x'"),
+            (0, String::from("{\"$char\":\"x\"}\n"), String::new())
+        );
+        let (status, stdout, stderr) = rsn(&["to-json"], "a: [");
+        assert_eq!((status, stdout.as_str()), (1, ""));
+        assert!(stderr.contains("<stdin>:"), "{stderr}");
+        let (status, _, stderr) = rsn(&["to-json", "a.rsn", "b.rsn"], "");
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: expected at most one file"),
+            "{stderr}"
+        );
+
+        assert_eq!(
+            rsn(&["from-json", "--compact"], "{\"a\": [1, \"x\"]}"),
+            (0, String::from("{\"a\":[1,\"x\"]}\n"), String::new())
+        );
+        assert_eq!(
+            rsn(
+                &["from-json", "--compact", "--annotated"],
+                "{\"$char\": \"x\"}"
+            ),
+            (0, String::from("'x'\n"), String::new())
+        );
+        let (status, stdout, stderr) = rsn(&["from-json"], "{\"a\": }");
+        assert_eq!((status, stdout.as_str()), (1, ""));
+        assert!(stderr.contains("<stdin>:"), "{stderr}");
+    }
+
+    #[test]
+    fn get() {
+        let source = "servers: [{ port: 80 }]";
+        assert_eq!(
+            rsn(&["get", "servers[0].port"], source),
+            (0, String::from("80\n"), String::new())
+        );
+        assert_eq!(
+            rsn(&["get", "servers[1]", "-"], source),
+            (
+                1,
+                String::new(),
+                String::from("error: <stdin>: `servers[1]` not found\n")
+            )
+        );
+        let (status, _, stderr) = rsn(&["get", "servers["], source);
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: `servers[` is not a valid path"),
+            "{stderr}"
+        );
+        let (status, _, stderr) = rsn(&["get"], source);
+        assert_eq!(status, 2);
+        assert!(stderr.starts_with("error: expected a path"), "{stderr}");
+    }
+}
@@ -1,18 +1,41 @@
 use alloc::borrow::Cow;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt::Display;
+use core::marker::PhantomData;
 use core::ops::Range;
 
-use serde::de::{DeserializeOwned, EnumAccess, MapAccess, SeqAccess, VariantAccess};
+use serde::de::{
+    DeserializeOwned, EnumAccess, Expected, MapAccess, SeqAccess, Unexpected, VariantAccess,
+};
 use serde::Deserialize;
 
-use crate::parser::{self, Config, Event, EventKind, Name, Nested, Parser, Primitive};
-use crate::tokenizer::{self, Integer};
+use crate::de::path::Segment;
+pub use crate::de::path::{Path, PathSegment};
+pub use crate::de::raw::RawValue;
+pub use crate::de::spanned::Spanned;
+use crate::de::spanned::SpannedAccess;
+use crate::parser::{self, Config, Event, EventKind, Nested, Parser, Primitive};
+#[cfg(feature = "std")]
+use crate::tokenizer::ReaderTokenizer;
+use crate::tokenizer::{self, Dialect, Integer, TokenSource, Tokenizer};
+use crate::value::{self, Value};
+
+mod path;
+pub(crate) mod raw;
+mod spanned;
 
 /// Deserializes Rsn using Serde.
-pub struct Deserializer<'de> {
-    parser: BetterPeekable<Parser<'de>>,
+pub struct Deserializer<'de, Tokens = Tokenizer<'de, false>>
+where
+    Tokens: TokenSource<'de>,
+{
+    parser: BetterPeekable<Parser<'de, Tokens>>,
     newtype_state: Option<NewtypeState>,
+    path: Vec<Segment<'de>>,
+    /// The path segment of the map key that was most recently deserialized,
+    /// until its value is deserialized.
+    key_segment: Option<Segment<'de>>,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq)]
@@ -24,13 +47,53 @@ enum NewtypeState {
 impl<'de> Deserializer<'de> {
     /// Returns a deserializer for `source` with the given `configuration`.
     ///
-    /// `Config::include_comments` will always be disabled, regardless of the
-    /// value set in `configuration`.
+    /// `Config::include_comments` and `Config::recover_from_errors` will always
+    /// be disabled, regardless of the values set in `configuration`.
     #[must_use]
     pub fn new(source: &'de str, configuration: Config) -> Self {
+        Self::from_parser(Parser::new(
+            source,
+            configuration
+                .include_comments(false)
+                .recover_from_errors(false),
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Deserializer<'static, ReaderTokenizer<R, false>>
+where
+    R: std::io::Read,
+{
+    /// Returns a deserializer that incrementally reads from `reader` with the
+    /// given `configuration`.
+    ///
+    /// Because the input is not kept in memory, types that borrow from the
+    /// source cannot be deserialized using this deserializer.
+    ///
+    /// `Config::include_comments` and `Config::recover_from_errors` will always
+    /// be disabled, regardless of the values set in `configuration`.
+    #[must_use]
+    pub fn from_reader(reader: R, configuration: Config) -> Self {
+        Self::from_parser(Parser::from_reader(
+            reader,
+            configuration
+                .include_comments(false)
+                .recover_from_errors(false),
+        ))
+    }
+}
+
+impl<'de, Tokens> Deserializer<'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
+    fn from_parser(parser: Parser<'de, Tokens>) -> Self {
         Self {
-            parser: BetterPeekable::new(Parser::new(source, configuration.include_comments(false))),
+            parser: BetterPeekable::new(parser),
             newtype_state: None,
+            path: Vec::new(),
+            key_segment: None,
         }
     }
 
@@ -48,6 +111,34 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    /// Returns an iterator that deserializes a sequence of `T` values from
+    /// this deserializer.
+    ///
+    /// See [`StreamDeserializer`] for more information.
+    #[must_use]
+    pub fn into_stream<T>(self) -> StreamDeserializer<'de, T, Tokens>
+    where
+        T: Deserialize<'de>,
+    {
+        StreamDeserializer {
+            de: self,
+            fused: false,
+            _value: PhantomData,
+        }
+    }
+
+    fn deserialize_all<T: Deserialize<'de>>(mut self) -> Result<T, Error> {
+        let result = match T::deserialize(&mut self) {
+            Ok(result) => result,
+            Err(err) => {
+                let location = err.location.unwrap_or_else(|| self.parser.current_range());
+                return Err(Error::new(location, err.kind).with_path(err.path));
+            }
+        };
+        self.ensure_eof()?;
+        Ok(result)
+    }
+
     fn handle_unit(&mut self) -> Result<(), DeserializerError> {
         self.with_error_context(|de| match de.parser.next().transpose()? {
             Some(Event {
@@ -75,6 +166,11 @@ impl<'de> Deserializer<'de> {
                 }
                 Ok(())
             }
+            // JSON's `null` is parsed as `None`.
+            Some(Event {
+                kind: EventKind::Primitive(Primitive::Identifier(ident)),
+                ..
+            }) if ident == "None" && de.parser.config().dialect == Dialect::Json => Ok(()),
             Some(evt) => Err(DeserializerError::new(
                 evt.location,
                 ErrorKind::ExpectedUnit,
@@ -83,6 +179,29 @@ impl<'de> Deserializer<'de> {
         })
     }
 
+    /// Returns the byte offset of the start of the next value, including the
+    /// name of a named structure or enum variant.
+    fn peek_value_start(&mut self) -> Result<usize, DeserializerError> {
+        match self.parser.peek() {
+            Some(Ok(Event {
+                kind:
+                    EventKind::BeginNested {
+                        name: Some(name), ..
+                    },
+                ..
+            })) => Ok(name.location.start),
+            Some(Ok(event)) => Ok(event.location.start),
+            Some(Err(_)) => {
+                let err = self.parser.next().expect("just peeked").unwrap_err();
+                Err(err.into())
+            }
+            None => Err(DeserializerError::new(
+                None,
+                parser::ErrorKind::UnexpectedEof,
+            )),
+        }
+    }
+
     fn with_error_context<T>(
         &mut self,
         f: impl FnOnce(&mut Self) -> Result<T, DeserializerError>,
@@ -107,6 +226,36 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    /// Invokes `f` with `segment` added to the path of the value being
+    /// deserialized.
+    fn with_path_segment<T>(
+        &mut self,
+        segment: Segment<'de>,
+        f: impl FnOnce(&mut Self) -> Result<T, DeserializerError>,
+    ) -> Result<T, DeserializerError> {
+        self.path.push(segment);
+        let result = f(&mut *self);
+        self.leave_path_segment(result)
+    }
+
+    /// Removes the last segment of the current path. If `result` is an error
+    /// that does not have a path yet, the current path is recorded first.
+    fn leave_path_segment<T>(
+        &mut self,
+        result: Result<T, DeserializerError>,
+    ) -> Result<T, DeserializerError> {
+        let result = result.map_err(|mut err| {
+            if err.path.is_none() {
+                err.path = Some(Path(
+                    self.path.iter().map(Segment::to_path_segment).collect(),
+                ));
+            }
+            err
+        });
+        self.path.pop();
+        result
+    }
+
     fn set_newtype_state(&mut self, state: NewtypeState) -> NewtypeStateModification {
         let old_state = self.newtype_state.replace(state);
         NewtypeStateModification(old_state)
@@ -117,6 +266,147 @@ impl<'de> Deserializer<'de> {
     }
 }
 
+/// An iterator that deserializes a sequence of values from a single source.
+///
+/// This is useful for formats where one Rsn value is written after another,
+/// such as a log where each line contains a single value:
+///
+/// ```rust
+/// use rsn::de::Deserializer;
+/// use rsn::parser::Config;
+///
+/// let source = "[1, 2]\n[3]\n[]\n";
+/// let values = Deserializer::new(source, Config::default())
+///     .into_stream::<Vec<u32>>()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(values, [(0, vec![1, 2]), (7, vec![3]), (11, vec![])]);
+/// ```
+///
+/// Each item contains the byte offset where the value starts along with the
+/// deserialized value.
+///
+/// If a value can be parsed but not deserialized as `T`, an error is returned
+/// and the remainder of that value is skipped. The next call to `next()` will
+/// resume with the following value. If the input contains a syntax error that
+/// cannot be skipped, the error is returned and iteration stops.
+pub struct StreamDeserializer<'de, T, Tokens = Tokenizer<'de, false>>
+where
+    Tokens: TokenSource<'de>,
+{
+    de: Deserializer<'de, Tokens>,
+    fused: bool,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> StreamDeserializer<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    /// Returns a stream deserializer for `source` with the given
+    /// `configuration`.
+    ///
+    /// `Config::include_comments` and `Config::recover_from_errors` will always
+    /// be disabled, regardless of the values set in `configuration`.
+    #[must_use]
+    pub fn new(source: &'de str, configuration: Config) -> Self {
+        Deserializer::new(source, configuration).into_stream()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, R> StreamDeserializer<'static, T, ReaderTokenizer<R, false>>
+where
+    T: DeserializeOwned,
+    R: std::io::Read,
+{
+    /// Returns a stream deserializer that incrementally reads from `reader`
+    /// with the given `configuration`.
+    ///
+    /// `Config::include_comments` and `Config::recover_from_errors` will always
+    /// be disabled, regardless of the values set in `configuration`.
+    #[must_use]
+    pub fn from_reader(reader: R, configuration: Config) -> Self {
+        Deserializer::from_reader(reader, configuration).into_stream()
+    }
+}
+
+impl<'de, T, Tokens> StreamDeserializer<'de, T, Tokens>
+where
+    T: Deserialize<'de>,
+    Tokens: TokenSource<'de>,
+{
+    /// Returns the byte offset of the end of the last value read.
+    #[must_use]
+    pub fn byte_offset(&self) -> usize {
+        self.de.parser.current_offset()
+    }
+
+    /// Consumes events until the current value has been completely parsed.
+    /// Returns false if the value could not be skipped.
+    fn skip_value(&mut self) -> bool {
+        let mut last_error_end = None;
+        while self.de.parser.peeked.is_some() || !self.de.parser.value_complete() {
+            match self.de.parser.next() {
+                Some(Ok(_)) => {}
+                // Syntax errors are skipped as long as the parser is making
+                // progress through the input.
+                Some(Err(err)) if last_error_end.map_or(true, |end| err.location.end > end) => {
+                    last_error_end = Some(err.location.end);
+                }
+                Some(Err(_)) | None => return false,
+            }
+        }
+        true
+    }
+}
+
+impl<'de, T, Tokens> Iterator for StreamDeserializer<'de, T, Tokens>
+where
+    T: Deserialize<'de>,
+    Tokens: TokenSource<'de>,
+{
+    type Item = Result<(usize, T), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fused {
+            return None;
+        }
+
+        self.de.parser.iter.begin_next_value();
+        let offset_before = self.byte_offset();
+        let start = match self.de.parser.peek()? {
+            Ok(Event {
+                kind:
+                    EventKind::BeginNested {
+                        name: Some(name), ..
+                    },
+                ..
+            }) => name.location.start,
+            Ok(event) => event.location.start,
+            Err(_) => {
+                let err = self.de.parser.next().expect("just peeked").unwrap_err();
+                // An error at the start of a value leaves the parser ready to
+                // parse the next value, but only retry if the input advanced.
+                self.fused = self.byte_offset() == offset_before;
+                return Some(Err(err.into()));
+            }
+        };
+
+        match self
+            .de
+            .with_error_start(start, |de| T::deserialize(&mut *de))
+        {
+            Ok(value) => Some(Ok((start, value))),
+            Err(err) => {
+                self.fused = !self.skip_value();
+                let location = err.location.unwrap_or_else(|| start..self.byte_offset());
+                Some(Err(Error::new(location, err.kind).with_path(err.path)))
+            }
+        }
+    }
+}
+
 #[must_use]
 #[derive(Copy, Clone)]
 struct NewtypeStateModification(Option<NewtypeState>);
@@ -144,7 +434,10 @@ macro_rules! deserialize_int_impl {
     };
 }
 
-impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
+impl<'de, Tokens> serde::de::Deserializer<'de> for &mut Deserializer<'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
     type Error = DeserializerError;
 
     deserialize_int_impl!(deserialize_i8, visit_i8, as_i8);
@@ -256,7 +549,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
                         if v == "None" && event.location.end - event.location.start == 4 {
                             visitor.visit_none()
                         } else {
-                            visitor.visit_borrowed_str(v)
+                            match v {
+                                Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+                                Cow::Owned(v) => visitor.visit_string(v),
+                            }
                         }
                     }
                     Primitive::Bytes(v) => match v {
@@ -265,6 +561,7 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
                     },
                 },
                 EventKind::Comment(_) => unreachable!("comments are disabled"),
+                EventKind::Placeholder => unreachable!("error recovery is disabled"),
                 EventKind::EndNested => unreachable!("parser would error"),
             }
         })
@@ -340,43 +637,57 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        self.with_error_context(|de| match de.parser.next().transpose()? {
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::Identifier(str)),
-                ..
-            }) => visitor.visit_borrowed_str(str),
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::String(str)),
-                ..
-            }) => match str {
-                Cow::Borrowed(str) => visitor.visit_borrowed_str(str),
-                Cow::Owned(str) => visitor.visit_string(str),
-            },
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::Bytes(bytes)),
-                location,
-            }) => match bytes {
-                Cow::Borrowed(bytes) => visitor.visit_borrowed_str(
-                    core::str::from_utf8(bytes)
-                        .map_err(|_| DeserializerError::new(location, ErrorKind::InvalidUtf8))?,
-                ),
-                Cow::Owned(bytes) => visitor.visit_string(
-                    String::from_utf8(bytes)
-                        .map_err(|_| DeserializerError::new(location, ErrorKind::InvalidUtf8))?,
-                ),
-            },
-            Some(Event {
-                kind:
-                    EventKind::BeginNested {
-                        name: Some(name), ..
-                    },
-                ..
-            }) => visitor.visit_str(name.name),
-            Some(evt) => Err(DeserializerError::new(
-                evt.location,
-                ErrorKind::ExpectedString,
-            )),
-            None => Err(DeserializerError::new(None, ErrorKind::ExpectedString)),
+        self.with_error_context(|de| {
+            let event = de.parser.next().transpose()?;
+            let location = event.as_ref().map(|event| event.location.clone());
+            match event {
+                // JSON's `null` is parsed as `None`, which isn't a string.
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Identifier(_)),
+                    location,
+                }) if de.parser.config().dialect == Dialect::Json => {
+                    Err(DeserializerError::new(location, ErrorKind::ExpectedString))
+                }
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Identifier(str) | Primitive::String(str)),
+                    ..
+                }) => match str {
+                    Cow::Borrowed(str) => visitor.visit_borrowed_str(str),
+                    Cow::Owned(str) => OwnedVisitError::visit(|| visitor.visit_string(str)),
+                },
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Bytes(bytes)),
+                    location,
+                }) => match bytes {
+                    Cow::Borrowed(bytes) => {
+                        visitor.visit_borrowed_str(core::str::from_utf8(bytes).map_err(|_| {
+                            DeserializerError::new(location, ErrorKind::InvalidUtf8)
+                        })?)
+                    }
+                    Cow::Owned(bytes) => {
+                        let str = String::from_utf8(bytes).map_err(|_| {
+                            DeserializerError::new(location, ErrorKind::InvalidUtf8)
+                        })?;
+                        OwnedVisitError::visit(|| visitor.visit_string(str))
+                    }
+                },
+                Some(Event {
+                    kind:
+                        EventKind::BeginNested {
+                            name: Some(name), ..
+                        },
+                    ..
+                }) => match name.name {
+                    Cow::Borrowed(name) => visitor.visit_borrowed_str(name),
+                    Cow::Owned(name) => OwnedVisitError::visit(|| visitor.visit_string(name)),
+                },
+                Some(evt) => Err(DeserializerError::new(
+                    evt.location,
+                    ErrorKind::ExpectedString,
+                )),
+                None => Err(DeserializerError::new(None, ErrorKind::ExpectedString)),
+            }
+            .map_err(|err| err.or_location(location))
         })
     }
 
@@ -391,30 +702,40 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        self.with_error_context(|de| match de.parser.next().transpose()? {
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::Identifier(str)),
-                ..
-            }) => visitor.visit_borrowed_bytes(str.as_bytes()),
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::String(str)),
-                ..
-            }) => match str {
-                Cow::Borrowed(str) => visitor.visit_borrowed_bytes(str.as_bytes()),
-                Cow::Owned(str) => visitor.visit_byte_buf(str.into_bytes()),
-            },
-            Some(Event {
-                kind: EventKind::Primitive(Primitive::Bytes(bytes)),
-                ..
-            }) => match bytes {
-                Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
-                Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
-            },
-            Some(evt) => Err(DeserializerError::new(
-                evt.location,
-                ErrorKind::ExpectedBytes,
-            )),
-            None => Err(DeserializerError::new(None, ErrorKind::ExpectedBytes)),
+        self.with_error_context(|de| {
+            let event = de.parser.next().transpose()?;
+            let location = event.as_ref().map(|event| event.location.clone());
+            match event {
+                // JSON's `null` is parsed as `None`, which isn't a string.
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Identifier(_)),
+                    location,
+                }) if de.parser.config().dialect == Dialect::Json => {
+                    Err(DeserializerError::new(location, ErrorKind::ExpectedBytes))
+                }
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Identifier(str) | Primitive::String(str)),
+                    ..
+                }) => match str {
+                    Cow::Borrowed(str) => visitor.visit_borrowed_bytes(str.as_bytes()),
+                    Cow::Owned(str) => {
+                        OwnedVisitError::visit(|| visitor.visit_byte_buf(str.into_bytes()))
+                    }
+                },
+                Some(Event {
+                    kind: EventKind::Primitive(Primitive::Bytes(bytes)),
+                    ..
+                }) => match bytes {
+                    Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+                    Cow::Owned(bytes) => OwnedVisitError::visit(|| visitor.visit_byte_buf(bytes)),
+                },
+                Some(evt) => Err(DeserializerError::new(
+                    evt.location,
+                    ErrorKind::ExpectedBytes,
+                )),
+                None => Err(DeserializerError::new(None, ErrorKind::ExpectedBytes)),
+            }
+            .map_err(|err| err.or_location(location))
         })
     }
 
@@ -440,11 +761,11 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
             Some(Ok(Event {
                 kind:
                     EventKind::BeginNested {
-                        name: Some(Name { name: "Some", .. }),
+                        name: Some(name),
                         kind: Nested::Tuple,
                     },
                 ..
-            })) => {
+            })) if name.name == "Some" => {
                 de.parser.next();
                 let result = visitor.visit_some(&mut *de)?;
                 match de.parser.next().transpose()? {
@@ -475,13 +796,24 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
 
     fn deserialize_unit_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        self.deserialize_unit(visitor)
+        if matches!(
+            self.parser.peek(),
+            Some(Ok(Event {
+                kind: EventKind::Primitive(Primitive::Identifier(ident)),
+                ..
+            })) if ident == name
+        ) {
+            self.parser.next();
+            visitor.visit_unit()
+        } else {
+            self.deserialize_unit(visitor)
+        }
     }
 
     fn deserialize_newtype_struct<V>(
@@ -492,6 +824,34 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
+        if name == raw::NAME {
+            return self.with_error_context(|de| {
+                let start = de.peek_value_start()?;
+                serde::de::IgnoredAny::deserialize(&mut *de)?;
+                let location = start..de.parser.last_end;
+                match de.parser.source(location.clone()) {
+                    Some(source) => visitor.visit_borrowed_str(source),
+                    None => Err(DeserializerError::new(location, ErrorKind::CannotBorrow)),
+                }
+            });
+        }
+
+        if name == value::NAME {
+            return self.with_error_context(|de| {
+                let event = de.parser.next().transpose()?.ok_or_else(|| {
+                    DeserializerError::new(None, parser::ErrorKind::UnexpectedEof)
+                })?;
+                let config = *de.parser.config();
+                let value = Value::from_parser_event(event, &mut de.parser, config)?;
+                value::visit_value(&value, visitor).map_err(serde::de::Error::custom)
+            });
+        }
+
+        // Peeking ensures any RON attributes have been parsed.
+        self.parser.peek();
+        if self.parser.ron_extensions().unwrap_newtypes {
+            return self.with_error_context(|de| visitor.visit_newtype_struct(de));
+        }
         self.deserialize_tuple_struct(name, 1, visitor)
     }
 
@@ -635,6 +995,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
+        if struct_name == spanned::NAME {
+            return self.with_error_context(|de| visitor.visit_map(SpannedAccess::new(de)?));
+        }
+
         self.with_error_context(|de| {
             match de.parser.next().transpose()? {
                 Some(Event {
@@ -715,7 +1079,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
                         depth -= 1;
                     }
                     Some(Event {
-                        kind: EventKind::Primitive(_) | EventKind::Comment(_),
+                        kind:
+                            EventKind::Primitive(_)
+                            | EventKind::Comment(_)
+                            | EventKind::Placeholder,
                         ..
                     }) => {}
                     None => {
@@ -736,7 +1103,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     }
 }
 
-impl<'de> MapAccess<'de> for Deserializer<'de> {
+impl<'de, Tokens> MapAccess<'de> for Deserializer<'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
     type Error = DeserializerError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
@@ -753,9 +1123,19 @@ impl<'de> MapAccess<'de> for Deserializer<'de> {
             }
             Some(Ok(evt)) => {
                 let error_start = evt.location.start;
-                de.with_error_start(error_start, |de| seed.deserialize(de).map(Some))
+                let segment = Segment::key(&evt.kind);
+                let result = de.with_path_segment(segment.clone(), |de| {
+                    de.with_error_start(error_start, |de| seed.deserialize(de).map(Some))
+                });
+                // The key's segment is added to the path again while its value
+                // is deserialized.
+                de.key_segment = result.is_ok().then_some(segment);
+                result
+            }
+            Some(_) => {
+                de.key_segment = None;
+                seed.deserialize(de).map(Some)
             }
-            Some(_) => seed.deserialize(de).map(Some),
             None => Err(DeserializerError::new(
                 None,
                 parser::ErrorKind::UnexpectedEof,
@@ -767,27 +1147,44 @@ impl<'de> MapAccess<'de> for Deserializer<'de> {
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        seed.deserialize(&mut *self)
+        match self.key_segment.take() {
+            Some(segment) => self.with_path_segment(segment, |de| seed.deserialize(de)),
+            None => seed.deserialize(&mut *self),
+        }
     }
 }
 
 mod sealed {
     use super::{
-        parser, Deserializer, DeserializerError, EnumAccess, ErrorKind, Event, EventKind, Nested,
-        NewtypeState, Primitive, SeqAccess, VariantAccess,
+        parser, Cow, Deserializer, DeserializerError, Dialect, EnumAccess, ErrorKind, Event,
+        EventKind, Nested, NewtypeState, Primitive, Segment, SeqAccess, TokenSource, VariantAccess,
     };
 
-    pub struct SequenceDeserializer<'a, 'de> {
-        de: &'a mut Deserializer<'de>,
+    pub struct SequenceDeserializer<'a, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
+        de: &'a mut Deserializer<'de, Tokens>,
+        index: usize,
         ended: bool,
     }
-    impl<'a, 'de> SequenceDeserializer<'a, 'de> {
-        pub(crate) fn new(de: &'a mut Deserializer<'de>) -> Self {
-            Self { de, ended: false }
+    impl<'a, 'de, Tokens> SequenceDeserializer<'a, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
+        pub(crate) fn new(de: &'a mut Deserializer<'de, Tokens>) -> Self {
+            Self {
+                de,
+                index: 0,
+                ended: false,
+            }
         }
     }
 
-    impl<'a, 'de> SeqAccess<'de> for SequenceDeserializer<'a, 'de> {
+    impl<'a, 'de, Tokens> SeqAccess<'de> for SequenceDeserializer<'a, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
         type Error = DeserializerError;
 
         fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -805,9 +1202,15 @@ mod sealed {
                 }
                 Some(Ok(evt)) => {
                     let error_start = evt.location.start;
-                    de.with_error_start(error_start, |de| seed.deserialize(de).map(Some))
+                    let index = self.index;
+                    self.index += 1;
+                    de.with_path_segment(Segment::Index(index), |de| {
+                        de.with_error_start(error_start, |de| seed.deserialize(de).map(Some))
+                    })
                 }
-                Some(_) => seed.deserialize(de).map(Some),
+                Some(_) => de.with_path_segment(Segment::Index(self.index), |de| {
+                    seed.deserialize(de).map(Some)
+                }),
                 None => Err(DeserializerError::new(
                     None,
                     parser::ErrorKind::UnexpectedEof,
@@ -816,7 +1219,10 @@ mod sealed {
         }
     }
 
-    impl<'a, 'de> Drop for SequenceDeserializer<'a, 'de> {
+    impl<'a, 'de, Tokens> Drop for SequenceDeserializer<'a, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
         fn drop(&mut self) {
             if !self.ended {
                 let mut levels = 1;
@@ -848,15 +1254,27 @@ mod sealed {
         }
     }
 
-    impl<'a, 'de> EnumAccess<'de> for &'a mut Deserializer<'de> {
+    impl<'a, 'de, Tokens> EnumAccess<'de> for &'a mut Deserializer<'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
         type Error = DeserializerError;
-        type Variant = EnumVariantAccessor<'a, 'de>;
+        type Variant = EnumVariantAccessor<'a, 'de, Tokens>;
 
         fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
         where
             V: serde::de::DeserializeSeed<'de>,
         {
+            let json = self.parser.config().dialect == Dialect::Json;
             match self.parser.peek() {
+                // JSON's `null` is parsed as `None`, which isn't a variant.
+                Some(Ok(Event {
+                    kind: EventKind::Primitive(Primitive::Identifier(_)),
+                    location,
+                })) if json => Err(DeserializerError::new(
+                    location.clone(),
+                    ErrorKind::ExpectedEnum,
+                )),
                 Some(Ok(Event {
                     kind: EventKind::Primitive(Primitive::Identifier(_) | Primitive::String(_)),
                     ..
@@ -868,8 +1286,9 @@ mod sealed {
                         },
                     ..
                 })) => {
+                    let variant_name = name.name.clone();
                     let variant = seed.deserialize(&mut VariantDeserializer(name))?;
-                    Ok((variant, EnumVariantAccessor::Nested(self)))
+                    Ok((variant, EnumVariantAccessor::Nested(self, variant_name)))
                 }
                 _ => Err(DeserializerError::new(None, ErrorKind::ExpectedEnum)),
             }
@@ -1123,12 +1542,18 @@ mod sealed {
         }
     }
 
-    pub enum EnumVariantAccessor<'a, 'de> {
+    pub enum EnumVariantAccessor<'a, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
         Unit,
-        Nested(&'a mut Deserializer<'de>),
+        Nested(&'a mut Deserializer<'de, Tokens>, Cow<'de, str>),
     }
 
-    impl<'a, 'de> VariantAccess<'de> for EnumVariantAccessor<'a, 'de> {
+    impl<'de, Tokens> VariantAccess<'de> for EnumVariantAccessor<'_, 'de, Tokens>
+    where
+        Tokens: TokenSource<'de>,
+    {
         type Error = DeserializerError;
 
         fn unit_variant(self) -> Result<(), Self::Error> {
@@ -1139,7 +1564,7 @@ mod sealed {
         where
             T: serde::de::DeserializeSeed<'de>,
         {
-            if let EnumVariantAccessor::Nested(deserializer) = self {
+            if let EnumVariantAccessor::Nested(deserializer, name) = self {
                 let modification = match deserializer.parser.peek() {
                     Some(Ok(Event {
                         kind:
@@ -1161,7 +1586,9 @@ mod sealed {
                     })) => Some(deserializer.set_newtype_state(NewtypeState::StructVariant)),
                     _ => None,
                 };
-                let result = deserializer.with_error_context(|de| seed.deserialize(&mut *de))?;
+                let result = deserializer.with_path_segment(Segment::Variant(name), |de| {
+                    de.with_error_context(|de| seed.deserialize(&mut *de))
+                })?;
                 if let Some(modification) = modification {
                     if deserializer.finish_newtype(modification) == Some(NewtypeState::TupleVariant)
                     {
@@ -1181,13 +1608,15 @@ mod sealed {
         where
             V: serde::de::Visitor<'de>,
         {
-            if let EnumVariantAccessor::Nested(deserializer) = self {
+            if let EnumVariantAccessor::Nested(deserializer, name) = self {
                 let nested_event = deserializer
                     .parser
                     .next()
                     .expect("variant access matched Nested")?;
-                deserializer.with_error_start(nested_event.location.start, |de| {
-                    visitor.visit_seq(SequenceDeserializer::new(de))
+                deserializer.with_path_segment(Segment::Variant(name), |de| {
+                    de.with_error_start(nested_event.location.start, |de| {
+                        visitor.visit_seq(SequenceDeserializer::new(de))
+                    })
                 })
             } else {
                 Err(DeserializerError::new(None, ErrorKind::ExpectedTupleStruct))
@@ -1202,13 +1631,14 @@ mod sealed {
         where
             V: serde::de::Visitor<'de>,
         {
-            if let EnumVariantAccessor::Nested(deserializer) = self {
+            if let EnumVariantAccessor::Nested(deserializer, name) = self {
                 let nested_event = deserializer
                     .parser
                     .next()
                     .expect("variant access matched Nested")?;
-                deserializer
-                    .with_error_start(nested_event.location.start, |de| visitor.visit_map(de))
+                deserializer.with_path_segment(Segment::Variant(name), |de| {
+                    de.with_error_start(nested_event.location.start, |de| visitor.visit_map(de))
+                })
             } else {
                 Err(DeserializerError::new(None, ErrorKind::ExpectedTupleStruct))
             }
@@ -1223,6 +1653,9 @@ pub struct Error {
     pub location: Range<usize>,
     /// The kind of error that occurred.
     pub kind: ErrorKind,
+    /// The path to the value that was being deserialized when the error
+    /// occurred.
+    pub path: Path,
 }
 
 impl Error {
@@ -1230,22 +1663,28 @@ impl Error {
         Self {
             location,
             kind: kind.into(),
+            path: Path::default(),
         }
     }
+
+    fn with_path(mut self, path: Option<Path>) -> Self {
+        self.path = path.unwrap_or_default();
+        self
+    }
 }
 
 impl From<parser::Error> for Error {
     fn from(err: parser::Error) -> Self {
-        Self {
-            location: err.location,
-            kind: err.kind.into(),
-        }
+        Self::new(err.location, err.kind)
     }
 }
 impl serde::ser::StdError for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
         write!(
             f,
             "{} at {}..{}",
@@ -1261,6 +1700,9 @@ pub struct DeserializerError {
     pub location: Option<Range<usize>>,
     /// The kind of error that occurred.
     pub kind: ErrorKind,
+    /// The path to the value that was being deserialized when the error
+    /// occurred, if available.
+    pub path: Option<Path>,
 }
 
 impl DeserializerError {
@@ -1268,8 +1710,17 @@ impl DeserializerError {
         Self {
             location: location.into(),
             kind: kind.into(),
+            path: None,
         }
     }
+
+    /// Sets the location of this error to `location` if it has no location.
+    fn or_location(mut self, location: Option<Range<usize>>) -> Self {
+        if self.location.is_none() {
+            self.location = location;
+        }
+        self
+    }
 }
 
 impl serde::de::Error for DeserializerError {
@@ -1277,17 +1728,65 @@ impl serde::de::Error for DeserializerError {
     where
         T: Display,
     {
-        Self {
-            location: None,
-            kind: ErrorKind::Message(msg.to_string()),
-        }
+        Self::new(None, ErrorKind::Message(msg.to_string()))
+    }
+
+    fn invalid_type(unexpected: Unexpected<'_>, expected: &dyn Expected) -> Self {
+        Self::custom(format_args!(
+            "invalid type: {unexpected}, expected {expected}"
+        ))
     }
 }
 
 impl serde::ser::StdError for DeserializerError {}
 
+/// The error returned by a visitor that was given a string or byte string
+/// that could not be borrowed from the input.
+///
+/// Visitors that require borrowed data, such as those for `&str` and `&[u8]`,
+/// reject owned values using [`serde::de::Error::invalid_type`], which is
+/// reported as [`ErrorKind::CannotBorrow`].
+#[derive(Debug)]
+struct OwnedVisitError(DeserializerError);
+
+impl OwnedVisitError {
+    fn visit<T>(visit: impl FnOnce() -> Result<T, Self>) -> Result<T, DeserializerError> {
+        visit().map_err(|err| err.0)
+    }
+}
+
+impl serde::de::Error for OwnedVisitError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(DeserializerError::custom(msg))
+    }
+
+    fn invalid_type(unexpected: Unexpected<'_>, expected: &dyn Expected) -> Self {
+        Self(
+            if matches!(unexpected, Unexpected::Str(_) | Unexpected::Bytes(_)) {
+                DeserializerError::new(None, ErrorKind::CannotBorrow)
+            } else {
+                DeserializerError::invalid_type(unexpected, expected)
+            },
+        )
+    }
+}
+
+impl serde::ser::StdError for OwnedVisitError {}
+
+impl Display for OwnedVisitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
 impl Display for DeserializerError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(path) = self.path.as_ref().filter(|path| !path.is_empty()) {
+            write!(f, "{path}: ")?;
+        }
         if let Some(location) = &self.location {
             write!(f, "{} at {}..{}", self.kind, location.start, location.end)
         } else {
@@ -1298,10 +1797,7 @@ impl Display for DeserializerError {
 
 impl From<parser::Error> for DeserializerError {
     fn from(err: parser::Error) -> Self {
-        Self {
-            location: Some(err.location),
-            kind: err.kind.into(),
-        }
+        Self::new(err.location, err.kind)
     }
 }
 
@@ -1345,6 +1841,11 @@ pub enum ErrorKind {
     /// `Some(_)` can only contain one value but more than one value was
     /// encountered.
     SomeCanOnlyContainOneValue,
+    /// A borrowed `&str`, `&[u8]`, or [`RawValue`] was requested, but the
+    /// value could not be borrowed from the input. Values containing escape
+    /// sequences must be decoded into a new allocation, and values parsed
+    /// from a reader are always owned.
+    CannotBorrow,
     /// An Rsn parsing error.
     Parser(parser::ErrorKind),
     /// An error from deserializing Serde.
@@ -1386,6 +1887,10 @@ impl Display for ErrorKind {
             ErrorKind::ExpectedEnum => f.write_str("expected enum"),
             ErrorKind::NameMismatch(name) => write!(f, "name mismatch, expected {name}"),
             ErrorKind::InvalidUtf8 => f.write_str("invalid utf-8"),
+            ErrorKind::CannotBorrow => f.write_str(
+                "value cannot be borrowed because it contains escape sequences or was read from \
+                 a reader",
+            ),
         }
     }
 }
@@ -1404,18 +1909,7 @@ impl Config {
     ///
     /// Returns an error if `source` cannot be deserialized as `T`.
     pub fn deserialize<'de, T: Deserialize<'de>>(self, source: &'de str) -> Result<T, Error> {
-        let mut deserializer = Deserializer::new(source, self);
-        let result = match T::deserialize(&mut deserializer) {
-            Ok(result) => result,
-            Err(err) => {
-                let location = err
-                    .location
-                    .unwrap_or_else(|| deserializer.parser.current_range());
-                return Err(Error::new(location, err.kind));
-            }
-        };
-        deserializer.ensure_eof()?;
-        Ok(result)
+        Deserializer::new(source, self).deserialize_all()
     }
 
     /// Deserializes `T` from `source` using this configuration.
@@ -1451,6 +1945,9 @@ impl Config {
 
     /// Deserializes `T` from `reader` using this configuration.
     ///
+    /// The input is parsed incrementally as it is read, which means the
+    /// entire contents of `reader` are never held in memory at once.
+    ///
     /// ```rust
     /// let deserialized: Vec<usize> = rsn::parser::Config::default()
     ///     .deserialize_from_reader(&b"[1, 2, 3]"[..])
@@ -1465,13 +1962,9 @@ impl Config {
     #[cfg(feature = "std")]
     pub fn deserialize_from_reader<T: DeserializeOwned, R: std::io::Read>(
         self,
-        mut reader: R,
+        reader: R,
     ) -> Result<T, Error> {
-        let mut source = alloc::vec::Vec::new();
-        reader
-            .read_to_end(&mut source)
-            .map_err(|e| Error::new(0..0, ErrorKind::Message(e.to_string())))?;
-        self.deserialize_from_slice(&source)
+        Deserializer::from_reader(reader, self).deserialize_all()
     }
 }
 
@@ -1481,6 +1974,8 @@ where
 {
     iter: T,
     peeked: Option<T::Item>,
+    /// The end of the location of the last event returned from `next()`.
+    last_end: usize,
 }
 
 impl<T> BetterPeekable<T>
@@ -1488,12 +1983,16 @@ where
     T: Iterator,
 {
     pub fn new(iter: T) -> Self {
-        Self { iter, peeked: None }
+        Self {
+            iter,
+            peeked: None,
+            last_end: 0,
+        }
     }
 
     pub fn peek(&mut self) -> Option<&T::Item> {
         if self.peeked.is_none() {
-            self.peeked = self.next();
+            self.peeked = self.iter.next();
         }
 
         self.peeked.as_ref()
@@ -1511,22 +2010,31 @@ where
     }
 }
 
-impl<T> Iterator for BetterPeekable<T>
+impl<'de, T> Iterator for BetterPeekable<T>
 where
-    T: Iterator,
+    T: Iterator<Item = Result<Event<'de>, parser::Error>>,
 {
     type Item = T::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.peeked.take().or_else(|| self.iter.next())
+        let item = self.peeked.take().or_else(|| self.iter.next());
+        if let Some(Ok(event)) = &item {
+            self.last_end = event.location.end;
+        }
+        item
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
     use serde::{Deserialize, Serialize};
 
+    use super::StreamDeserializer;
     use crate::parser::Config;
+    use crate::Spanned;
 
     #[test]
     fn basic_named() {
@@ -1622,4 +2130,552 @@ mod tests {
             BasicEnums::Tuple(1, 2)
         );
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader() {
+        #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+        enum BasicEnums {
+            Unit,
+            NewType(u32),
+            Struct { a: alloc::string::String },
+            Tuple(u32, u32),
+        }
+
+        let parsed = crate::from_reader::<alloc::vec::Vec<BasicEnums>, _>(
+            &b"[Unit, NewType(1), Struct{ a: \"hello\" }, Tuple(1, 2)]"[..],
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            [
+                BasicEnums::Unit,
+                BasicEnums::NewType(1),
+                BasicEnums::Struct {
+                    a: alloc::string::String::from("hello")
+                },
+                BasicEnums::Tuple(1, 2),
+            ]
+        );
+
+        let err = crate::from_reader::<alloc::vec::Vec<u32>, _>(&b"[1, 2] 3"[..]).unwrap_err();
+        assert_eq!(err.location, 7..8);
+    }
+
+    #[test]
+    fn stream() {
+        #[derive(Debug, Deserialize, Eq, PartialEq)]
+        struct Entry<'a> {
+            level: &'a str,
+            count: u32,
+        }
+
+        let source = "Entry { level: \"info\", count: 1 }\n\
+                      Entry { level: \"warn\", count: -1 }\n\
+                      Entry { level: \"error\", count: 3 }\n\
+                      4\n\
+                      } Entry { level: \"debug\", count: 5 }";
+        let mut stream = StreamDeserializer::<Entry<'_>>::new(source, Config::default());
+        assert_eq!(
+            stream.next().unwrap().unwrap(),
+            (
+                0,
+                Entry {
+                    level: "info",
+                    count: 1
+                }
+            )
+        );
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(&source[err.location], "-1");
+        assert_eq!(
+            stream.next().unwrap().unwrap(),
+            (
+                69,
+                Entry {
+                    level: "error",
+                    count: 3
+                }
+            )
+        );
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(&source[err.location], "4");
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(&source[err.location], "}");
+        assert_eq!(
+            stream.next().unwrap().unwrap(),
+            (
+                108,
+                Entry {
+                    level: "debug",
+                    count: 5
+                }
+            )
+        );
+        assert!(stream.next().is_none());
+        assert_eq!(stream.byte_offset(), source.len());
+    }
+
+    #[test]
+    fn stream_syntax_error() {
+        let mut stream = StreamDeserializer::<Vec<u32>>::new("[1] [2,, 3] [4]", Config::default());
+        assert_eq!(stream.next().unwrap().unwrap(), (0, vec![1]));
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.location, 7..8);
+        assert_eq!(stream.next().unwrap().unwrap(), (12, vec![4]));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stream_from_reader() {
+        let values = StreamDeserializer::<Vec<u32>, _>::from_reader(
+            &b"[1, 2]\n[3]\n"[..],
+            Config::default(),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(values, [(0, vec![1, 2]), (7, vec![3])]);
+    }
+
+    #[test]
+    fn spanned() {
+        #[derive(Debug, Deserialize, Eq, PartialEq)]
+        enum Plugin {
+            Unit,
+            Tuple(Spanned<u32>, u32),
+            Struct { value: Spanned<bool> },
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Server {
+            name: Spanned<alloc::string::String>,
+            port: Spanned<u16>,
+            tls: Option<Spanned<Tls>>,
+            plugins: Vec<Spanned<Plugin>>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Tls {
+            #[allow(dead_code)]
+            cert: alloc::string::String,
+        }
+
+        let source = r#"Server {
+            name: "web",
+            port: 8080,
+            tls: Tls { cert: "a.pem" },
+            plugins: [Unit, Tuple(1, 2), Struct { value: true }],
+        }"#;
+        let server = crate::from_str::<Server>(source).unwrap();
+        assert_eq!(&source[server.name.span()], r#""web""#);
+        assert_eq!(server.name.get_ref(), "web");
+        assert_eq!(&source[server.port.span()], "8080");
+        assert_eq!(
+            &source[server.tls.unwrap().span()],
+            r#"Tls { cert: "a.pem" }"#
+        );
+        let spans = server
+            .plugins
+            .iter()
+            .map(|plugin| &source[plugin.span()])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, ["Unit", "Tuple(1, 2)", "Struct { value: true }"]);
+        let Plugin::Tuple(first, _) = server.plugins[1].get_ref() else {
+            unreachable!()
+        };
+        assert_eq!(&source[first.span()], "1");
+        let Plugin::Struct { value } = server.plugins[2].get_ref() else {
+            unreachable!()
+        };
+        assert_eq!(&source[value.span()], "true");
+    }
+
+    #[test]
+    fn raw_value() {
+        use alloc::string::String;
+
+        use serde::de::value::{Error as ValueError, StrDeserializer};
+
+        use crate::RawValue;
+
+        #[derive(Debug, Deserialize, Serialize)]
+        struct Host<'a> {
+            name: String,
+            #[serde(borrow)]
+            plugins: Vec<RawValue<'a>>,
+            fallback: Option<RawValue<'a>>,
+        }
+
+        let source = r#"Host {
+            name: "host",
+            plugins: [
+                Resize { width: 640, /* pixels */ height: 480 },
+                [1, "two", 3],
+                r"raw",
+            ],
+            fallback: Some(Blur(0.5)),
+        }"#;
+        let host = crate::from_str::<Host<'_>>(source).unwrap();
+        let plugins = host.plugins.iter().map(RawValue::get).collect::<Vec<_>>();
+        assert_eq!(
+            plugins,
+            [
+                "Resize { width: 640, /* pixels */ height: 480 }",
+                r#"[1, "two", 3]"#,
+                r#"r"raw""#,
+            ]
+        );
+        let source_range = source.as_bytes().as_ptr_range();
+        assert!(source_range.contains(&host.plugins[0].get().as_ptr()));
+        assert_eq!(host.fallback.as_ref().unwrap().get(), "Blur(0.5)");
+        assert_eq!(
+            host.plugins[1]
+                .deserialize_as::<(u8, String, u8)>()
+                .unwrap(),
+            (1, String::from("two"), 3)
+        );
+
+        // Serializing writes each raw value verbatim.
+        assert_eq!(
+            crate::to_string(&host).unwrap(),
+            r#"Host{name:"host",plugins:[Resize { width: 640, /* pixels */ height: 480 },[1, "two", 3],r"raw"],fallback:Some(Blur(0.5))}"#
+        );
+        let owned = host.plugins[1].clone().into_owned();
+        assert_eq!(crate::to_string(&owned).unwrap(), r#"[1, "two", 3]"#);
+
+        // Raw values can't be captured from a reader.
+        let mut de = super::Deserializer::from_reader(&b"[1, 2]"[..], Config::default());
+        let err = RawValue::deserialize(&mut de).unwrap_err();
+        assert_eq!(err.kind, super::ErrorKind::CannotBorrow);
+        assert_eq!(err.location, Some(0..6));
+
+        // Invalid Rsn isn't accepted or serialized.
+        assert_eq!(
+            RawValue::new("1 2").unwrap_err().kind,
+            crate::parser::ErrorKind::TrailingData
+        );
+        assert_eq!(
+            RawValue::new(" ").unwrap_err().kind,
+            crate::parser::ErrorKind::UnexpectedEof
+        );
+        let invalid = RawValue::deserialize(StrDeserializer::<ValueError>::new("[1,")).unwrap();
+        assert!(crate::to_string(&invalid).is_err());
+
+        // Comments after the value would comment out what is written after
+        // it, while comments within it are kept.
+        let err = RawValue::new("1 // c").unwrap_err();
+        assert_eq!(
+            (err.location, err.kind),
+            (2..6, crate::parser::ErrorKind::TrailingData)
+        );
+        let commented = RawValue::new("// a\n[1, // b\n2] ").unwrap();
+        let source = crate::to_string(&vec![commented.clone(), commented]).unwrap();
+        assert_eq!(
+            crate::from_str::<Vec<Vec<u8>>>(&source).unwrap(),
+            [[1, 2], [1, 2]]
+        );
+    }
+
+    #[test]
+    fn error_paths() {
+        use alloc::collections::BTreeMap;
+        use alloc::string::{String, ToString};
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        enum Plugin {
+            Tuple(u32, u32),
+            Struct { value: bool },
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Server {
+            tls: Option<Tls>,
+            plugins: Vec<Plugin>,
+            ports: BTreeMap<u16, String>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Tls {
+            cert: String,
+        }
+
+        #[track_caller]
+        fn assert_path(source: &str, expected: &str) {
+            let err = crate::from_str::<Vec<Server>>(source).unwrap_err();
+            assert_eq!(err.path.to_string(), expected, "{err}");
+        }
+
+        assert_path(
+            "[Server { tls: None, plugins: [], ports: {} }, \
+             { tls: Tls { cert: 1 }, plugins: [], ports: {} }]",
+            "[1].tls.cert",
+        );
+        assert_path(
+            "[{ tls: None, plugins: [Struct { value: true }, Tuple(1, false)], ports: {} }]",
+            "[0].plugins[1]::Tuple[1]",
+        );
+        assert_path(
+            "[{ tls: None, plugins: [Struct { value: \"x\" }], ports: {} }]",
+            "[0].plugins[0]::Struct.value",
+        );
+        assert_path(
+            "[{ tls: None, plugins: [], ports: { 80: \"http\", 443: 1 } }]",
+            "[0].ports[443]",
+        );
+        assert_path(
+            "[{ tls: None, plugins: [], ports: { 80: \"http\", \"b\": \"\" } }]",
+            "[0].ports.b",
+        );
+        assert_path("[{ tls: Tls {}, plugins: [], ports: {} }]", "[0].tls");
+        assert_path("[{ tls: None, plugins: [, ports: {} }]", "[0].plugins[0]");
+        // A duplicate field is reported after its key is read, without
+        // deserializing its value.
+        assert_path("[{ tls: None, tls: None, plugins: [], ports: {} }]", "[0]");
+
+        let err = crate::from_str::<BTreeMap<String, u32>>("{\"a b\": true}").unwrap_err();
+        assert_eq!(err.path.0, [super::PathSegment::Field(String::from("a b"))]);
+        assert_eq!(err.to_string(), "[\"a b\"]: expected integer at 8..12");
+
+        // The path is reset between values of a stream.
+        let mut stream =
+            StreamDeserializer::<Vec<u32>>::new("[1, true] [2] [\"a\"]", Config::default());
+        assert_eq!(stream.next().unwrap().unwrap_err().path.to_string(), "[1]");
+        assert_eq!(stream.next().unwrap().unwrap(), (10, vec![2]));
+        assert_eq!(stream.next().unwrap().unwrap_err().path.to_string(), "[0]");
+    }
+
+    #[test]
+    fn json() {
+        use alloc::string::String;
+
+        use crate::de::ErrorKind;
+        use crate::tokenizer::Dialect;
+        use crate::value::{List, OwnedValue, Value};
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Payload {
+            name: String,
+            tags: Vec<String>,
+            parent: Option<u32>,
+            nothing: (),
+            ratio: f32,
+            kind: Kind,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Kind {
+            Basic,
+        }
+
+        #[derive(Debug, Deserialize)]
+        enum Maybe {
+            None,
+        }
+
+        let config = Config::default().dialect(Dialect::Json);
+        let parsed = config
+            .deserialize::<Payload>(
+                "{\"name\": \"\\ud83e\\udd80\", \"tags\": [\"a\", \"b\"], \"parent\": null, \
+                 \"nothing\": null, \"ratio\": 0.5e1, \"kind\": \"Basic\"}",
+            )
+            .unwrap();
+        assert_eq!(
+            parsed,
+            Payload {
+                name: String::from("🦀"),
+                tags: vec![String::from("a"), String::from("b")],
+                parent: None,
+                nothing: (),
+                ratio: 5.,
+                kind: Kind::Basic,
+            }
+        );
+        assert_eq!(config.deserialize::<Option<u32>>(" 1 ").unwrap(), Some(1));
+
+        let source = "{\"parent\": None}";
+        let err = config.deserialize::<Payload>(source).unwrap_err();
+        assert_eq!(&source[err.location], "None");
+
+        // `null` isn't a string, identifier, or enum variant.
+        let err = config.deserialize::<String>("null").unwrap_err();
+        assert_eq!((err.location, err.kind), (0..4, ErrorKind::ExpectedString));
+        let err = config.deserialize::<Kind>("null").unwrap_err();
+        assert_eq!((err.location, err.kind), (0..4, ErrorKind::ExpectedEnum));
+        let err = config.deserialize::<Maybe>("null").unwrap_err();
+        assert_eq!((err.location, err.kind), (0..4, ErrorKind::ExpectedEnum));
+        assert_eq!(
+            config.deserialize::<OwnedValue>("[null]").unwrap(),
+            Value::Array(List(vec![Value::unit()]))
+        );
+        assert_eq!(Value::from_str("null", config).unwrap(), Value::unit());
+    }
+
+    #[test]
+    fn ron() {
+        use alloc::string::String;
+
+        use crate::tokenizer::Dialect;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Meters(f32);
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Marker;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Shape {
+            Dot(Point),
+            Line { from: Point, to: Point },
+            Empty,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Scene {
+            name: String,
+            width: Meters,
+            label: Option<String>,
+            shapes: Vec<Shape>,
+            markers: (Marker, Marker),
+        }
+
+        let config = Config::default().dialect(Dialect::Ron);
+        let parsed = config
+            .deserialize::<Scene>(
+                r#"#![enable(implicit_some, unwrap_newtypes, unwrap_variant_newtypes)]
+                // A scene.
+                Scene(
+                    name: "demo",
+                    width: 1.5f32,
+                    label: "a",
+                    shapes: [
+                        Dot(x: 1, y: 2),
+                        Line(from: (x: 0, y: 0), to: Point(x: 1u8, y: 1)),
+                        Empty,
+                    ],
+                    markers: (Marker, ()),
+                )"#,
+            )
+            .unwrap();
+        assert_eq!(
+            parsed,
+            Scene {
+                name: String::from("demo"),
+                width: Meters(1.5),
+                label: Some(String::from("a")),
+                shapes: vec![
+                    Shape::Dot(Point { x: 1, y: 2 }),
+                    Shape::Line {
+                        from: Point { x: 0, y: 0 },
+                        to: Point { x: 1, y: 1 }
+                    },
+                    Shape::Empty,
+                ],
+                markers: (Marker, Marker),
+            }
+        );
+
+        assert_eq!(
+            config.deserialize::<Meters>("Meters(2.)").unwrap(),
+            Meters(2.)
+        );
+        let source = "2. #![enable(unwrap_newtypes)]";
+        let err = config.deserialize::<f32>(source).unwrap_err();
+        assert_eq!(err.location, 3..4);
+        let source = "#![enable(implicit_some, unknown)] 2.";
+        let err = config.deserialize::<Meters>(source).unwrap_err();
+        assert_eq!(&source[err.location], "unknown");
+    }
+
+    #[test]
+    fn borrowed() {
+        use alloc::borrow::Cow;
+        use alloc::string::{String, ToString};
+
+        use super::ErrorKind;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Catalogue<'a> {
+            name: &'a str,
+            raw: &'a str,
+            bytes: &'a [u8],
+            raw_bytes: &'a [u8],
+            string_bytes: &'a [u8],
+            tags: Vec<&'a str>,
+            #[serde(borrow)]
+            description: Cow<'a, str>,
+        }
+
+        let source = r#"Catalogue {
+            name: "widget",
+            raw: r"C:\path",
+            bytes: b"abc",
+            raw_bytes: br"\n",
+            string_bytes: "text",
+            tags: ["a", r"b"],
+            description: "no escapes",
+        }"#;
+        let parsed = crate::from_str::<Catalogue<'_>>(source).unwrap();
+        assert_eq!(
+            parsed,
+            Catalogue {
+                name: "widget",
+                raw: r"C:\path",
+                bytes: b"abc",
+                raw_bytes: br"\n",
+                string_bytes: b"text",
+                tags: vec!["a", "b"],
+                description: Cow::Borrowed("no escapes"),
+            }
+        );
+        let source_range = source.as_bytes().as_ptr_range();
+        for borrowed in [parsed.name, parsed.raw, parsed.tags[0], &parsed.description] {
+            assert!(source_range.contains(&borrowed.as_ptr()));
+        }
+        for borrowed in [parsed.bytes, parsed.raw_bytes, parsed.string_bytes] {
+            assert!(source_range.contains(&borrowed.as_ptr()));
+        }
+
+        let raw = crate::from_str::<&str>("r#\"say \"hi\"\"#").unwrap();
+        assert_eq!(raw, r#"say "hi""#);
+
+        // `Cow` falls back to an owned value when the string has escapes.
+        let description = crate::from_str::<Cow<'_, str>>(r#""a\nb""#).unwrap();
+        assert!(matches!(description, Cow::Owned(_)));
+
+        // Borrowing a string with escapes is impossible.
+        let source = r#"["a", "b\"c"]"#;
+        let err = crate::from_str::<Vec<&str>>(source).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::CannotBorrow);
+        assert_eq!(&source[err.location], r#""b\"c""#);
+        assert_eq!(err.path.to_string(), "[1]");
+        let err = crate::from_str::<&[u8]>(r#"b"\x00""#).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::CannotBorrow);
+        let err = Config::default()
+            .dialect(crate::tokenizer::Dialect::Json)
+            .deserialize::<&str>(r#""\u0041""#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::CannotBorrow);
+
+        // Other type mismatches are still reported using Serde's message.
+        let err = <super::DeserializerError as serde::de::Error>::invalid_type(
+            serde::de::Unexpected::Str("a"),
+            &"a number",
+        );
+        assert_eq!(
+            err.kind,
+            ErrorKind::Message(String::from(
+                "invalid type: string \"a\", expected a number"
+            ))
+        );
+    }
 }
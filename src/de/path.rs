@@ -0,0 +1,127 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::parser::{EventKind, Primitive};
+use crate::tokenizer::Integer;
+
+/// The location of a value within the data being deserialized.
+///
+/// A path is displayed using a syntax similar to Rust's, for example
+/// `servers[2].tls.cert`.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// let error = rsn::from_str::<HashMap<String, Vec<u32>>>(r#"{ a: [1, 2, "3"] }"#).unwrap_err();
+/// assert_eq!(error.path.to_string(), "a[2]");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Path(pub Vec<PathSegment>);
+
+impl Path {
+    /// Returns true if this path refers to the root value.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if is_identifier(name) => {
+                    if index > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(name)?;
+                }
+                PathSegment::Field(name) => write!(f, "[{name:?}]")?,
+                PathSegment::Key(key) => write!(f, "[{key}]")?,
+                PathSegment::Index(element) => write!(f, "[{element}]")?,
+                PathSegment::Variant(name) => {
+                    if index > 0 {
+                        f.write_str("::")?;
+                    }
+                    f.write_str(name)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single step of a [`Path`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PathSegment {
+    /// A structure field, or a map key that is a string or identifier.
+    Field(String),
+    /// A map key that is not a string or identifier, written in Rsn syntax.
+    ///
+    /// Keys that are not primitive values are represented as `_`.
+    Key(String),
+    /// The index of an element in a sequence or tuple.
+    Index(usize),
+    /// The name of an enum variant.
+    Variant(String),
+}
+
+/// A [`PathSegment`] that borrows from the source until it is needed for an
+/// error.
+#[derive(Clone)]
+pub(crate) enum Segment<'de> {
+    Field(Cow<'de, str>),
+    Key(Option<Primitive<'de>>),
+    Index(usize),
+    Variant(Cow<'de, str>),
+}
+
+impl<'de> Segment<'de> {
+    /// Returns the segment for the map key that begins with `kind`.
+    pub(crate) fn key(kind: &EventKind<'de>) -> Self {
+        match kind {
+            EventKind::Primitive(Primitive::Identifier(name) | Primitive::String(name)) => {
+                Segment::Field(name.clone())
+            }
+            EventKind::Primitive(primitive) => Segment::Key(Some(primitive.clone())),
+            _ => Segment::Key(None),
+        }
+    }
+
+    pub(crate) fn to_path_segment(&self) -> PathSegment {
+        match self {
+            Segment::Field(name) => PathSegment::Field(name.to_string()),
+            Segment::Key(key) => PathSegment::Key(key.as_ref().map_or_else(
+                || String::from("_"),
+                |key| match key {
+                    Primitive::Bool(value) => value.to_string(),
+                    Primitive::Integer(Integer::Usize(value)) => value.to_string(),
+                    Primitive::Integer(Integer::Isize(value)) => value.to_string(),
+                    Primitive::Integer(Integer::UnsignedLarge(value)) => value.to_string(),
+                    Primitive::Integer(Integer::SignedLarge(value)) => value.to_string(),
+                    Primitive::Float(value) => value.to_string(),
+                    Primitive::Char(value) => format!("{value:?}"),
+                    Primitive::String(value) | Primitive::Identifier(value) => {
+                        format!("{value:?}")
+                    }
+                    Primitive::Bytes(value) => format!("b\"{}\"", value.escape_ascii()),
+                },
+            )),
+            Segment::Index(index) => PathSegment::Index(*index),
+            Segment::Variant(name) => PathSegment::Variant(name.to_string()),
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |ch| ch == '_' || is_xid_start(ch))
+        && chars.all(is_xid_continue)
+}
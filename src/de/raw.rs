@@ -0,0 +1,189 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::Visitor;
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{self, Config, EventKind, Parser};
+
+/// The newtype struct name [`Deserializer`](crate::de::Deserializer) and
+/// [`Serializer`](crate::ser::Serializer) recognize as a [`RawValue`].
+pub(crate) const NAME: &str = "$__rsn_private_RawValue";
+
+/// An unparsed fragment of Rsn containing exactly one value.
+///
+/// When deserialized, a `RawValue` captures the exact source of the value
+/// without interpreting it. When serialized, the source is written verbatim
+/// after checking that it is valid Rsn. This allows passing a value through
+/// without knowing its type.
+///
+/// ```rust
+/// use rsn::RawValue;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Plugin<'a> {
+///     name: String,
+///     #[serde(borrow)]
+///     config: RawValue<'a>,
+/// }
+///
+/// let source = r#"Plugin{name:"resize",config:Resize { width: 640 }}"#;
+/// let plugin: Plugin<'_> = rsn::from_str(source).unwrap();
+/// assert_eq!(plugin.config.get(), "Resize { width: 640 }");
+/// assert_eq!(rsn::to_string(&plugin).unwrap(), source);
+/// ```
+///
+/// The source of a `RawValue` is borrowed when deserialized with this
+/// crate's [`Deserializer`](crate::de::Deserializer). It can only be
+/// deserialized from a borrowed source, not from a reader. Use
+/// [`RawValue::into_owned`] to store it beyond the lifetime of its source.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RawValue<'a>(Cow<'a, str>);
+
+impl<'a> RawValue<'a> {
+    /// Returns a raw value containing `source`, after checking that it
+    /// contains exactly one valid Rsn value.
+    ///
+    /// ```rust
+    /// use rsn::RawValue;
+    ///
+    /// assert_eq!(RawValue::new("[1, 2]").unwrap().get(), "[1, 2]");
+    /// assert!(RawValue::new("1 2").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` is not a single valid Rsn value, or if a
+    /// comment follows the value.
+    pub fn new(source: impl Into<Cow<'a, str>>) -> Result<Self, parser::Error> {
+        let source = source.into();
+        validate(&source)?;
+        Ok(Self(source))
+    }
+
+    /// Returns the source of this value.
+    #[must_use]
+    pub fn get(&self) -> &str {
+        &self.0
+    }
+
+    /// Deserializes this value as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this value cannot be deserialized as `T`.
+    pub fn deserialize_as<'de, T>(&'de self) -> Result<T, crate::de::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        crate::from_str(&self.0)
+    }
+
+    /// Returns an owned representation of this value, copying the source to
+    /// the heap if needed.
+    #[must_use]
+    pub fn into_owned(self) -> RawValue<'static> {
+        RawValue(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl fmt::Display for RawValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for RawValue<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Checks that `source` contains exactly one valid Rsn value.
+///
+/// Comments after the value are rejected, as a line comment would comment
+/// out anything written after the raw value.
+pub(crate) fn validate(source: &str) -> Result<(), parser::Error> {
+    let mut parser = Parser::new(source, Config::default().include_comments(true));
+    let mut depth = 0_usize;
+    let mut has_value = false;
+    for event in &mut parser {
+        let event = event?;
+        match event.kind {
+            EventKind::Comment(_) if has_value => {
+                return Err(parser::Error::new(
+                    event.location,
+                    parser::ErrorKind::TrailingData,
+                ));
+            }
+            EventKind::Comment(_) => {}
+            EventKind::BeginNested { .. } => depth += 1,
+            EventKind::EndNested => {
+                depth -= 1;
+                has_value = depth == 0;
+            }
+            _ => has_value = depth == 0,
+        }
+    }
+    if has_value {
+        Ok(())
+    } else {
+        let end = parser.current_offset();
+        Err(parser::Error::new(
+            end..end,
+            parser::ErrorKind::UnexpectedEof,
+        ))
+    }
+}
+
+impl Serialize for RawValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(NAME, &*self.0)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawValue<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(NAME, RawValueVisitor(PhantomData))
+    }
+}
+
+struct RawValueVisitor<'a>(PhantomData<&'a ()>);
+
+impl<'de: 'a, 'a> Visitor<'de> for RawValueVisitor<'a> {
+    type Value = RawValue<'a>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value deserialized by rsn")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RawValue(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RawValue(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RawValue(Cow::Owned(v)))
+    }
+}
@@ -0,0 +1,259 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::de::{Deserializer, DeserializerError};
+use crate::tokenizer::TokenSource;
+
+/// The struct name [`Deserializer`] recognizes as a request for a [`Spanned`]
+/// value.
+pub(crate) const NAME: &str = "$__rsn_private_Spanned";
+const START: &str = "$__rsn_private_start";
+const END: &str = "$__rsn_private_end";
+const VALUE: &str = "$__rsn_private_value";
+const FIELDS: &[&str] = &[START, END, VALUE];
+
+/// A deserialized value along with the byte range of the source it was
+/// deserialized from.
+///
+/// This allows reporting the location of values that parse successfully but
+/// are rejected by later validation.
+///
+/// ```rust
+/// use rsn::Spanned;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Server {
+///     port: Spanned<u16>,
+/// }
+///
+/// let source = "Server { port: 80 }";
+/// let server: Server = rsn::from_str(source).unwrap();
+/// assert_eq!(*server.port.get_ref(), 80);
+/// assert_eq!(&source[server.port.span()], "80");
+/// ```
+///
+/// The span of a named structure or enum variant includes its name. `Spanned`
+/// can only be deserialized using this crate's
+/// [`Deserializer`](crate::de::Deserializer). When serialized, only the value
+/// is serialized.
+///
+/// Comparisons and hashing only consider the value, not the span.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    span: Range<usize>,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// Returns a new instance wrapping `value` that was deserialized from the
+    /// byte range `span`.
+    #[must_use]
+    pub const fn new(span: Range<usize>, value: T) -> Self {
+        Self { span, value }
+    }
+
+    /// Returns the byte range of the source this value was deserialized from.
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the byte offset of the start of this value.
+    #[must_use]
+    pub const fn start(&self) -> usize {
+        self.span.start
+    }
+
+    /// Returns the byte offset of the end of this value.
+    #[must_use]
+    pub const fn end(&self) -> usize {
+        self.span.end
+    }
+
+    /// Returns a reference to the contained value.
+    #[must_use]
+    pub const fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns an exclusive reference to the contained value.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Returns the contained value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> PartialEq for Spanned<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for Spanned<T> where T: Eq {}
+
+impl<T> PartialOrd for Spanned<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T> Ord for Spanned<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T> Hash for Spanned<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> Serialize for Spanned<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Spanned<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, FIELDS, SpannedVisitor(PhantomData))
+    }
+}
+
+struct SpannedVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for SpannedVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Spanned<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value deserialized by rsn")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        if map.next_key::<&str>()? != Some(START) {
+            return Err(serde::de::Error::custom("spanned start key not found"));
+        }
+        let start: usize = map.next_value()?;
+        if map.next_key::<&str>()? != Some(VALUE) {
+            return Err(serde::de::Error::custom("spanned value key not found"));
+        }
+        let value: T = map.next_value()?;
+        if map.next_key::<&str>()? != Some(END) {
+            return Err(serde::de::Error::custom("spanned end key not found"));
+        }
+        let end: usize = map.next_value()?;
+
+        Ok(Spanned::new(start..end, value))
+    }
+}
+
+/// Provides the fields of a [`Spanned`] value to its visitor.
+pub(crate) struct SpannedAccess<'a, 'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
+    de: &'a mut Deserializer<'de, Tokens>,
+    start: usize,
+    next_field: Option<&'static str>,
+}
+
+impl<'a, 'de, Tokens> SpannedAccess<'a, 'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
+    pub(crate) fn new(de: &'a mut Deserializer<'de, Tokens>) -> Result<Self, DeserializerError> {
+        let start = de.peek_value_start()?;
+        Ok(Self {
+            de,
+            start,
+            next_field: Some(START),
+        })
+    }
+}
+
+impl<'de, Tokens> MapAccess<'de> for SpannedAccess<'_, 'de, Tokens>
+where
+    Tokens: TokenSource<'de>,
+{
+    type Error = DeserializerError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.next_field {
+            Some(field) => seed
+                .deserialize(BorrowedStrDeserializer::new(field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.next_field {
+            Some(START) => {
+                self.next_field = Some(VALUE);
+                seed.deserialize(serde::de::value::UsizeDeserializer::new(self.start))
+            }
+            Some(VALUE) => {
+                self.next_field = Some(END);
+                seed.deserialize(&mut *self.de)
+            }
+            Some(END) => {
+                self.next_field = None;
+                seed.deserialize(serde::de::value::UsizeDeserializer::new(
+                    self.de.parser.last_end,
+                ))
+            }
+            _ => unreachable!("next_value_seed called without a key"),
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+use core::ops::Range;
+
+use crate::{parser, tokenizer};
+
+/// Converts byte offsets within a source into line and column positions.
+///
+/// Lines may be terminated by either `\n` or `\r\n`. Columns are measured in
+/// characters, which means multi-byte UTF-8 sequences only advance the column
+/// by one.
+///
+/// ```rust
+/// use rsn::diagnostics::{Position, SourceMap};
+///
+/// let source = "{\r\n    a: \"🦀\",\r\n    b }";
+/// let map = SourceMap::new(source);
+/// let offset = source.find('}').unwrap();
+/// assert_eq!(map.position(offset), Position { line: 3, column: 7 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Returns a new source map for `source`.
+    #[must_use]
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(
+            source
+                .bytes()
+                .enumerate()
+                .filter(|(_, byte)| *byte == b'\n')
+                .map(|(index, _)| index + 1),
+        );
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Returns the source this map was created from.
+    #[must_use]
+    pub const fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the number of lines in the source.
+    ///
+    /// An empty source contains one empty line.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the line and column of `offset`.
+    ///
+    /// Offsets beyond the end of the source are treated as the end of the
+    /// source. Offsets that are in the middle of a UTF-8 sequence are treated
+    /// as the start of that sequence.
+    #[must_use]
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = self.clamp(offset);
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let before = &self.source[self.line_starts[line_index]..offset];
+        Position {
+            line: line_index + 1,
+            column: before.trim_end_matches('\r').chars().count() + 1,
+        }
+    }
+
+    /// Returns the contents of `line`, excluding its line ending. Lines are
+    /// numbered starting at 1.
+    #[must_use]
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        let range = self.line_range(line.checked_sub(1)?)?;
+        Some(&self.source[range])
+    }
+
+    /// Returns a renderable snippet of the source that `error` refers to.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use rsn::diagnostics::SourceMap;
+    ///
+    /// let source = "{\n    a 1\n}";
+    /// let error = rsn::from_str::<HashMap<String, u32>>(source).unwrap_err();
+    /// let map = SourceMap::new(source);
+    /// assert_eq!(
+    ///     map.snippet(&error).to_string(),
+    ///     "error: expected `:`\n --> 2:7\n  |\n2 |     a 1\n  |       ^\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn snippet<'e>(&'e self, error: &'e impl Diagnostic) -> Snippet<'e> {
+        Snippet {
+            map: self,
+            location: error.location(),
+            message: error.message(),
+            origin: None,
+            ansi: false,
+        }
+    }
+
+    fn clamp(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    /// Returns the byte range of the line at the zero-based `index`, excluding
+    /// the line ending.
+    fn line_range(&self, index: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(index)?;
+        let end = self
+            .line_starts
+            .get(index + 1)
+            .map_or(self.source.len(), |next| next - 1);
+        let line = &self.source[start..end];
+        Some(start..start + line.trim_end_matches('\r').len())
+    }
+}
+
+/// A line and column within a source. Both values start at 1.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Position {
+    /// The line number.
+    pub line: usize,
+    /// The column number, measured in characters.
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// An error that refers to a range of the source it was produced from.
+pub trait Diagnostic {
+    /// Returns the byte range of the source this error refers to.
+    fn location(&self) -> Range<usize>;
+
+    /// Returns the message describing this error.
+    fn message(&self) -> &dyn Display;
+}
+
+impl Diagnostic for tokenizer::Error {
+    fn location(&self) -> Range<usize> {
+        self.location.clone()
+    }
+
+    fn message(&self) -> &dyn Display {
+        &self.kind
+    }
+}
+
+impl Diagnostic for parser::Error {
+    fn location(&self) -> Range<usize> {
+        self.location.clone()
+    }
+
+    fn message(&self) -> &dyn Display {
+        &self.kind
+    }
+}
+
+impl Diagnostic for crate::value::JsonError {
+    fn location(&self) -> Range<usize> {
+        self.location.clone()
+    }
+
+    fn message(&self) -> &dyn Display {
+        &self.kind
+    }
+}
+
+impl Diagnostic for crate::value::QueryError {
+    fn location(&self) -> Range<usize> {
+        self.location.clone()
+    }
+
+    fn message(&self) -> &dyn Display {
+        &self.kind
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Diagnostic for crate::de::Error {
+    fn location(&self) -> Range<usize> {
+        self.location.clone()
+    }
+
+    fn message(&self) -> &dyn Display {
+        &self.kind
+    }
+}
+
+/// The source lines an error refers to, rendered with the error's message and
+/// the offending range underlined.
+///
+/// Returned from [`SourceMap::snippet`]. The snippet is rendered using its
+/// [`Display`] implementation.
+#[derive(Clone)]
+pub struct Snippet<'a> {
+    map: &'a SourceMap<'a>,
+    location: Range<usize>,
+    message: &'a dyn Display,
+    origin: Option<&'a str>,
+    ansi: bool,
+}
+
+impl<'a> Snippet<'a> {
+    /// Sets the name of the source, such as its file path, and returns self.
+    /// The name is shown before the line and column of the error.
+    #[must_use]
+    pub const fn origin(mut self, origin: &'a str) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Sets whether ANSI escape codes are used to color the rendered snippet,
+    /// and returns self.
+    #[must_use]
+    pub const fn ansi(mut self, enabled: bool) -> Self {
+        self.ansi = enabled;
+        self
+    }
+
+    fn style<'s>(&self, code: &'s str) -> &'s str {
+        if self.ansi {
+            code
+        } else {
+            ""
+        }
+    }
+}
+
+const ERROR_STYLE: &str = "\x1b[1;31m";
+const GUTTER_STYLE: &str = "\x1b[1;34m";
+const MESSAGE_STYLE: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+impl Display for Snippet<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let start = self.map.clamp(self.location.start);
+        let end = self.map.clamp(self.location.end).max(start);
+        let first = self.map.position(start);
+        let last = self.map.position(end);
+        let error = self.style(ERROR_STYLE);
+        let gutter = self.style(GUTTER_STYLE);
+        let message = self.style(MESSAGE_STYLE);
+        let reset = self.style(RESET);
+        let width = count_digits(last.line);
+
+        writeln!(f, "{error}error{reset}{message}: {}{reset}", self.message)?;
+        write!(f, "{:width$}{gutter}-->{reset} ", "")?;
+        if let Some(origin) = self.origin {
+            write!(f, "{origin}:")?;
+        }
+        writeln!(f, "{first}")?;
+        writeln!(f, "{:width$} {gutter}|{reset}", "")?;
+        for line in first.line..=last.line {
+            let range = self
+                .map
+                .line_range(line - 1)
+                .expect("position returned a valid line");
+            let text = &self.map.source[range.clone()];
+            // Offsets within the line ending are moved to the end of the line.
+            let underline_start = (start.max(range.start) - range.start).min(text.len());
+            let underline_end = (end.min(range.end).max(start) - range.start).min(text.len());
+            // Lines after the first that the range only touches at their
+            // start aren't shown.
+            if line > first.line && underline_end == 0 {
+                continue;
+            }
+
+            writeln!(f, "{gutter}{line:>width$} |{reset} {text}")?;
+            write!(f, "{:width$} {gutter}|{reset} ", "")?;
+            // Preserve tabs so that the underline lines up with the source.
+            for ch in text[..underline_start].chars() {
+                f.write_char(if ch == '\t' { '\t' } else { ' ' })?;
+            }
+            let carets = text[underline_start..underline_end].chars().count().max(1);
+            f.write_str(error)?;
+            for _ in 0..carets {
+                f.write_char('^')?;
+            }
+            writeln!(f, "{reset}")?;
+        }
+        Ok(())
+    }
+}
+
+fn count_digits(mut value: usize) -> usize {
+    let mut digits = 1;
+    while value >= 10 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let source = "ab\r\nc🦀d\n\nend";
+        let map = SourceMap::new(source);
+        assert_eq!(map.line_count(), 4);
+        assert_eq!(map.position(0), Position { line: 1, column: 1 });
+        assert_eq!(map.position(2), Position { line: 1, column: 3 });
+        assert_eq!(map.position(3), Position { line: 1, column: 3 });
+        assert_eq!(map.position(4), Position { line: 2, column: 1 });
+        // Offsets inside of the crab are moved to its start.
+        assert_eq!(map.position(6), Position { line: 2, column: 2 });
+        assert_eq!(map.position(9), Position { line: 2, column: 3 });
+        assert_eq!(map.position(11), Position { line: 3, column: 1 });
+        assert_eq!(map.position(12), Position { line: 4, column: 1 });
+        assert_eq!(map.position(100), Position { line: 4, column: 4 });
+        assert_eq!(map.line(1), Some("ab"));
+        assert_eq!(map.line(2), Some("c🦀d"));
+        assert_eq!(map.line(3), Some(""));
+        assert_eq!(map.line(4), Some("end"));
+        assert_eq!(map.line(0), None);
+        assert_eq!(map.line(5), None);
+    }
+
+    #[test]
+    fn render_plain() {
+        let source = "[\n\t\"🦀\", 1 2,\n]";
+        let map = SourceMap::new(source);
+        let error = parser::Parser::new(source, parser::Config::default())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            map.snippet(&error).to_string(),
+            "error: expected `,` or `]`\n --> 2:9\n  |\n2 | \t\"🦀\", 1 2,\n  | \t       ^\n"
+        );
+    }
+
+    #[test]
+    fn render_multiline() {
+        let source = "a\r\nbcd\r\nef";
+        let map = SourceMap::new(source);
+        let error = parser::Error::new(1..9, parser::ErrorKind::ExpectedValue);
+        assert_eq!(
+            map.snippet(&error).to_string(),
+            "error: a value was expected\n --> 1:2\n  |\n1 | a\n  |  ^\n2 | bcd\n  | ^^^\n3 | ef\n  | ^\n"
+        );
+    }
+
+    #[test]
+    fn render_crlf() {
+        let source = "a\r\nb";
+        let map = SourceMap::new(source);
+        let error = parser::Error::new(2..3, parser::ErrorKind::ExpectedValue);
+        assert_eq!(
+            map.snippet(&error).to_string(),
+            "error: a value was expected\n --> 1:2\n  |\n1 | a\n  |  ^\n"
+        );
+        let error = parser::Error::new(1..4, parser::ErrorKind::ExpectedValue);
+        assert_eq!(
+            map.snippet(&error).to_string(),
+            "error: a value was expected\n --> 1:2\n  |\n1 | a\n  |  ^\n2 | b\n  | ^\n"
+        );
+    }
+
+    #[test]
+    fn render_eof() {
+        let source = "[1, 2";
+        let map = SourceMap::new(source);
+        let error = parser::Parser::new(source, parser::Config::default())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            map.snippet(&error).to_string(),
+            "error: expected `,` or `]`\n --> 1:6\n  |\n1 | [1, 2\n  |      ^\n"
+        );
+        assert_eq!(
+            map.snippet(&error).origin("list.rsn").to_string(),
+            "error: expected `,` or `]`\n --> list.rsn:1:6\n  |\n1 | [1, 2\n  |      ^\n"
+        );
+    }
+
+    #[test]
+    fn render_ansi() {
+        let source = "@";
+        let map = SourceMap::new(source);
+        let error = tokenizer::Tokenizer::minified(source)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            map.snippet(&error).ansi(true).to_string(),
+            "\x1b[1;31merror\x1b[0m\x1b[1m: unexpected `@`\x1b[0m\n \x1b[1;34m-->\x1b[0m 1:1\n  \x1b[1;34m|\x1b[0m\n\x1b[1;34m1 |\x1b[0m @\n  \x1b[1;34m|\x1b[0m \x1b[1;31m^\x1b[0m\n"
+        );
+    }
+}
@@ -0,0 +1,978 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+use core::mem;
+use core::str::FromStr;
+
+use crate::parser::{self, Config, Parser};
+use crate::tokenizer::{Balanced, Dialect, Token, TokenKind, Tokenizer};
+use crate::value::{self, OwnedValue};
+use crate::writer;
+
+mod format;
+
+/// An Rsn document that can be edited while preserving its formatting.
+///
+/// A document keeps all whitespace and comments from the source it was parsed
+/// from. Displaying a document produces output that is identical to its
+/// source, except for the values that have been changed.
+///
+/// ```rust
+/// use rsn::document::Document;
+///
+/// let mut document: Document = "Config {
+///     // The address to listen on.
+///     address: \"0.0.0.0\",
+///     port: 80, // Privileged
+/// }"
+/// .parse()
+/// .unwrap();
+/// let config = document.root_mut().as_nested_mut().unwrap();
+/// config.insert("port", "8080".parse().unwrap());
+/// config.insert("tls", "true".parse().unwrap());
+/// config.remove("address");
+///
+/// assert_eq!(
+///     document.to_string(),
+///     "Config {
+///     port: 8080, // Privileged
+///     tls: true,
+/// }"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    prefix: String,
+    root: Value,
+    suffix: String,
+}
+
+impl Document {
+    /// Parses `source` as a document using `config`.
+    ///
+    /// `Config::include_comments` and `Config::recover_from_errors` are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`parser::ErrorKind::UnsupportedDialect`] if `Config::dialect`
+    /// is not [`Dialect::Rsn`]. Otherwise, returns the first error encountered
+    /// while parsing `source`.
+    pub fn parse(source: &str, config: Config) -> Result<Self, parser::Error> {
+        // The builder preserves the source's formatting using Rsn's tokens.
+        if config.dialect != Dialect::Rsn {
+            return Err(parser::Error::new(
+                0..0,
+                parser::ErrorKind::UnsupportedDialect,
+            ));
+        }
+        if let Some(err) = Parser::new(
+            source,
+            config.include_comments(false).recover_from_errors(false),
+        )
+        .find_map(Result::err)
+        {
+            return Err(err);
+        }
+
+        let mut builder = Builder::new(source);
+        let prefix = builder.trivia();
+        let root = if config.allow_implicit_map_at_root && builder.starts_implicit_map() {
+            Value::Nested(builder.contents(None, String::new(), parser::Nested::Map, true))
+        } else {
+            builder.value()
+        };
+        // An implicit map can be ended by a stray `}`, which is kept with the
+        // trailing whitespace and comments.
+        let suffix = builder.source[builder.offset()..].to_string();
+        Ok(Self {
+            prefix,
+            root,
+            suffix,
+        })
+    }
+
+    /// Returns the root value of this document.
+    #[must_use]
+    pub const fn root(&self) -> &Value {
+        &self.root
+    }
+
+    /// Returns an exclusive reference to the root value of this document.
+    #[must_use]
+    pub fn root_mut(&mut self) -> &mut Value {
+        &mut self.root
+    }
+
+    /// Returns this document formatted using `config`, keeping all comments.
+    ///
+    /// See [`format`](crate::format) for more information.
+    #[must_use]
+    pub fn format(&self, config: &writer::Config) -> String {
+        format::format(self, config)
+    }
+}
+
+impl FromStr for Document {
+    type Err = parser::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Config::default())
+    }
+}
+
+impl Display for Document {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.prefix)?;
+        Display::fmt(&self.root, f)?;
+        f.write_str(&self.suffix)
+    }
+}
+
+/// A value within a [`Document`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A single literal or identifier.
+    Primitive(Primitive),
+    /// A tuple, list, map, or named structure.
+    Nested(Nested),
+}
+
+impl Value {
+    /// Parses `source` as a value.
+    ///
+    /// The formatting of `source` is preserved, except for any whitespace or
+    /// comments before or after the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered while parsing `source`.
+    pub fn parse(source: &str, config: Config) -> Result<Self, parser::Error> {
+        Document::parse(source, config).map(|document| document.root)
+    }
+
+    /// Returns this value as a [`Primitive`], if it is one.
+    #[must_use]
+    pub const fn as_primitive(&self) -> Option<&Primitive> {
+        match self {
+            Value::Primitive(primitive) => Some(primitive),
+            Value::Nested(_) => None,
+        }
+    }
+
+    /// Returns this value as a [`Nested`], if it is one.
+    #[must_use]
+    pub const fn as_nested(&self) -> Option<&Nested> {
+        match self {
+            Value::Nested(nested) => Some(nested),
+            Value::Primitive(_) => None,
+        }
+    }
+
+    /// Returns this value as an exclusive reference to a [`Nested`], if it is
+    /// one.
+    #[must_use]
+    pub fn as_nested_mut(&mut self) -> Option<&mut Nested> {
+        match self {
+            Value::Nested(nested) => Some(nested),
+            Value::Primitive(_) => None,
+        }
+    }
+
+    /// Returns the value of the entry whose key is `key`, if this value is a
+    /// map that contains it.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_nested()?.get(key)
+    }
+
+    /// Returns an exclusive reference to the value of the entry whose key is
+    /// `key`, if this value is a map that contains it.
+    #[must_use]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_nested_mut()?.get_mut(key)
+    }
+
+    /// Returns the contents of this value, discarding its formatting.
+    ///
+    /// The value is parsed without [`Config::max_depth`]'s limit, as a
+    /// document may be nested more deeply than the default allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this value can't be parsed as a
+    /// [`value::Value`].
+    pub fn to_value(&self) -> Result<OwnedValue, parser::Error> {
+        match self {
+            Value::Nested(nested) if nested.implicit && nested.entries.is_empty() => {
+                Ok(OwnedValue::Map(value::Map::default()))
+            }
+            _ => {
+                let implicit = matches!(self, Value::Nested(nested) if nested.implicit);
+                let source = self.to_string();
+                value::Value::from_str(
+                    &source,
+                    Config::default()
+                        .allow_implicit_map_at_root(implicit)
+                        .max_depth(None),
+                )
+                .map(value::Value::into_owned)
+            }
+        }
+    }
+
+    /// Returns the string this value represents if it is a string or an
+    /// identifier.
+    fn as_key(&self) -> Option<Cow<'_, str>> {
+        match Tokenizer::minified(&self.as_primitive()?.raw).next() {
+            Some(Ok(Token {
+                kind: TokenKind::Identifier(key) | TokenKind::String(key),
+                ..
+            })) => Some(key),
+            _ => None,
+        }
+    }
+
+    fn is_identifier(&self) -> bool {
+        self.as_primitive()
+            .map_or(false, |primitive| is_identifier(&primitive.raw))
+    }
+}
+
+impl FromStr for Value {
+    type Err = parser::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Config::default())
+    }
+}
+
+impl TryFrom<value::Value<'_>> for Value {
+    type Error = parser::Error;
+
+    /// Parses the Rsn representation of `value`, without
+    /// [`Config::max_depth`]'s limit.
+    fn try_from(value: value::Value<'_>) -> Result<Self, Self::Error> {
+        Self::parse(&value.to_string(), Config::default().max_depth(None))
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Primitive(primitive) => f.write_str(&primitive.raw),
+            Value::Nested(nested) => Display::fmt(nested, f),
+        }
+    }
+}
+
+/// A literal or identifier within a [`Document`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Primitive {
+    raw: String,
+}
+
+impl Primitive {
+    /// Returns the source of this primitive as it appears in the document.
+    #[must_use]
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// A tuple, list, map, or named structure within a [`Document`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nested {
+    name: Option<String>,
+    after_name: String,
+    kind: parser::Nested,
+    /// True for a map at the root of a document that has no braces.
+    implicit: bool,
+    entries: Vec<Entry>,
+    /// The whitespace and comments between the last entry and the closing
+    /// delimiter.
+    trailing: String,
+}
+
+impl Nested {
+    /// Returns the name of this structure, if it is named.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the kind of this nested value.
+    #[must_use]
+    pub const fn kind(&self) -> parser::Nested {
+        self.kind
+    }
+
+    /// Returns the number of entries this value contains.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this value contains no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries of this value.
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the entries of this value as exclusive references.
+    #[must_use]
+    pub fn entries_mut(&mut self) -> &mut [Entry] {
+        &mut self.entries
+    }
+
+    /// Returns the value of the entry whose key is `key`.
+    ///
+    /// Keys match if they are a string or identifier equal to `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let index = self.position(key)?;
+        Some(&self.entries[index].value)
+    }
+
+    /// Returns an exclusive reference to the value of the entry whose key is
+    /// `key`.
+    #[must_use]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        let index = self.position(key)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    /// Returns the value of the entry at `index`.
+    #[must_use]
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.entries.get(index).map(|entry| &entry.value)
+    }
+
+    /// Returns an exclusive reference to the value of the entry at `index`.
+    #[must_use]
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.entries.get_mut(index).map(|entry| &mut entry.value)
+    }
+
+    /// Sets the value of the entry whose key is `key` to `value`. If no entry
+    /// matches, a new entry is appended that is formatted similarly to the
+    /// existing entries.
+    ///
+    /// Returns the previous value, if an entry already existed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this value is not a map.
+    pub fn insert(&mut self, key: &str, value: Value) -> Option<Value> {
+        assert_eq!(self.kind, parser::Nested::Map, "insert requires a map");
+        if let Some(index) = self.position(key) {
+            return Some(mem::replace(&mut self.entries[index].value, value));
+        }
+
+        let (before_colon, after_colon) = self
+            .entries
+            .last()
+            .and_then(|entry| entry.key.as_ref())
+            .map_or((String::new(), String::from(" ")), |key| {
+                (
+                    whitespace_or(&key.before_colon, ""),
+                    whitespace_or(&key.after_colon, " "),
+                )
+            });
+        let key = Key {
+            value: self.new_key(key),
+            before_colon,
+            after_colon,
+        };
+        self.push_entry(Some(key), value);
+        None
+    }
+
+    /// Removes the entry whose key is `key`, returning its value.
+    ///
+    /// Any comments before the entry or after its comma on the same line are
+    /// removed with it.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.position(key)?;
+        self.remove_index(index)
+    }
+
+    /// Changes the key of the entry whose key is `from` to `to`. The key is
+    /// written as an identifier if the existing key was an identifier and `to`
+    /// is a valid identifier. Otherwise, the key is written as a string.
+    ///
+    /// Returns false if no entry has the key `from`, or if another entry
+    /// already has the key `to`.
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        let Some(index) = self.position(from) else {
+            return false;
+        };
+        if from != to && self.position(to).is_some() {
+            return false;
+        }
+
+        if let Some(key) = &mut self.entries[index].key {
+            key.value = Value::Primitive(Primitive {
+                raw: if key.value.is_identifier() && is_identifier(to) {
+                    to.to_string()
+                } else {
+                    value::Value::String(Cow::Borrowed(to)).to_string()
+                },
+            });
+        }
+        true
+    }
+
+    /// Appends `value` to this sequence, formatted similarly to the existing
+    /// entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this value is a map.
+    pub fn push(&mut self, value: Value) {
+        assert_ne!(self.kind, parser::Nested::Map, "push requires a sequence");
+        self.push_entry(None, value);
+    }
+
+    /// Removes the entry at `index`, returning its value.
+    ///
+    /// Any comments before the entry or after its comma on the same line are
+    /// removed with it.
+    pub fn remove_index(&mut self, index: usize) -> Option<Value> {
+        if index >= self.entries.len() {
+            return None;
+        }
+
+        let removed = self.entries.remove(index);
+        if index == self.entries.len() {
+            // Keep the trailing comma style when removing the last entry.
+            if let Some(last) = self.entries.last_mut() {
+                if last.comma && !removed.comma {
+                    last.comma = false;
+                    let separator = mem::take(&mut last.before_comma) + &last.after_comma;
+                    last.after_comma.clear();
+                    self.trailing.insert_str(0, &separator);
+                }
+            }
+        } else if index == 0 && !self.entries[0].leading.contains('\n') {
+            // The following entry takes the place of the removed entry on the
+            // same line.
+            let spacing = removed.leading.len()
+                - removed
+                    .leading
+                    .trim_start_matches(|ch: char| ch.is_whitespace())
+                    .len();
+            self.entries[0].leading = removed.leading[..spacing].to_string();
+        }
+        Some(removed.value)
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            entry
+                .key
+                .as_ref()
+                .and_then(|existing| existing.value.as_key())
+                .map_or(false, |existing| existing == key)
+        })
+    }
+
+    fn new_key(&self, key: &str) -> Value {
+        let identifier_keys = match self.entries.last().and_then(|entry| entry.key.as_ref()) {
+            Some(last) => last.value.is_identifier(),
+            None => self.name.is_some() || self.implicit,
+        };
+        let raw = if identifier_keys && is_identifier(key) {
+            key.to_string()
+        } else {
+            value::Value::String(Cow::Borrowed(key)).to_string()
+        };
+        Value::Primitive(Primitive { raw })
+    }
+
+    fn push_entry(&mut self, key: Option<Key>, value: Value) {
+        let leading = match self.entries.last() {
+            Some(last) if last.leading.contains('\n') => {
+                let line = &last.leading[last.leading.rfind('\n').expect("contains newline")..];
+                let indented = line.len()
+                    - line[1..]
+                        .trim_start_matches(|ch: char| ch.is_whitespace())
+                        .len();
+                line[..indented].to_string()
+            }
+            Some(_) => String::from(" "),
+            None if self.implicit => String::new(),
+            None => {
+                if let Some(newline) = self.trailing.rfind('\n') {
+                    let indentation = &self.trailing[newline..];
+                    let mut leading = indentation
+                        [..indentation.len() - indentation[1..].trim_start().len()]
+                        .to_string();
+                    leading.push_str("    ");
+                    leading
+                } else {
+                    if self.trailing.is_empty() {
+                        self.trailing.push(' ');
+                    }
+                    String::from(" ")
+                }
+            }
+        };
+
+        let mut comma = false;
+        if let Some(last) = self.entries.last_mut() {
+            comma = last.comma;
+            if !last.comma && (!self.implicit || !leading.contains('\n')) {
+                last.comma = true;
+                let same_line = line_comment_len(&self.trailing);
+                last.after_comma = self.trailing[..same_line].to_string();
+                self.trailing.drain(..same_line);
+            }
+        }
+        self.entries.push(Entry {
+            leading,
+            key,
+            value,
+            before_comma: String::new(),
+            comma,
+            after_comma: String::new(),
+        });
+    }
+}
+
+impl Display for Nested {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (open, close) = match (self.implicit, self.kind) {
+            (true, _) => ("", ""),
+            (false, parser::Nested::Tuple) => ("(", ")"),
+            (false, parser::Nested::List) => ("[", "]"),
+            (false, parser::Nested::Map) => ("{", "}"),
+        };
+        if let Some(name) = &self.name {
+            f.write_str(name)?;
+            f.write_str(&self.after_name)?;
+        }
+        f.write_str(open)?;
+        for entry in &self.entries {
+            Display::fmt(entry, f)?;
+        }
+        f.write_str(&self.trailing)?;
+        f.write_str(close)
+    }
+}
+
+/// An entry of a [`Nested`] value: either a value in a sequence, or a
+/// key-value pair in a map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    leading: String,
+    key: Option<Key>,
+    value: Value,
+    before_comma: String,
+    comma: bool,
+    /// Comments on the same line after the comma.
+    after_comma: String,
+}
+
+impl Entry {
+    /// Returns the key of this entry, if it is part of a map.
+    #[must_use]
+    pub fn key(&self) -> Option<&Value> {
+        self.key.as_ref().map(|key| &key.value)
+    }
+
+    /// Returns the value of this entry.
+    #[must_use]
+    pub const fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns an exclusive reference to the value of this entry.
+    #[must_use]
+    pub fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.leading)?;
+        if let Some(key) = &self.key {
+            Display::fmt(&key.value, f)?;
+            f.write_str(&key.before_colon)?;
+            f.write_char(':')?;
+            f.write_str(&key.after_colon)?;
+        }
+        Display::fmt(&self.value, f)?;
+        if self.comma {
+            f.write_str(&self.before_comma)?;
+            f.write_char(',')?;
+            f.write_str(&self.after_comma)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Key {
+    value: Value,
+    before_colon: String,
+    after_colon: String,
+}
+
+/// Builds a document from the tokens of a source that has already been
+/// validated by [`Parser`].
+struct Builder<'a> {
+    source: &'a str,
+    tokens: Vec<Token<'a>>,
+    index: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            tokens: Tokenizer::full(source)
+                .collect::<Result<_, _>>()
+                .expect("source was validated"),
+            index: 0,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.source.len(), |token| token.location.start)
+    }
+
+    fn peek(&self) -> Option<&TokenKind<'a>> {
+        self.tokens.get(self.index).map(|token| &token.kind)
+    }
+
+    /// Returns the first token after the current token that isn't whitespace.
+    fn peek_after_current(&self) -> Option<&TokenKind<'a>> {
+        self.tokens
+            .get(self.index + 1..)?
+            .iter()
+            .map(|token| &token.kind)
+            .find(|kind| !matches!(kind, TokenKind::Whitespace(_)))
+    }
+
+    fn starts_implicit_map(&self) -> bool {
+        matches!(self.peek_after_current(), Some(TokenKind::Colon))
+    }
+
+    fn trivia(&mut self) -> String {
+        let start = self.offset();
+        while matches!(
+            self.peek(),
+            Some(TokenKind::Whitespace(_) | TokenKind::Comment(_))
+        ) {
+            self.index += 1;
+        }
+        self.source[start..self.offset()].to_string()
+    }
+
+    fn value(&mut self) -> Value {
+        let token = self.tokens[self.index].clone();
+        let named = matches!(token.kind, TokenKind::Identifier(_))
+            && matches!(
+                self.peek_after_current(),
+                Some(TokenKind::Open(Balanced::Paren | Balanced::Brace))
+            );
+        self.index += 1;
+        let raw = self.source[token.location].to_string();
+        match token.kind {
+            TokenKind::Open(balanced) => {
+                Value::Nested(self.contents(None, String::new(), balanced.into(), false))
+            }
+            _ if named => {
+                let after_name = self.trivia();
+                let Some(TokenKind::Open(balanced)) = self.peek() else {
+                    unreachable!("checked above")
+                };
+                let kind = (*balanced).into();
+                self.index += 1;
+                Value::Nested(self.contents(Some(raw), after_name, kind, false))
+            }
+            _ => Value::Primitive(Primitive { raw }),
+        }
+    }
+
+    fn contents(
+        &mut self,
+        name: Option<String>,
+        after_name: String,
+        kind: parser::Nested,
+        implicit: bool,
+    ) -> Nested {
+        let mut entries = Vec::new();
+        let mut leading = self.trivia();
+        while !matches!(self.peek(), None | Some(TokenKind::Close(_))) {
+            let key = if kind == parser::Nested::Map {
+                let value = self.value();
+                let before_colon = self.trivia();
+                // The colon
+                self.index += 1;
+                Some(Key {
+                    value,
+                    before_colon,
+                    after_colon: self.trivia(),
+                })
+            } else {
+                None
+            };
+            let value = self.value();
+            let mut entry = Entry {
+                leading: String::new(),
+                key,
+                value,
+                before_comma: String::new(),
+                comma: false,
+                after_comma: String::new(),
+            };
+            let mut separator = self.trivia();
+            if matches!(self.peek(), Some(TokenKind::Comma)) {
+                self.index += 1;
+                entry.comma = true;
+                entry.before_comma = separator;
+                separator = self.trivia();
+                entry.after_comma = separator.drain(..line_comment_len(&separator)).collect();
+            }
+            entry.leading = mem::replace(&mut leading, separator);
+            entries.push(entry);
+        }
+        if !implicit {
+            // The closing delimiter
+            self.index += 1;
+        }
+
+        Nested {
+            name,
+            after_name,
+            kind,
+            implicit,
+            entries,
+            trailing: leading,
+        }
+    }
+}
+
+/// Returns the length of the comments at the start of `trivia` that are on
+/// the same line, or 0 if `trivia` doesn't start with a comment on the same
+/// line.
+fn line_comment_len(trivia: &str) -> usize {
+    let mut length = 0;
+    for token in Tokenizer::full(trivia) {
+        match token {
+            Ok(Token {
+                kind: TokenKind::Comment(_),
+                location,
+            }) => length = location.end,
+            Ok(Token {
+                kind: TokenKind::Whitespace(whitespace),
+                ..
+            }) if !whitespace.contains('\n') => {}
+            _ => break,
+        }
+    }
+    length
+}
+
+fn whitespace_or(trivia: &str, default: &str) -> String {
+    if trivia.trim().is_empty() {
+        trivia.to_string()
+    } else {
+        default.to_string()
+    }
+}
+
+fn is_identifier(source: &str) -> bool {
+    let mut tokens = Tokenizer::minified(source);
+    matches!(
+        tokens.next(),
+        Some(Ok(Token {
+            kind: TokenKind::Identifier(_),
+            location,
+        })) if location.end == source.len()
+    ) && tokens.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn parse(source: &str) -> Document {
+        let document =
+            Document::parse(source, Config::default().allow_implicit_map_at_root(true)).unwrap();
+        assert_eq!(document.to_string(), source);
+        document
+    }
+
+    fn root(document: &mut Document) -> &mut Nested {
+        document.root_mut().as_nested_mut().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        parse("1");
+        parse("  /* leading */ \"hi\" // trailing\n");
+        parse("[]");
+        parse("[1,2 , 3,]");
+        parse("Named ( 1 , /* two */ 2 )");
+        parse(
+            "// Header\n\nConfig {\n    // The name.\n    name: \"a\", // Inline\n    \
+             nested: { \"key\" /* before */ : [b'a', 'b', r#\"raw\"#], 1: Unit },\n\n    \
+             empty: Empty {},\n}\n",
+        );
+        parse("a: 1\nb: [\n    2,\n]\n// done\n");
+        parse("a: 1, b: 2 }");
+    }
+
+    #[test]
+    fn get() {
+        let mut document =
+            parse("Config { \"name\": \"a\", r#type: 1, nested: [Inner { a: true }] }");
+        let config = document.root().as_nested().unwrap();
+        assert_eq!(config.name(), Some("Config"));
+        assert_eq!(config.len(), 3);
+        assert_eq!(config.get("name").unwrap().to_string(), "\"a\"");
+        assert_eq!(
+            config
+                .get("nested")
+                .unwrap()
+                .as_nested()
+                .unwrap()
+                .get_index(0)
+                .unwrap()
+                .get("a")
+                .unwrap()
+                .to_value()
+                .unwrap(),
+            value::Value::Bool(true)
+        );
+        assert!(config.get("missing").is_none());
+
+        *document.root_mut().get_mut("name").unwrap() = "\"b\"".parse().unwrap();
+        assert_eq!(
+            document.to_string(),
+            "Config { \"name\": \"b\", r#type: 1, nested: [Inner { a: true }] }"
+        );
+    }
+
+    #[test]
+    fn insert() {
+        let mut document = parse("{\n    // A\n    a: 1 // one\n}");
+        root(&mut document).insert("b", "2".parse().unwrap());
+        assert_eq!(
+            document.to_string(),
+            "{\n    // A\n    a: 1, // one\n    b: 2\n}"
+        );
+
+        let mut document = parse("Named {\n    a : 1,\n}");
+        root(&mut document).insert("b c", "[\n        2,\n    ]".parse().unwrap());
+        assert_eq!(
+            document.to_string(),
+            "Named {\n    a : 1,\n    \"b c\" : [\n        2,\n    ],\n}"
+        );
+
+        let mut document = parse("{a: 1}");
+        assert_eq!(
+            root(&mut document).insert("a", "2".parse().unwrap()),
+            Some("1".parse().unwrap())
+        );
+        root(&mut document).insert("b", value::Value::Bool(false).try_into().unwrap());
+        assert_eq!(document.to_string(), "{a: 2, b: false}");
+
+        let mut document = parse("Named {}");
+        root(&mut document).insert("a", "1".parse().unwrap());
+        assert_eq!(document.to_string(), "Named { a: 1 }");
+
+        let mut document = parse("  {\n  }");
+        root(&mut document).insert("a", "1".parse().unwrap());
+        assert_eq!(document.to_string(), "  {\n      \"a\": 1\n  }");
+
+        let mut document = parse("a: 1\nb: 2\n");
+        root(&mut document).insert("c", "3".parse().unwrap());
+        assert_eq!(document.to_string(), "a: 1\nb: 2\nc: 3\n");
+    }
+
+    #[test]
+    fn remove() {
+        let source = "{\n    // A\n    a: 1, // one\n    b: 2, /* two */\n    c: 3,\n}";
+        let mut document = parse(source);
+        assert_eq!(root(&mut document).remove("a"), Some("1".parse().unwrap()));
+        assert_eq!(document.to_string(), "{\n    b: 2, /* two */\n    c: 3,\n}");
+        root(&mut document).remove("c");
+        assert_eq!(document.to_string(), "{\n    b: 2, /* two */\n}");
+        assert_eq!(root(&mut document).remove("c"), None);
+
+        let mut document = parse("[1, 2, 3]");
+        root(&mut document).remove_index(0);
+        assert_eq!(document.to_string(), "[2, 3]");
+        root(&mut document).remove_index(1);
+        assert_eq!(document.to_string(), "[2]");
+        root(&mut document).remove_index(0);
+        assert_eq!(document.to_string(), "[]");
+        root(&mut document).push("4".parse().unwrap());
+        assert_eq!(document.to_string(), "[ 4 ]");
+
+        let mut document = parse("{\n    a: 1, // one\n    b: 2\n}");
+        root(&mut document).remove("b");
+        assert_eq!(document.to_string(), "{\n    a: 1 // one\n}");
+    }
+
+    #[test]
+    fn rename() {
+        let mut document = parse("{ a: 1, \"b\": 2 }");
+        assert!(root(&mut document).rename("a", "x"));
+        assert!(root(&mut document).rename("b", "y"));
+        assert!(!root(&mut document).rename("x", "y"));
+        assert!(!root(&mut document).rename("missing", "z"));
+        assert!(root(&mut document).rename("x", "not an identifier"));
+        assert_eq!(
+            document.to_string(),
+            "{ \"not an identifier\": 1, \"y\": 2 }"
+        );
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let source = alloc::format!("{}{}", "[".repeat(200), "]".repeat(200));
+        assert_eq!(
+            Document::parse(&source, Config::default())
+                .unwrap_err()
+                .kind,
+            parser::ErrorKind::TooDeeplyNested
+        );
+        let document = Document::parse(&source, Config::default().max_depth(None)).unwrap();
+        let value = document.root().to_value().unwrap();
+        assert_eq!(value.to_string(), source);
+        assert_eq!(Value::try_from(value).unwrap().to_string(), source);
+    }
+
+    #[test]
+    fn dialects() {
+        for (source, dialect) in [
+            ("(a: .5)", Dialect::Ron),
+            ("\"\\ud83d\\ude00\"", Dialect::Json),
+        ] {
+            assert!(Parser::validate(source, Config::default().dialect(dialect)));
+            assert_eq!(
+                Document::parse(source, Config::default().dialect(dialect)),
+                Err(parser::Error::new(
+                    0..0,
+                    parser::ErrorKind::UnsupportedDialect
+                ))
+            );
+        }
+    }
+}
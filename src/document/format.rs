@@ -0,0 +1,313 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use super::{line_comment_len, Document, Entry, Nested, Value};
+use crate::parser;
+use crate::tokenizer::{Token, TokenKind, Tokenizer};
+use crate::writer::Config;
+
+/// Formats `document` using `config`, keeping all of its comments.
+pub(super) fn format(document: &Document, config: &Config) -> String {
+    let mut formatter = match config {
+        Config::Compact | Config::Canonical => Formatter {
+            output: String::new(),
+            pretty: false,
+            indentation: "",
+            newline: "",
+            blank_line_pending: false,
+        },
+        Config::Pretty {
+            indentation,
+            newline,
+            ..
+        } => Formatter {
+            output: String::new(),
+            pretty: true,
+            indentation,
+            newline,
+            blank_line_pending: false,
+        },
+    };
+
+    let mut written = false;
+    for comment in comments(&document.prefix) {
+        formatter.own_line_comment(0, written && comment.newlines_before > 1, comment.text);
+        written = true;
+    }
+    let blank = written && newlines_after_comments(&document.prefix) > 1;
+    if matches!(&document.root, Value::Nested(nested) if nested.implicit) {
+        // The first entry of the map begins its own line.
+        formatter.blank_line_pending = blank;
+    } else {
+        formatter.line(0, blank);
+    }
+    formatter.value(&document.root, 0);
+    for comment in comments(&document.suffix) {
+        if comment.newlines_before == 0 {
+            formatter.space();
+            formatter.comment(comment.text);
+        } else {
+            formatter.own_line_comment(0, comment.newlines_before > 1, comment.text);
+        }
+    }
+    if formatter.pretty {
+        formatter.output.push_str(formatter.newline);
+    }
+    formatter.output
+}
+
+struct Formatter<'a> {
+    output: String,
+    pretty: bool,
+    indentation: &'a str,
+    newline: &'a str,
+    /// When true, the next line is preceded by a blank line.
+    blank_line_pending: bool,
+}
+
+impl Formatter<'_> {
+    /// Begins a new line indented to `depth`, optionally preceded by a blank
+    /// line. Nothing is written at the start of the output or when using the
+    /// compact format.
+    fn line(&mut self, depth: usize, blank: bool) {
+        let blank = blank || mem::take(&mut self.blank_line_pending);
+        if !self.pretty || self.output.is_empty() {
+            return;
+        }
+        if blank {
+            self.output.push_str(self.newline);
+        }
+        self.output.push_str(self.newline);
+        for _ in 0..depth {
+            self.output.push_str(self.indentation);
+        }
+    }
+
+    fn space(&mut self) {
+        if self.pretty {
+            self.output.push(' ');
+        }
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.output.push_str(comment);
+        if !self.pretty && comment.starts_with("//") {
+            // Line comments must always be followed by a newline.
+            self.output.push('\n');
+        }
+    }
+
+    /// Writes a comment that is not on the same line as a value.
+    fn own_line_comment(&mut self, depth: usize, blank: bool, comment: &str) {
+        self.line(depth, blank);
+        // The compact format still places these comments on their own line so
+        // that they remain attached to the same value when parsed again.
+        if !self.pretty && !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push('\n');
+        }
+        self.comment(comment);
+    }
+
+    fn value(&mut self, value: &Value, depth: usize) {
+        match value {
+            Value::Primitive(primitive) => self.output.push_str(&primitive.raw),
+            Value::Nested(nested) => self.nested(nested, depth),
+        }
+    }
+
+    fn nested(&mut self, nested: &Nested, depth: usize) {
+        let (open, close, inner) = match (nested.implicit, nested.kind) {
+            (true, _) => ("", "", depth),
+            (false, parser::Nested::Tuple) => ("(", ")", depth + 1),
+            (false, parser::Nested::List) => ("[", "]", depth + 1),
+            (false, parser::Nested::Map) => ("{", "}", depth + 1),
+        };
+        if let Some(name) = &nested.name {
+            self.output.push_str(name);
+            if nested.kind == parser::Nested::Map {
+                self.space();
+            }
+        }
+        self.output.push_str(open);
+
+        let mut written = false;
+        for (index, entry) in nested.entries.iter().enumerate() {
+            // Comments on the same line as the end of the previous entry
+            // belong to the previous entry.
+            let leading = if index > 0 {
+                &entry.leading[line_comment_len(&entry.leading)..]
+            } else {
+                &entry.leading
+            };
+            for comment in comments(leading) {
+                self.own_line_comment(inner, written && comment.newlines_before > 1, comment.text);
+                written = true;
+            }
+            self.line(inner, written && newlines_after_comments(leading) > 1);
+            written = true;
+
+            self.entry(entry, inner);
+            let last = index + 1 == nested.entries.len();
+            if !last && (!nested.implicit || !self.pretty) {
+                self.output.push(',');
+            }
+
+            let following = nested
+                .entries
+                .get(index + 1)
+                .map_or(&nested.trailing, |next| &next.leading);
+            let mut line_comment = false;
+            for comment in
+                entry_comments(entry).chain(comments(&following[..line_comment_len(following)]))
+            {
+                if line_comment && self.pretty {
+                    self.line(inner, false);
+                } else {
+                    self.space();
+                }
+                self.comment(comment.text);
+                line_comment = comment.text.starts_with("//");
+            }
+        }
+
+        let trailing = if nested.entries.is_empty() {
+            &nested.trailing
+        } else {
+            &nested.trailing[line_comment_len(&nested.trailing)..]
+        };
+        for comment in comments(trailing) {
+            self.own_line_comment(inner, written && comment.newlines_before > 1, comment.text);
+            written = true;
+        }
+        if written && !nested.implicit {
+            self.line(depth, false);
+        }
+        self.output.push_str(close);
+    }
+
+    fn entry(&mut self, entry: &Entry, depth: usize) {
+        if let Some(key) = &entry.key {
+            self.value(&key.value, depth);
+            self.output.push(':');
+            self.space();
+        }
+        self.value(&entry.value, depth);
+    }
+}
+
+/// A comment within whitespace and comments.
+struct Comment<'a> {
+    text: &'a str,
+    /// The byte offset of the end of this comment.
+    end: usize,
+    /// The number of newlines between this comment and the preceding comment
+    /// or token.
+    newlines_before: usize,
+}
+
+fn comments(trivia: &str) -> impl Iterator<Item = Comment<'_>> {
+    let mut newlines = 0;
+    Tokenizer::full(trivia).filter_map(move |token| match token {
+        Ok(Token {
+            kind: TokenKind::Comment(_),
+            location,
+        }) => {
+            let newlines_before = newlines;
+            newlines = 0;
+            Some(Comment {
+                end: location.end,
+                text: &trivia[location],
+                newlines_before,
+            })
+        }
+        Ok(Token {
+            kind: TokenKind::Whitespace(whitespace),
+            ..
+        }) => {
+            newlines += whitespace.matches('\n').count();
+            None
+        }
+        _ => None,
+    })
+}
+
+/// Returns the number of newlines after the last comment in `trivia`.
+fn newlines_after_comments(trivia: &str) -> usize {
+    let start = comments(trivia).last().map_or(0, |comment| comment.end);
+    trivia[start..].matches('\n').count()
+}
+
+/// Returns the comments found within an entry and after its comma.
+fn entry_comments(entry: &Entry) -> impl Iterator<Item = Comment<'_>> {
+    let mut trivia = Vec::new();
+    if let Some(key) = &entry.key {
+        trivia.push(key.before_colon.as_str());
+        trivia.push(key.after_colon.as_str());
+    }
+    trivia.push(entry.before_comma.as_str());
+    trivia.push(entry.after_comma.as_str());
+    trivia.into_iter().flat_map(comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+
+    use crate::writer::Config;
+
+    const PRETTY: Config = Config::Pretty {
+        indentation: Cow::Borrowed("    "),
+        newline: Cow::Borrowed("\n"),
+        max_width: None,
+        align_map_values: false,
+    };
+
+    #[track_caller]
+    fn assert_formats(source: &str, pretty: &str, compact: &str) {
+        assert_eq!(crate::format(source, &PRETTY).unwrap(), pretty);
+        assert_eq!(crate::format(pretty, &PRETTY).unwrap(), pretty);
+        assert_eq!(crate::format(source, &Config::Compact).unwrap(), compact);
+        assert_eq!(crate::format(compact, &Config::Compact).unwrap(), compact);
+    }
+
+    #[test]
+    fn comments() {
+        assert_formats(
+            "// Header\n\nConfig{ // Config\n  name:\"rsn\",values:[1,2,],\n\n\n  // About b\n  \
+             b : { x /* k */ : 1 // one\n }, // after b\n  c: [ // dangling\n ] ,\n // end\n} // trailing\n",
+            "// Header\n\nConfig {\n    // Config\n    name: \"rsn\",\n    values: [\n        1,\n        2\n    ],\n\n    \
+             // About b\n    b: {\n        x: 1 /* k */ // one\n    }, // after b\n    c: [\n        // dangling\n    ]\n    \
+             // end\n} // trailing\n",
+            "// Header\nConfig{\n// Config\nname:\"rsn\",values:[1,2],\n// About b\nb:{x:1/* k */// one\n},// after b\n\
+             c:[\n// dangling\n]\n// end\n}// trailing\n",
+        );
+    }
+
+    #[test]
+    fn implicit_map() {
+        assert_formats(
+            "// Header\n\na: 1 // one\nb: Named(1, 2)\n\n\n\nc: {}",
+            "// Header\n\na: 1 // one\nb: Named(\n    1,\n    2\n)\n\nc: {}\n",
+            "// Header\na:1,// one\nb:Named(1,2),c:{}",
+        );
+    }
+
+    #[test]
+    fn primitives_unchanged() {
+        assert_formats(
+            "( 0xFF , 1_000 , r#\"raw\"# , b'a' , 1e3 , Unit )",
+            "(\n    0xFF,\n    1_000,\n    r#\"raw\"#,\n    b'a',\n    1e3,\n    Unit\n)\n",
+            "(0xFF,1_000,r#\"raw\"#,b'a',1e3,Unit)",
+        );
+        assert_formats("Named { }", "Named {}\n", "Named{}");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            crate::format("[1 2]", &PRETTY).unwrap_err().kind,
+            crate::parser::ErrorKind::ExpectedCommaOrEnd(crate::parser::Nested::List)
+        );
+    }
+}
@@ -12,6 +12,10 @@ extern crate std;
 /// Serde deserialization support.
 #[cfg(feature = "serde")]
 pub mod de;
+/// Line and column positions and source snippets for errors.
+pub mod diagnostics;
+/// Editing Rsn documents while preserving their formatting and comments.
+pub mod document;
 /// Parse data or a reader into a sequence of Rsn events.
 pub mod parser;
 /// Serde serialization support.
@@ -24,6 +28,9 @@ pub mod value;
 /// Types for writing Rsn.
 pub mod writer;
 
+#[cfg(feature = "serde")]
+pub use crate::de::{RawValue, Spanned};
+
 /// Deserializes `D` from `source` using the default Rsn
 /// [`Config`](parser::Config).
 ///
@@ -87,13 +94,10 @@ pub fn from_reader<D: serde::de::DeserializeOwned, R: std::io::Read>(
 ///
 /// # Errors
 ///
-/// Rsn itself does not produce any errors while serializing values. This
-/// function will return errors that arise within `Serialize` implementations
+/// Returns an error if an error arises within a `Serialize` implementation
 /// encountered while serializing `value`.
 #[cfg(feature = "serde")]
-pub fn to_string<S: serde::Serialize>(
-    value: &S,
-) -> Result<alloc::string::String, core::fmt::Error> {
+pub fn to_string<S: serde::Serialize>(value: &S) -> Result<alloc::string::String, ser::Error> {
     ser::Config::default().serialize(value)
 }
 
@@ -107,11 +111,10 @@ pub fn to_string<S: serde::Serialize>(
 ///
 /// # Errors
 ///
-/// Rsn itself does not produce any errors while serializing values. This
-/// function will return errors that arise within `Serialize` implementations
+/// Returns an error if an error arises within a `Serialize` implementation
 /// encountered while serializing `value`.
 #[cfg(feature = "serde")]
-pub fn to_vec<S: serde::Serialize>(value: &S) -> Result<alloc::vec::Vec<u8>, core::fmt::Error> {
+pub fn to_vec<S: serde::Serialize>(value: &S) -> Result<alloc::vec::Vec<u8>, ser::Error> {
     ser::Config::default().serialize_to_vec(value)
 }
 
@@ -132,7 +135,7 @@ pub fn to_vec<S: serde::Serialize>(value: &S) -> Result<alloc::vec::Vec<u8>, cor
 pub fn to_writer<S: serde::Serialize, W: std::io::Write>(
     value: &S,
     writer: W,
-) -> std::io::Result<usize> {
+) -> Result<usize, ser::Error> {
     ser::Config::default().serialize_to_writer(value, writer)
 }
 
@@ -147,15 +150,58 @@ pub fn to_writer<S: serde::Serialize, W: std::io::Write>(
 ///
 /// # Errors
 ///
-/// Rsn itself does not produce any errors while serializing values. This
-/// function will return errors that arise within `Serialize` implementations
+/// Returns an error if an error arises within a `Serialize` implementation
 /// encountered while serializing `value`.
 #[cfg(feature = "serde")]
 pub fn to_string_pretty<S: serde::Serialize>(
     value: &S,
-) -> Result<alloc::string::String, core::fmt::Error> {
+) -> Result<alloc::string::String, ser::Error> {
     ser::Config::pretty().serialize(value)
 }
 
+/// Reformats the Rsn in `source` using `config`.
+///
+/// The output is indented and spaced the same way as [`writer::Writer`],
+/// including its use of commas. Line and block comments are kept alongside
+/// the values they were written next to, and single blank lines between
+/// entries are preserved. The contents of values are not modified: numbers,
+/// strings, and identifiers are written exactly as they appear in `source`.
+///
+/// Implicit maps at the root of the document are allowed, and are formatted
+/// with one entry per line.
+/// [`writer::Config::Pretty::max_width`] and
+/// [`writer::Config::Pretty::align_map_values`] are not used: nested values
+/// are always written with one entry per line, without alignment.
+///
+/// ```rust
+/// use rsn::writer::Config;
+///
+/// let source = "Config{ // The configuration\n  name:\"rsn\",values:[1,2,],\n}";
+/// let config = Config::Pretty {
+///     indentation: "  ".into(),
+///     newline: "\n".into(),
+///     max_width: None,
+///     align_map_values: false,
+/// };
+/// assert_eq!(
+///     rsn::format(source, &config).unwrap(),
+///     "Config {\n  // The configuration\n  name: \"rsn\",\n  values: [\n    1,\n    2\n  ]\n}\n"
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `source` isn't valid Rsn.
+pub fn format(
+    source: &str,
+    config: &writer::Config,
+) -> Result<alloc::string::String, parser::Error> {
+    let document = document::Document::parse(
+        source,
+        parser::Config::default().allow_implicit_map_at_root(true),
+    )?;
+    Ok(document.format(config))
+}
+
 #[cfg(test)]
 mod tests;
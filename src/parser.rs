@@ -1,43 +1,162 @@
 use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
 use alloc::vec::Vec;
 use core::fmt::{Display, Formatter};
 use core::mem;
 use core::ops::{Deref, Range};
 
-use crate::tokenizer::{self, Balanced, Integer, Token, TokenKind, Tokenizer};
+#[cfg(feature = "std")]
+use crate::tokenizer::ReaderTokenizer;
+use crate::tokenizer::{
+    self, Balanced, Dialect, Integer, Token, TokenKind, TokenSource, Tokenizer,
+};
 
 /// Parses input as a sequence of [`Event`]s.
+///
+/// By default, a parser reads tokens from a [`Tokenizer`] that borrows the
+/// entire source. When the `std` feature is enabled,
+/// [`Parser::from_reader`] returns a parser that incrementally reads from a
+/// [`std::io::Read`] implementor and produces owned events.
 #[derive(Debug)]
-pub struct Parser<'s> {
-    tokens: Tokenizer<'s, false>,
-    peeked: Option<Result<Token<'s>, tokenizer::Error>>,
-    nested: Vec<(usize, NestedState)>,
+pub struct Parser<'s, Tokens = Tokenizer<'s, false>> {
+    tokens: Tokens,
+    peeked: VecDeque<Result<Token<'s>, tokenizer::Error>>,
+    /// The starting offset, state, and number of entries of each open nested
+    /// value.
+    nested: Vec<(usize, NestedState, usize)>,
+    /// The number of entries in the implicit map at the root, if any.
+    implicit_map_len: usize,
     root_state: State<'s>,
     config: Config,
+    ron_extensions: RonExtensions,
+    /// The most recently consumed token, tracked when recovering from errors.
+    last_token: LastToken,
+    /// What the parser was attempting to parse most recently, tracked when
+    /// recovering from errors.
+    expecting: Expecting,
+    /// Results produced while recovering from an error.
+    recovered: VecDeque<Result<Event<'s>, Error>>,
 }
 
 impl<'s> Parser<'s> {
     /// Returns a parser that parses `source` using `configuration`.
     #[must_use]
     pub fn new(source: &'s str, configuration: Config) -> Self {
+        Self::from_tokens(
+            Tokenizer::minified(source).with_dialect(configuration.dialect),
+            configuration,
+        )
+    }
+
+    /// Validates that `source` would parse successfully using `configuration`.
+    #[must_use]
+    pub fn validate(source: &'s str, configuration: Config) -> bool {
+        Self::new(source, configuration).all(|result| result.is_ok())
+    }
+
+    /// Parses `source` using `configuration` with
+    /// [`Config::recover_from_errors`] enabled, returning all events parsed
+    /// and all errors encountered.
+    ///
+    /// ```rust
+    /// use rsn::parser::{Config, ErrorKind, EventKind, Parser};
+    ///
+    /// let (events, errors) = Parser::parse_recovering("[1, , 3 4]", Config::default());
+    /// assert_eq!(events.len(), 5);
+    /// assert_eq!(events[2].kind, EventKind::Placeholder);
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].kind, ErrorKind::ExpectedValue);
+    /// assert_eq!(errors[0].location, 4..5);
+    /// ```
+    #[must_use]
+    pub fn parse_recovering(
+        source: &'s str,
+        configuration: Config,
+    ) -> (Vec<Event<'s>>, Vec<Error>) {
+        let mut events = Vec::new();
+        let mut errors = Vec::new();
+        for result in Self::new(source, configuration.recover_from_errors(true)) {
+            match result {
+                Ok(event) => events.push(event),
+                Err(err) => errors.push(err),
+            }
+        }
+        (events, errors)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Parser<'static, ReaderTokenizer<R, false>>
+where
+    R: std::io::Read,
+{
+    /// Returns a parser that incrementally parses the contents of `reader`
+    /// using `configuration`.
+    ///
+    /// The input is read in chunks as events are requested, and only the
+    /// input that has not yet been parsed is kept in memory. All events
+    /// returned own their data.
+    ///
+    /// ```rust
+    /// use rsn::parser::{Config, EventKind, Parser};
+    ///
+    /// let events = Parser::from_reader(&b"[1, 2, 3]"[..], Config::default())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(events.len(), 5);
+    /// assert_eq!(events[4].kind, EventKind::EndNested);
+    /// ```
+    #[must_use]
+    pub fn from_reader(reader: R, configuration: Config) -> Self {
+        let mut tokens = ReaderTokenizer::minified(reader).with_dialect(configuration.dialect);
+        if let Some(max) = configuration.max_input_size {
+            tokens = tokens.with_read_limit(max.saturating_add(1));
+        }
+        Self::from_tokens(tokens, configuration)
+    }
+}
+
+impl<'s, Tokens> Parser<'s, Tokens>
+where
+    Tokens: TokenSource<'s>,
+{
+    /// Returns a parser that parses the tokens produced by `tokens` using
+    /// `configuration`.
+    ///
+    /// `tokens` must not produce [`TokenKind::Whitespace`] tokens, and should
+    /// use the same [`Dialect`] as `configuration`.
+    #[must_use]
+    pub fn from_tokens(tokens: Tokens, configuration: Config) -> Self {
         Self {
-            tokens: Tokenizer::minified(source),
-            peeked: None,
+            tokens,
+            peeked: VecDeque::new(),
             nested: Vec::new(),
+            implicit_map_len: 0,
             root_state: State::AtStart,
             config: configuration,
+            ron_extensions: RonExtensions::default(),
+            last_token: LastToken::Eof,
+            expecting: Expecting::Root,
+            recovered: VecDeque::new(),
         }
     }
 
-    /// Validates that `source` would parse successfully using `configuration`.
+    /// Returns the configuration of this parser.
     #[must_use]
-    pub fn validate(source: &'s str, configuration: Config) -> bool {
-        Self::new(source, configuration).all(|result| result.is_ok())
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the extensions enabled by the `#![enable(...)]` attributes
+    /// parsed so far when parsing [`Dialect::Ron`].
+    #[must_use]
+    pub const fn ron_extensions(&self) -> RonExtensions {
+        self.ron_extensions
     }
 
     /// Returns the current byte offset of the parser.
     #[must_use]
-    pub const fn current_offset(&self) -> usize {
+    pub fn current_offset(&self) -> usize {
         self.tokens.current_offset()
     }
 
@@ -45,20 +164,79 @@ impl<'s> Parser<'s> {
     /// the current byte offset of the parser.
     #[must_use]
     pub fn current_range(&self) -> Range<usize> {
-        let start = self.nested.last().map_or(0, |(offset, _)| *offset);
+        let start = self.nested.last().map_or(0, |(offset, _, _)| *offset);
         start..self.tokens.current_offset()
     }
 
+    /// Returns the input in the byte range `range`, if the parser's token
+    /// source borrows its input.
+    #[cfg(feature = "serde")]
+    pub(crate) fn source(&self, range: Range<usize>) -> Option<&'s str> {
+        self.tokens.source(range)
+    }
+
+    /// Returns true if a complete value has been parsed at the root of the
+    /// document.
+    #[cfg(feature = "serde")]
+    pub(crate) fn value_complete(&self) -> bool {
+        self.nested.is_empty() && matches!(self.root_state, State::Finished)
+    }
+
+    /// Allows another value to be parsed after a complete value has been
+    /// parsed at the root of the document. This has no effect if a value is
+    /// still being parsed.
+    #[cfg(feature = "serde")]
+    pub(crate) fn begin_next_value(&mut self) {
+        if self.value_complete() {
+            self.root_state = State::AtStart;
+            self.implicit_map_len = 0;
+        }
+    }
+
     fn peek(&mut self) -> Option<&Token<'s>> {
-        if self.peeked.is_none() {
-            self.peeked = self.tokens.next();
+        self.peek_nth(0)
+    }
+
+    /// Returns the token `n` tokens after the next token without consuming
+    /// any tokens.
+    fn peek_nth(&mut self, n: usize) -> Option<&Token<'s>> {
+        while self.peeked.len() <= n {
+            if matches!(self.peeked.back(), Some(Err(_))) {
+                return None;
+            }
+            let token = self.tokens.next()?;
+            self.peeked.push_back(token);
         }
 
-        self.peeked.as_ref().and_then(|r| r.as_ref().ok())
+        self.peeked.get(n).and_then(|r| r.as_ref().ok())
     }
 
     fn next_token(&mut self) -> Option<Result<Token<'s>, tokenizer::Error>> {
-        self.peeked.take().or_else(|| self.tokens.next())
+        let mut token = self.peeked.pop_front().or_else(|| self.tokens.next());
+        if let Some(Ok(Token {
+            kind: TokenKind::Comment(comment),
+            location,
+        })) = &token
+        {
+            // RON attributes are only allowed before the root value.
+            if self.config.dialect == Dialect::Ron
+                && comment.starts_with('#')
+                && !(self.nested.is_empty() && self.root_state == State::AtStart)
+            {
+                token = Some(Err(tokenizer::Error::new(
+                    location.start..location.start + 1,
+                    tokenizer::ErrorKind::Unexpected('#'),
+                )));
+            }
+        }
+        if self.config.recover_from_errors {
+            self.last_token = match &token {
+                Some(Ok(token)) => LastToken::from(token),
+                Some(Err(_)) => LastToken::Other,
+                None => LastToken::Eof,
+            };
+        }
+        token
     }
 
     fn next_token_parts(
@@ -110,33 +288,41 @@ impl<'s> Parser<'s> {
                 token.location,
                 EventKind::Primitive(Primitive::Integer(Integer::Usize(value as usize))),
             )),
-            TokenKind::String(value) => Ok(Event::new(
-                token.location,
-                EventKind::Primitive(Primitive::String(value)),
-            )),
-            TokenKind::Bytes(value) => Ok(Event::new(
-                token.location,
-                EventKind::Primitive(Primitive::Bytes(value)),
-            )),
-            TokenKind::Identifier(value) => self.parse_identifier(token, value),
+            TokenKind::String(value) => {
+                self.check_string_length(&token.location, value.len())?;
+                Ok(Event::new(
+                    token.location,
+                    EventKind::Primitive(Primitive::String(value)),
+                ))
+            }
+            TokenKind::Bytes(value) => {
+                self.check_string_length(&token.location, value.len())?;
+                Ok(Event::new(
+                    token.location,
+                    EventKind::Primitive(Primitive::Bytes(value)),
+                ))
+            }
+            TokenKind::Identifier(_) if self.config.dialect == Dialect::Json => {
+                // The only identifier the JSON dialect produces is `null`.
+                Ok(Event::new(
+                    token.location,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("None"))),
+                ))
+            }
+            TokenKind::Identifier(value) => self.parse_identifier(token.location, value),
             TokenKind::Open(Balanced::Paren) => {
-                self.nested.push((
-                    token.location.start,
-                    NestedState::Tuple(ListStateExpecting::Value),
-                ));
+                let (state, kind) = self.paren_contents();
+                self.push_nested(&token.location, state)?;
                 Ok(Event::new(
                     token.location,
-                    EventKind::BeginNested {
-                        name: None,
-                        kind: Nested::Tuple,
-                    },
+                    EventKind::BeginNested { name: None, kind },
                 ))
             }
             TokenKind::Open(Balanced::Bracket) => {
-                self.nested.push((
-                    token.location.start,
+                self.push_nested(
+                    &token.location,
                     NestedState::List(ListStateExpecting::Value),
-                ));
+                )?;
                 Ok(Event::new(
                     token.location,
                     EventKind::BeginNested {
@@ -146,10 +332,7 @@ impl<'s> Parser<'s> {
                 ))
             }
             TokenKind::Open(Balanced::Brace) => {
-                self.nested.push((
-                    token.location.start,
-                    NestedState::Map(MapStateExpecting::Key),
-                ));
+                self.push_nested(&token.location, NestedState::Map(MapStateExpecting::Key))?;
                 Ok(Event::new(
                     token.location,
                     EventKind::BeginNested {
@@ -172,7 +355,11 @@ impl<'s> Parser<'s> {
         }
     }
 
-    fn parse_identifier(&mut self, token: Token<'s>, value: &'s str) -> Result<Event<'s>, Error> {
+    fn parse_identifier(
+        &mut self,
+        location: Range<usize>,
+        value: Cow<'s, str>,
+    ) -> Result<Event<'s>, Error> {
         if matches!(
             self.peek(),
             Some(Token {
@@ -190,17 +377,12 @@ impl<'s> Parser<'s> {
 
             let kind = match balanced {
                 Balanced::Paren => {
-                    self.nested.push((
-                        open_location.start,
-                        NestedState::Tuple(ListStateExpecting::Value),
-                    ));
-                    Nested::Tuple
+                    let (state, kind) = self.paren_contents();
+                    self.push_nested(&open_location, state)?;
+                    kind
                 }
                 Balanced::Brace => {
-                    self.nested.push((
-                        open_location.start,
-                        NestedState::Map(MapStateExpecting::Key),
-                    ));
+                    self.push_nested(&open_location, NestedState::Map(MapStateExpecting::Key))?;
                     Nested::Map
                 }
                 Balanced::Bracket => {
@@ -212,7 +394,7 @@ impl<'s> Parser<'s> {
                 open_location,
                 EventKind::BeginNested {
                     name: Some(Name {
-                        location: token.location,
+                        location,
                         name: value,
                     }),
                     kind,
@@ -229,26 +411,137 @@ impl<'s> Parser<'s> {
             return Err(Error::new(location, ErrorKind::ExpectedMapOrTuple));
         } else {
             Ok(Event::new(
-                token.location,
+                location,
                 EventKind::Primitive(Primitive::Identifier(value)),
             ))
         }
     }
 
+    /// Begins parsing a nested value opened by the token at `location`.
+    fn push_nested(&mut self, location: &Range<usize>, state: NestedState) -> Result<(), Error> {
+        if self
+            .config
+            .max_depth
+            .map_or(false, |max| self.nested.len() >= max)
+        {
+            return Err(Error::new(location.clone(), ErrorKind::TooDeeplyNested));
+        }
+        self.nested.push((location.start, state, 0));
+        Ok(())
+    }
+
+    /// Counts an entry of the current nested value that begins with the token
+    /// at `location`.
+    fn begin_entry(&mut self, location: &Range<usize>) -> Result<(), Error> {
+        let len = match self.nested.last_mut() {
+            Some((_, _, len)) => len,
+            None => &mut self.implicit_map_len,
+        };
+        *len += 1;
+        if self
+            .config
+            .max_collection_length
+            .map_or(false, |max| *len > max)
+        {
+            return Err(Error::new(location.clone(), ErrorKind::CollectionTooLong));
+        }
+        Ok(())
+    }
+
+    fn check_string_length(&self, location: &Range<usize>, length: usize) -> Result<(), Error> {
+        if self
+            .config
+            .max_string_length
+            .map_or(false, |max| length > max)
+        {
+            return Err(Error::new(location.clone(), ErrorKind::StringTooLong));
+        }
+        Ok(())
+    }
+
+    /// Returns the input size error if more input than
+    /// [`Config::max_input_size`] has been read.
+    fn check_input_size(&self, result: Option<&Result<Event<'s>, Error>>) -> Option<Error> {
+        let max = self.config.max_input_size?;
+        if matches!(self.root_state, State::InputTooLarge) {
+            return None;
+        }
+        let end = match result {
+            Some(Err(err)) => err.location.end,
+            _ => 0,
+        }
+        .max(self.tokens.current_offset());
+        (end > max).then(|| Error::new(max..end, ErrorKind::InputTooLarge))
+    }
+
+    /// Returns the state and kind of a nested value that was opened by `(`.
+    ///
+    /// When parsing [`Dialect::Ron`], a `(` followed by `field:` begins a
+    /// structure, which is parsed as a map.
+    fn paren_contents(&mut self) -> (NestedState, Nested) {
+        if self.config.dialect == Dialect::Ron {
+            let field = self.skip_peeked_comments(0);
+            if matches!(
+                self.peek_nth(field),
+                Some(Token {
+                    kind: TokenKind::Identifier(_),
+                    ..
+                })
+            ) {
+                let colon = self.skip_peeked_comments(field + 1);
+                if matches!(
+                    self.peek_nth(colon),
+                    Some(Token {
+                        kind: TokenKind::Colon,
+                        ..
+                    })
+                ) {
+                    return (NestedState::Struct(MapStateExpecting::Key), Nested::Map);
+                }
+            }
+        }
+
+        (NestedState::Tuple(ListStateExpecting::Value), Nested::Tuple)
+    }
+
+    /// Returns the index of the first token at or after the `index`th
+    /// upcoming token that isn't a comment.
+    fn skip_peeked_comments(&mut self, mut index: usize) -> usize {
+        while matches!(
+            self.peek_nth(index),
+            Some(Token {
+                kind: TokenKind::Comment(_),
+                ..
+            })
+        ) {
+            index += 1;
+        }
+        index
+    }
+
     fn parse_sequence(
         &mut self,
         state: ListStateExpecting,
         end: Balanced,
     ) -> Result<Event<'s>, Error> {
+        self.expecting = match state {
+            ListStateExpecting::Value => Expecting::Value,
+            ListStateExpecting::Comma => Expecting::Separator,
+        };
         match state {
             ListStateExpecting::Value => {
                 let token = self.next_or_eof()?;
-                if let TokenKind::Comment(comment) = &token.kind {
-                    Ok(Event::new(token.location, EventKind::Comment(comment)))
-                } else {
-                    self.nested.last_mut().expect("required for this fn").1 =
-                        NestedState::list(end, ListStateExpecting::Comma);
-                    self.parse_token(token, Some(end))
+                match token.kind {
+                    TokenKind::Comment(comment) => {
+                        Ok(Event::new(token.location, EventKind::Comment(comment)))
+                    }
+                    TokenKind::Close(closed) if closed == end => self.parse_token(token, Some(end)),
+                    _ => {
+                        self.begin_entry(&token.location)?;
+                        self.nested.last_mut().expect("required for this fn").1 =
+                            NestedState::list(end, ListStateExpecting::Comma);
+                        self.parse_token(token, Some(end))
+                    }
                 }
             }
             ListStateExpecting::Comma => match self.next_token_parts()? {
@@ -257,6 +550,7 @@ impl<'s> Parser<'s> {
                     Ok(Event::new(location, EventKind::EndNested))
                 }
                 (_, Some(TokenKind::Comma)) => {
+                    self.reject_trailing_comma(ErrorKind::ExpectedValue)?;
                     self.nested.last_mut().expect("required for this fn").1 =
                         NestedState::list(end, ListStateExpecting::Value);
                     self.parse_sequence(ListStateExpecting::Value, end)
@@ -272,23 +566,53 @@ impl<'s> Parser<'s> {
         }
     }
 
+    /// Returns an error containing `kind` if the JSON dialect is being parsed
+    /// and the next token closes the current nested value.
+    fn reject_trailing_comma(&mut self, kind: ErrorKind) -> Result<(), Error> {
+        if self.config.dialect == Dialect::Json {
+            if let Some(Token {
+                kind: TokenKind::Close(_),
+                location,
+            }) = self.peek()
+            {
+                return Err(Error::new(location.clone(), kind));
+            }
+        }
+        Ok(())
+    }
+
     fn map_state_mut(&mut self) -> &mut MapStateExpecting {
-        let Some((_, NestedState::Map(map_state))) = self.nested.last_mut() else {
+        let Some((_, NestedState::Map(map_state) | NestedState::Struct(map_state), _)) =
+            self.nested.last_mut()
+        else {
             unreachable!("not a map state")
         };
         map_state
     }
 
-    fn parse_map(&mut self, state: MapStateExpecting) -> Result<Event<'s>, Error> {
+    fn parse_map(&mut self, state: MapStateExpecting, end: Balanced) -> Result<Event<'s>, Error> {
+        self.expecting = state.expecting();
         match state {
             MapStateExpecting::Key => match self.next_token().transpose()? {
                 Some(Token {
                     kind: TokenKind::Comment(comment),
                     location,
                 }) => Ok(Event::new(location, EventKind::Comment(comment))),
+                Some(token)
+                    if self.config.dialect == Dialect::Json
+                        && !matches!(
+                            token.kind,
+                            TokenKind::String(_) | TokenKind::Close(Balanced::Brace)
+                        ) =>
+                {
+                    Err(Error::new(token.location, ErrorKind::ExpectedKey))
+                }
                 Some(token) => {
+                    if !matches!(token.kind, TokenKind::Close(closed) if closed == end) {
+                        self.begin_entry(&token.location)?;
+                    }
                     *self.map_state_mut() = MapStateExpecting::Colon;
-                    self.parse_token(token, Some(Balanced::Brace))
+                    self.parse_token(token, Some(end))
                 }
                 None => Err(Error::new(
                     self.tokens.current_offset()..self.tokens.current_offset(),
@@ -298,7 +622,7 @@ impl<'s> Parser<'s> {
             MapStateExpecting::Colon => match self.next_token_parts()? {
                 (_, Some(TokenKind::Colon)) => {
                     *self.map_state_mut() = MapStateExpecting::Value;
-                    self.parse_map(MapStateExpecting::Value)
+                    self.parse_map(MapStateExpecting::Value, end)
                 }
                 (location, Some(TokenKind::Comment(comment))) => {
                     Ok(Event::new(location, EventKind::Comment(comment)))
@@ -320,17 +644,19 @@ impl<'s> Parser<'s> {
                 )),
             },
             MapStateExpecting::Comma => match self.next_token_parts()? {
-                (location, Some(TokenKind::Close(Balanced::Brace))) => {
+                (location, Some(TokenKind::Close(closed))) if closed == end => {
                     self.nested.pop();
                     Ok(Event::new(location, EventKind::EndNested))
                 }
                 (_, Some(TokenKind::Comma)) => {
+                    self.reject_trailing_comma(ErrorKind::ExpectedKey)?;
                     *self.map_state_mut() = MapStateExpecting::Key;
-                    self.parse_map(MapStateExpecting::Key)
+                    self.parse_map(MapStateExpecting::Key, end)
                 }
                 (location, Some(TokenKind::Comment(comment))) => {
                     Ok(Event::new(location, EventKind::Comment(comment)))
                 }
+                // RON structures end with `)`, but are still maps.
                 (location, _) => Err(Error::new(
                     location,
                     ErrorKind::ExpectedCommaOrEnd(Nested::Map),
@@ -339,14 +665,20 @@ impl<'s> Parser<'s> {
         }
     }
 
+    fn parse_implicit_map_key(&mut self, token: Token<'s>) -> Result<Event<'s>, Error> {
+        self.begin_entry(&token.location)?;
+        self.parse_token(token, None)
+    }
+
     fn parse_implicit_map(&mut self, state: MapStateExpecting) -> Result<Event<'s>, Error> {
+        self.expecting = state.expecting();
         match state {
             MapStateExpecting::Key => match self.next_token().transpose()? {
                 Some(Token {
                     location,
                     kind: TokenKind::Comment(comment),
                 }) => Ok(Event::new(location, EventKind::Comment(comment))),
-                Some(token) => match self.parse_token(token, None)? {
+                Some(token) => match self.parse_implicit_map_key(token)? {
                     Event {
                         kind: EventKind::Primitive(primitive),
                         location,
@@ -406,7 +738,7 @@ impl<'s> Parser<'s> {
                 }
                 Some(token) => {
                     self.root_state = State::ImplicitMap(MapStateExpecting::Colon);
-                    match self.parse_token(token, None)? {
+                    match self.parse_implicit_map_key(token)? {
                         Event {
                             location,
                             kind: EventKind::Primitive(primitive),
@@ -422,19 +754,191 @@ impl<'s> Parser<'s> {
         }
     }
 
+    /// Parses a RON inner attribute, such as `#![enable(implicit_some)]`,
+    /// enabling the extensions it lists.
+    fn parse_ron_attribute(
+        &mut self,
+        location: Range<usize>,
+        attribute: Cow<'s, str>,
+    ) -> Result<Event<'s>, Error> {
+        let extensions = attribute
+            .strip_prefix("#![")
+            .and_then(|attribute| attribute.strip_suffix(']'))
+            .and_then(|attribute| attribute.trim().strip_prefix("enable"))
+            .and_then(|attribute| attribute.trim_start().strip_prefix('('))
+            .and_then(|attribute| attribute.strip_suffix(')'))
+            .ok_or_else(|| Error::new(location.clone(), ErrorKind::InvalidAttribute))?;
+        for extension in extensions.split(',') {
+            let extension = extension.trim();
+            let enabled = match extension {
+                "implicit_some" => &mut self.ron_extensions.implicit_some,
+                "unwrap_newtypes" => &mut self.ron_extensions.unwrap_newtypes,
+                "unwrap_variant_newtypes" => &mut self.ron_extensions.unwrap_variant_newtypes,
+                "explicit_struct_names" => &mut self.ron_extensions.explicit_struct_names,
+                // Trailing commas are allowed.
+                "" => continue,
+                _ => {
+                    let start = location.start
+                        + (extension.as_ptr() as usize - attribute.as_ptr() as usize);
+                    return Err(Error::new(
+                        start..start + extension.len(),
+                        ErrorKind::InvalidAttribute,
+                    ));
+                }
+            };
+            *enabled = true;
+        }
+
+        Ok(Event::new(location, EventKind::Comment(attribute)))
+    }
+
+    fn next_recovering_event(&mut self) -> Option<Result<Event<'s>, Error>> {
+        if let Some(recovered) = self.recovered.pop_front() {
+            return Some(recovered);
+        }
+
+        if !self.config.recover_from_errors {
+            return self.next_event();
+        }
+
+        let depth = self.nested.len();
+        let result = self.next_event()?;
+        if let Err(err) = &result {
+            self.recover(depth, self.expecting, err);
+        }
+        Some(result)
+    }
+
+    /// Resynchronizes the parser after `error` was returned while parsing at
+    /// a nesting `depth` while expecting `expecting`.
+    ///
+    /// Tokens are skipped until a `,` or a closing token that matches an open
+    /// nested value is found. Events needed to keep the event stream well
+    /// formed are queued in `self.recovered`, along with any errors
+    /// encountered while skipping.
+    fn recover(&mut self, depth: usize, expecting: Expecting, error: &Error) {
+        match expecting {
+            Expecting::Root | Expecting::Nothing => {
+                if expecting == Expecting::Root {
+                    self.recovered.push_back(Ok(Event::new(
+                        error.location.clone(),
+                        EventKind::Placeholder,
+                    )));
+                    self.root_state = State::Finished;
+                }
+                // There is nothing to resynchronize with at the root, so the
+                // remaining input is skipped. Only the first error is
+                // reported, excluding errors from the tokenizer.
+                while let Some(token) = self.next_token() {
+                    if let Err(err) = token {
+                        self.recovered.push_back(Err(err.into()));
+                    }
+                }
+                return;
+            }
+            Expecting::Value | Expecting::MapValue | Expecting::Key | Expecting::Separator => {}
+        }
+
+        // Any nesting that began while producing the error must be skipped.
+        let mut skip_depth = self.nested.len().saturating_sub(depth);
+        self.nested.truncate(depth);
+        let mut token = if skip_depth > 0 {
+            LastToken::Other
+        } else {
+            self.last_token.clone()
+        };
+        let at_eof = token == LastToken::Eof;
+        let sync = loop {
+            match token {
+                LastToken::Eof => break Sync::Eof,
+                LastToken::Comma if skip_depth == 0 => break Sync::Comma,
+                LastToken::Open => skip_depth += 1,
+                LastToken::Close(_, _) if skip_depth > 0 => skip_depth -= 1,
+                LastToken::Close(balanced, location) => {
+                    if let Some(index) = self
+                        .nested
+                        .iter()
+                        .rposition(|(_, state, _)| state.closed_by() == balanced)
+                    {
+                        break Sync::Close(index, location);
+                    }
+                    // Unmatched closing tokens are skipped.
+                }
+                LastToken::Comma | LastToken::Other => {}
+            }
+
+            if let Some(Err(err)) = self.next_token() {
+                self.recovered.push_back(Err(err.into()));
+            }
+            token = self.last_token.clone();
+        };
+
+        let needs_placeholder = match expecting {
+            Expecting::MapValue => true,
+            // A missing value at the end of a sequence is a trailing comma.
+            Expecting::Value => !at_eof,
+            _ => false,
+        };
+        if needs_placeholder {
+            self.recovered.push_back(Ok(Event::new(
+                error.location.clone(),
+                EventKind::Placeholder,
+            )));
+        }
+
+        match sync {
+            Sync::Comma => match self.nested.last_mut() {
+                Some((_, state, _)) => *state = state.after_comma(),
+                None => self.root_state = State::ImplicitMap(MapStateExpecting::Key),
+            },
+            Sync::Close(index, location) => {
+                while self.nested.len() > index + 1 {
+                    self.nested.pop();
+                    self.recovered.push_back(Ok(Event::new(
+                        location.start..location.start,
+                        EventKind::EndNested,
+                    )));
+                }
+                self.nested.pop();
+                self.recovered
+                    .push_back(Ok(Event::new(location, EventKind::EndNested)));
+            }
+            Sync::Eof => {
+                let end = self.tokens.current_offset();
+                let mut unclosed = self.nested.len();
+                self.nested.clear();
+                if matches!(self.root_state, State::ImplicitMap(_)) {
+                    unclosed += 1;
+                }
+                for _ in 0..unclosed {
+                    self.recovered
+                        .push_back(Ok(Event::new(end..end, EventKind::EndNested)));
+                }
+                self.root_state = State::Finished;
+            }
+        }
+    }
+
     fn next_event(&mut self) -> Option<Result<Event<'s>, Error>> {
         Some(match self.nested.last() {
             None => match &self.root_state {
                 State::AtStart => {
+                    self.expecting = Expecting::Root;
                     let token = match self.next_token()? {
                         Ok(token) => token,
                         Err(err) => return Some(Err(err.into())),
                     };
-                    match &token.kind {
+                    match token.kind {
+                        TokenKind::Comment(comment)
+                            if self.config.dialect == Dialect::Ron && comment.starts_with('#') =>
+                        {
+                            self.parse_ron_attribute(token.location, comment)
+                        }
                         TokenKind::Comment(comment) => {
                             Ok(Event::new(token.location, EventKind::Comment(comment)))
                         }
                         _ if self.config.allow_implicit_map_at_root
+                            && self.config.dialect == Dialect::Rsn
                             && matches!(
                                 self.peek(),
                                 Some(Token {
@@ -443,7 +947,7 @@ impl<'s> Parser<'s> {
                                 })
                             ) =>
                         {
-                            match self.parse_token(token, None) {
+                            match self.parse_implicit_map_key(token) {
                                 Ok(event) => {
                                     self.root_state = State::StartingImplicitMap(event);
                                     Ok(Event::new(
@@ -473,31 +977,46 @@ impl<'s> Parser<'s> {
                     Ok(event)
                 }
                 State::ImplicitMap(state) => self.parse_implicit_map(*state),
-                State::Finished => match self.next_token()? {
-                    Ok(token) => match token.kind {
-                        TokenKind::Comment(comment) => {
-                            Ok(Event::new(token.location, EventKind::Comment(comment)))
-                        }
-                        TokenKind::Whitespace(_) => unreachable!("disabled"),
-                        _ => Err(Error::new(token.location, ErrorKind::TrailingData)),
-                    },
-                    Err(err) => Err(err.into()),
-                },
+                State::InputTooLarge => return None,
+                State::Finished => {
+                    self.expecting = Expecting::Nothing;
+                    match self.next_token()? {
+                        Ok(token) => match token.kind {
+                            TokenKind::Comment(comment) => {
+                                Ok(Event::new(token.location, EventKind::Comment(comment)))
+                            }
+                            TokenKind::Whitespace(_) => unreachable!("disabled"),
+                            _ => Err(Error::new(token.location, ErrorKind::TrailingData)),
+                        },
+                        Err(err) => Err(err.into()),
+                    }
+                }
             },
 
-            Some((_, NestedState::Tuple(list))) => self.parse_sequence(*list, Balanced::Paren),
-            Some((_, NestedState::List(list))) => self.parse_sequence(*list, Balanced::Bracket),
-            Some((_, NestedState::Map(map))) => self.parse_map(*map),
+            Some((_, NestedState::Tuple(list), _)) => self.parse_sequence(*list, Balanced::Paren),
+            Some((_, NestedState::List(list), _)) => self.parse_sequence(*list, Balanced::Bracket),
+            Some((_, NestedState::Map(map), _)) => self.parse_map(*map, Balanced::Brace),
+            Some((_, NestedState::Struct(map), _)) => self.parse_map(*map, Balanced::Paren),
         })
     }
 }
 
-impl<'s> Iterator for Parser<'s> {
+impl<'s, Tokens> Iterator for Parser<'s, Tokens>
+where
+    Tokens: TokenSource<'s>,
+{
     type Item = Result<Event<'s>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let event = self.next_event()?;
+            let event = self.next_recovering_event();
+            if let Some(err) = self.check_input_size(event.as_ref()) {
+                self.nested.clear();
+                self.recovered.clear();
+                self.root_state = State::InputTooLarge;
+                return Some(Err(err));
+            }
+            let event = event?;
             if self.config.include_comments
                 || !matches!(
                     event,
@@ -516,7 +1035,7 @@ impl<'s> Iterator for Parser<'s> {
 }
 
 /// The configuration of a [`Parser`].
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub struct Config {
     /// Allows parsing an implicit map at the root of the Rsn document.
@@ -544,6 +1063,85 @@ pub struct Config {
     pub allow_implicit_map_at_root: bool,
     /// When true, the parser will include [`EventKind::Comment`] events.
     pub include_comments: bool,
+    /// When true, the parser will continue parsing after encountering an
+    /// error.
+    ///
+    /// Each error is still returned, but the parser then skips input until it
+    /// reaches a `,`, `}`, `]`, or `)` that allows it to resume parsing.
+    /// [`EventKind::Placeholder`] events are returned in place of missing or
+    /// invalid values, and [`EventKind::EndNested`] events are returned for
+    /// nested values that were not closed. This guarantees that the returned
+    /// events are always well formed, which allows all errors in a document
+    /// to be reported at once.
+    pub recover_from_errors: bool,
+    /// The syntax being parsed.
+    ///
+    /// When set to [`Dialect::Json`], only JSON as defined by [RFC 8259] is
+    /// accepted: map keys must be strings, trailing commas and comments are
+    /// not allowed, and [`Config::allow_implicit_map_at_root`] is ignored.
+    /// `null` is parsed as the identifier `None`, allowing JSON to be
+    /// deserialized into the same types as Rsn. When deserializing, `null` is
+    /// accepted as `None` or `()`, but not as a string or an enum variant,
+    /// and it is parsed as `()` when parsing a [`Value`](crate::value::Value).
+    ///
+    /// When set to [`Dialect::Ron`], RON's syntax is accepted in addition to
+    /// Rsn's. `#![enable(...)]` attributes before the root value are parsed
+    /// into [`Parser::ron_extensions`] and returned as
+    /// [`EventKind::Comment`]s, and structures written as `Name(field: value)`
+    /// or `(field: value)` are parsed as maps.
+    ///
+    /// [RFC 8259]: https://www.rfc-editor.org/rfc/rfc8259
+    pub dialect: Dialect,
+    /// The maximum number of nested values that may be open at once.
+    ///
+    /// Code that processes nested values recursively, such as parsing a
+    /// [`Value`](crate::value::Value) or deserializing with Serde, can
+    /// overflow the stack when given deeply nested input. Exceeding this limit
+    /// returns [`ErrorKind::TooDeeplyNested`].
+    ///
+    /// Defaults to 128.
+    pub max_depth: Option<usize>,
+    /// The maximum length in bytes of a string or byte string, after escape
+    /// sequences have been decoded. Exceeding this limit returns
+    /// [`ErrorKind::StringTooLong`].
+    ///
+    /// Defaults to no limit.
+    pub max_string_length: Option<usize>,
+    /// The maximum number of values in a list or tuple, or entries in a map.
+    /// Exceeding this limit returns [`ErrorKind::CollectionTooLong`].
+    ///
+    /// Defaults to no limit.
+    pub max_collection_length: Option<usize>,
+    /// The maximum number of bytes of input that will be parsed. Exceeding
+    /// this limit returns [`ErrorKind::InputTooLarge`], after which the
+    /// parser returns no more events.
+    ///
+    /// [`Parser::from_reader`] reads at most one byte more than this limit,
+    /// which bounds the memory used to hold any single token.
+    ///
+    /// Defaults to no limit.
+    pub max_input_size: Option<usize>,
+    /// How a map containing the same key more than once is handled when
+    /// parsing a [`Value`](crate::value::Value).
+    ///
+    /// Defaults to [`DuplicateKeys::LastWins`].
+    pub duplicate_keys: DuplicateKeys,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            allow_implicit_map_at_root: false,
+            include_comments: false,
+            recover_from_errors: false,
+            dialect: Dialect::default(),
+            max_depth: Some(128),
+            max_string_length: None,
+            max_collection_length: None,
+            max_input_size: None,
+            duplicate_keys: DuplicateKeys::default(),
+        }
+    }
 }
 
 impl Config {
@@ -560,6 +1158,91 @@ impl Config {
         self.include_comments = include;
         self
     }
+
+    /// Sets [`Config::recover_from_errors`] to `recover` and returns self.
+    #[must_use]
+    pub const fn recover_from_errors(mut self, recover: bool) -> Self {
+        self.recover_from_errors = recover;
+        self
+    }
+
+    /// Sets [`Config::dialect`] to `dialect` and returns self.
+    #[must_use]
+    pub const fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Sets [`Config::max_depth`] to `max` and returns self.
+    #[must_use]
+    pub const fn max_depth(mut self, max: Option<usize>) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// Sets [`Config::max_string_length`] to `max` and returns self.
+    #[must_use]
+    pub const fn max_string_length(mut self, max: Option<usize>) -> Self {
+        self.max_string_length = max;
+        self
+    }
+
+    /// Sets [`Config::max_collection_length`] to `max` and returns self.
+    #[must_use]
+    pub const fn max_collection_length(mut self, max: Option<usize>) -> Self {
+        self.max_collection_length = max;
+        self
+    }
+
+    /// Sets [`Config::max_input_size`] to `max` and returns self.
+    #[must_use]
+    pub const fn max_input_size(mut self, max: Option<usize>) -> Self {
+        self.max_input_size = max;
+        self
+    }
+
+    /// Sets [`Config::duplicate_keys`] to `duplicate_keys` and returns self.
+    #[must_use]
+    pub const fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+}
+
+/// How a map containing the same key more than once is handled when parsing a
+/// [`Value`](crate::value::Value).
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DuplicateKeys {
+    /// The first value is kept, and later values are ignored.
+    FirstWins,
+    /// The last value is kept. The entry remains at the position where the
+    /// key first appeared.
+    #[default]
+    LastWins,
+    /// [`ErrorKind::DuplicateKey`] is returned at the repeated key.
+    Error,
+}
+
+/// The extensions enabled by a RON document's `#![enable(...)]` attributes.
+///
+/// Rsn's [`Deserializer`](crate::de::Deserializer) always accepts `Some`
+/// values without the `Some(...)` wrapper and newtype variants containing
+/// unwrapped structures, so `implicit_some` and `unwrap_variant_newtypes` are
+/// always effectively enabled. `explicit_struct_names` is accepted but not
+/// enforced.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RonExtensions {
+    /// `Some(value)` may be written as `value`.
+    pub implicit_some: bool,
+    /// Newtype structures are written as the value they contain.
+    pub unwrap_newtypes: bool,
+    /// Newtype variants containing a structure or tuple may omit the
+    /// structure's parentheses: `Variant(field: 1)`.
+    pub unwrap_variant_newtypes: bool,
+    /// Structures must be written with their names.
+    pub explicit_struct_names: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -568,6 +1251,48 @@ enum State<'s> {
     StartingImplicitMap(Event<'s>),
     ImplicitMap(MapStateExpecting),
     Finished,
+    /// [`Config::max_input_size`] was exceeded, and no more input will be
+    /// parsed.
+    InputTooLarge,
+}
+
+/// What the parser expected to parse next, used when recovering from errors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Expecting {
+    Root,
+    Value,
+    Key,
+    MapValue,
+    Separator,
+    Nothing,
+}
+
+/// A summary of a consumed token, used when recovering from errors.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum LastToken {
+    Comma,
+    Open,
+    Close(Balanced, Range<usize>),
+    Other,
+    Eof,
+}
+
+impl From<&Token<'_>> for LastToken {
+    fn from(token: &Token<'_>) -> Self {
+        match token.kind {
+            TokenKind::Comma => Self::Comma,
+            TokenKind::Open(_) => Self::Open,
+            TokenKind::Close(balanced) => Self::Close(balanced, token.location.clone()),
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The token that allowed the parser to resume after an error.
+enum Sync {
+    Comma,
+    Close(usize, Range<usize>),
+    Eof,
 }
 
 /// An error that arose while parsing Rsn events.
@@ -627,6 +1352,26 @@ pub enum ErrorKind {
     ExpectedMapOrTuple,
     /// Additional data was found after a complete value was parsed.
     TrailingData,
+    /// A RON attribute was not `#![enable(...)]` with a list of known
+    /// extensions.
+    InvalidAttribute,
+    /// A nested value was opened while [`Config::max_depth`] nested values
+    /// were already open.
+    TooDeeplyNested,
+    /// A string or byte string was longer than [`Config::max_string_length`].
+    StringTooLong,
+    /// A list, tuple, or map contained more than
+    /// [`Config::max_collection_length`] entries.
+    CollectionTooLong,
+    /// The input was larger than [`Config::max_input_size`].
+    InputTooLarge,
+    /// A map contained the same key more than once while
+    /// [`Config::duplicate_keys`] was [`DuplicateKeys::Error`].
+    DuplicateKey,
+    /// [`Config::dialect`] is not supported by the requested operation.
+    ///
+    /// [`Document`](crate::document::Document)s can only be parsed from Rsn.
+    UnsupportedDialect,
 }
 
 impl From<tokenizer::ErrorKind> for ErrorKind {
@@ -649,6 +1394,16 @@ impl Display for ErrorKind {
             ErrorKind::TrailingData => f.write_str(
                 "source contained extra trailing data after a value was completely read",
             ),
+            ErrorKind::TooDeeplyNested => f.write_str("nesting limit exceeded"),
+            ErrorKind::StringTooLong => f.write_str("string length limit exceeded"),
+            ErrorKind::CollectionTooLong => f.write_str("collection length limit exceeded"),
+            ErrorKind::InputTooLarge => f.write_str("input size limit exceeded"),
+            ErrorKind::DuplicateKey => f.write_str("map contains a duplicate key"),
+            ErrorKind::UnsupportedDialect => f.write_str("dialect is not supported"),
+            ErrorKind::InvalidAttribute => f.write_str(
+                "expected `#![enable(...)]` listing implicit_some, unwrap_newtypes, \
+                 unwrap_variant_newtypes, or explicit_struct_names",
+            ),
             ErrorKind::ExpectedMapOrTuple => {
                 f.write_str("[ is not valid for a named value, expected { or (")
             }
@@ -670,6 +1425,16 @@ impl<'s> Event<'s> {
     fn new(location: Range<usize>, kind: EventKind<'s>) -> Self {
         Self { location, kind }
     }
+
+    /// Returns an owned representation of this event, copying any borrowed
+    /// data to the heap.
+    #[must_use]
+    pub fn into_owned(self) -> Event<'static> {
+        Event {
+            location: self.location,
+            kind: self.kind.into_owned(),
+        }
+    }
 }
 
 /// A kind of an event encountered when parsing Rsn.
@@ -694,7 +1459,30 @@ pub enum EventKind<'s> {
     /// A primitive literal.
     Primitive(Primitive<'s>),
     /// A comment.
-    Comment(&'s str),
+    Comment(Cow<'s, str>),
+    /// A placeholder for a value that was missing or could not be parsed.
+    ///
+    /// This event is only returned when [`Config::recover_from_errors`] is
+    /// enabled.
+    Placeholder,
+}
+
+impl EventKind<'_> {
+    /// Returns an owned representation of this event kind, copying any
+    /// borrowed data to the heap.
+    #[must_use]
+    pub fn into_owned(self) -> EventKind<'static> {
+        match self {
+            EventKind::BeginNested { name, kind } => EventKind::BeginNested {
+                name: name.map(Name::into_owned),
+                kind,
+            },
+            EventKind::EndNested => EventKind::EndNested,
+            EventKind::Primitive(primitive) => EventKind::Primitive(primitive.into_owned()),
+            EventKind::Comment(comment) => EventKind::Comment(Cow::Owned(comment.into_owned())),
+            EventKind::Placeholder => EventKind::Placeholder,
+        }
+    }
 }
 
 /// A name/identifier.
@@ -703,14 +1491,26 @@ pub struct Name<'s> {
     /// The byte range of the name in the source.
     pub location: Range<usize>,
     /// The name/identifier.
-    pub name: &'s str,
+    pub name: Cow<'s, str>,
+}
+
+impl Name<'_> {
+    /// Returns an owned representation of this name, copying to the heap if
+    /// needed.
+    #[must_use]
+    pub fn into_owned(self) -> Name<'static> {
+        Name {
+            location: self.location,
+            name: Cow::Owned(self.name.into_owned()),
+        }
+    }
 }
 
 impl<'s> Deref for Name<'s> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        self.name
+        &self.name
     }
 }
 
@@ -738,7 +1538,7 @@ pub enum Nested {
 }
 
 impl Nested {
-    fn err_display(self) -> &'static str {
+    pub(crate) fn err_display(self) -> &'static str {
         match self {
             Nested::Tuple => "`)`",
             Nested::Map => "`}`",
@@ -762,6 +1562,8 @@ enum NestedState {
     Tuple(ListStateExpecting),
     List(ListStateExpecting),
     Map(MapStateExpecting),
+    /// A RON structure, which is a map enclosed in parentheses.
+    Struct(MapStateExpecting),
 }
 
 impl NestedState {
@@ -772,6 +1574,23 @@ impl NestedState {
             Balanced::Brace => unreachable!("Brace must receive a MapState"),
         }
     }
+
+    fn closed_by(self) -> Balanced {
+        match self {
+            Self::Tuple(_) | Self::Struct(_) => Balanced::Paren,
+            Self::List(_) => Balanced::Bracket,
+            Self::Map(_) => Balanced::Brace,
+        }
+    }
+
+    fn after_comma(self) -> Self {
+        match self {
+            Self::Tuple(_) => Self::Tuple(ListStateExpecting::Value),
+            Self::List(_) => Self::List(ListStateExpecting::Value),
+            Self::Map(_) => Self::Map(MapStateExpecting::Key),
+            Self::Struct(_) => Self::Struct(MapStateExpecting::Key),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -788,6 +1607,16 @@ enum MapStateExpecting {
     Comma,
 }
 
+impl MapStateExpecting {
+    fn expecting(self) -> Expecting {
+        match self {
+            Self::Key => Expecting::Key,
+            Self::Colon | Self::Value => Expecting::MapValue,
+            Self::Comma => Expecting::Separator,
+        }
+    }
+}
+
 /// A primitive literal.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Primitive<'s> {
@@ -802,13 +1631,33 @@ pub enum Primitive<'s> {
     /// A string literal.
     String(Cow<'s, str>),
     /// An identifier.
-    Identifier(&'s str),
+    Identifier(Cow<'s, str>),
     /// A byte string literal.
     Bytes(Cow<'s, [u8]>),
 }
 
+impl Primitive<'_> {
+    /// Returns an owned representation of this primitive, copying any borrowed
+    /// data to the heap.
+    #[must_use]
+    pub fn into_owned(self) -> Primitive<'static> {
+        match self {
+            Primitive::Bool(value) => Primitive::Bool(value),
+            Primitive::Integer(value) => Primitive::Integer(value),
+            Primitive::Float(value) => Primitive::Float(value),
+            Primitive::Char(value) => Primitive::Char(value),
+            Primitive::String(value) => Primitive::String(Cow::Owned(value.into_owned())),
+            Primitive::Identifier(value) => Primitive::Identifier(Cow::Owned(value.into_owned())),
+            Primitive::Bytes(value) => Primitive::Bytes(Cow::Owned(value.into_owned())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::string::String;
+    use core::fmt::Write;
+
     use super::*;
     #[test]
     fn number_array() {
@@ -945,12 +1794,18 @@ mod tests {
                         kind: Nested::Map
                     }
                 ),
-                Event::new(1..2, EventKind::Primitive(Primitive::Identifier("a"))),
+                Event::new(
+                    1..2,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("a")))
+                ),
                 Event::new(
                     3..4,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(1)))
                 ),
-                Event::new(5..6, EventKind::Primitive(Primitive::Identifier("b"))),
+                Event::new(
+                    5..6,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("b")))
+                ),
                 Event::new(
                     7..8,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(2)))
@@ -971,12 +1826,18 @@ mod tests {
                         kind: Nested::Map
                     }
                 ),
-                Event::new(1..2, EventKind::Primitive(Primitive::Identifier("a"))),
+                Event::new(
+                    1..2,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("a")))
+                ),
                 Event::new(
                     3..4,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(1)))
                 ),
-                Event::new(5..6, EventKind::Primitive(Primitive::Identifier("b"))),
+                Event::new(
+                    5..6,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("b")))
+                ),
                 Event::new(
                     7..8,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(2)))
@@ -997,7 +1858,7 @@ mod tests {
         assert_eq!(
             &events,
             &[
-                Event::new(0..4, EventKind::Comment("/**/")),
+                Event::new(0..4, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(
                     4..5,
                     EventKind::BeginNested {
@@ -1005,19 +1866,25 @@ mod tests {
                         kind: Nested::Map
                     }
                 ),
-                Event::new(5..9, EventKind::Comment("/**/")),
-                Event::new(9..10, EventKind::Primitive(Primitive::Identifier("a"))),
-                Event::new(10..14, EventKind::Comment("/**/")),
-                Event::new(15..19, EventKind::Comment("/**/")),
+                Event::new(5..9, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(
+                    9..10,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("a")))
+                ),
+                Event::new(10..14, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(15..19, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(
                     19..20,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(1)))
                 ),
-                Event::new(20..24, EventKind::Comment("/**/")),
-                Event::new(25..29, EventKind::Comment("/**/")),
-                Event::new(29..30, EventKind::Primitive(Primitive::Identifier("b"))),
-                Event::new(30..34, EventKind::Comment("/**/")),
-                Event::new(35..39, EventKind::Comment("/**/")),
+                Event::new(20..24, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(25..29, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(
+                    29..30,
+                    EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("b")))
+                ),
+                Event::new(30..34, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(35..39, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(
                     39..40,
                     EventKind::BeginNested {
@@ -1025,22 +1892,22 @@ mod tests {
                         kind: Nested::List
                     }
                 ),
-                Event::new(40..44, EventKind::Comment("/**/")),
+                Event::new(40..44, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(
                     44..45,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(2)))
                 ),
-                Event::new(45..49, EventKind::Comment("/**/")),
-                Event::new(50..54, EventKind::Comment("/**/")),
+                Event::new(45..49, EventKind::Comment(Cow::Borrowed("/**/"))),
+                Event::new(50..54, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(
                     54..55,
                     EventKind::Primitive(Primitive::Integer(Integer::Usize(3)))
                 ),
-                Event::new(55..59, EventKind::Comment("/**/")),
+                Event::new(55..59, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(59..60, EventKind::EndNested),
-                Event::new(60..64, EventKind::Comment("/**/")),
+                Event::new(60..64, EventKind::Comment(Cow::Borrowed("/**/"))),
                 Event::new(64..65, EventKind::EndNested),
-                Event::new(65..69, EventKind::Comment("/**/")),
+                Event::new(65..69, EventKind::Comment(Cow::Borrowed("/**/"))),
             ]
         );
     }
@@ -1053,4 +1920,436 @@ mod tests {
             .unwrap_err();
         assert_eq!(err, Error::new(3..4, ErrorKind::ExpectedMapOrTuple));
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader() {
+        let source = "Named{a: [1, \"two\", r#three], /* comment */ b: (b\"four\", 'c')}";
+        let expected = Parser::new(source, Config::default().include_comments(true))
+            .map(|event| event.map(Event::into_owned))
+            .collect::<Vec<_>>();
+        let events =
+            Parser::from_reader(source.as_bytes(), Config::default().include_comments(true))
+                .collect::<Vec<_>>();
+        assert_eq!(events, expected);
+
+        let err = Parser::from_reader(&b"[1, 2"[..], Config::default())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            err,
+            Error::new(5..5, ErrorKind::ExpectedCommaOrEnd(Nested::List))
+        );
+    }
+
+    #[test]
+    fn recovery() {
+        let (events, errors) = Parser::parse_recovering(
+            "{a: [1, , 3 4], b, c: (1, @), d: {e: 1]}",
+            Config::default(),
+        );
+        assert_eq!(
+            errors,
+            &[
+                Error::new(8..9, ErrorKind::ExpectedValue),
+                Error::new(12..13, ErrorKind::ExpectedCommaOrEnd(Nested::List)),
+                Error::new(17..18, ErrorKind::ExpectedColon),
+                Error::new(
+                    26..27,
+                    ErrorKind::Tokenizer(tokenizer::ErrorKind::Unexpected('@'))
+                ),
+                Error::new(38..39, ErrorKind::ExpectedCommaOrEnd(Nested::Map)),
+                Error::new(40..40, ErrorKind::ExpectedCommaOrEnd(Nested::Map)),
+            ]
+        );
+        let kinds = events
+            .into_iter()
+            .map(|event| event.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            &[
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Map
+                },
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("a"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::List
+                },
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(1))),
+                EventKind::Placeholder,
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(3))),
+                EventKind::EndNested,
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("b"))),
+                EventKind::Placeholder,
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("c"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Tuple
+                },
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(1))),
+                EventKind::Placeholder,
+                EventKind::EndNested,
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("d"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Map
+                },
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("e"))),
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(1))),
+                EventKind::EndNested,
+                EventKind::EndNested,
+            ]
+        );
+    }
+
+    #[test]
+    fn recovery_at_eof() {
+        let (events, errors) = Parser::parse_recovering("[{a: 1, b:", Config::default());
+        assert_eq!(errors, &[Error::new(10..10, ErrorKind::ExpectedValue)]);
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[5].kind, EventKind::Placeholder);
+        assert_eq!(events[6], Event::new(10..10, EventKind::EndNested));
+        assert_eq!(events[7], Event::new(10..10, EventKind::EndNested));
+
+        let (events, errors) = Parser::parse_recovering("} [1]", Config::default());
+        assert_eq!(errors, &[Error::new(0..1, ErrorKind::ExpectedValue)]);
+        assert_eq!(events, &[Event::new(0..1, EventKind::Placeholder)]);
+    }
+
+    #[test]
+    fn json() {
+        #[track_caller]
+        fn assert_error(source: &str, location: Range<usize>, kind: ErrorKind) {
+            let err = Parser::new(source, Config::default().dialect(Dialect::Json))
+                .find_map(Result::err)
+                .unwrap();
+            assert_eq!(err, Error::new(location, kind));
+        }
+
+        let config = Config::default()
+            .dialect(Dialect::Json)
+            .allow_implicit_map_at_root(true);
+        let events = Parser::new("{\"a\": [null, true], \"b\": {}}", config)
+            .map(|event| event.map(|event| event.kind))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            &events,
+            &[
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Map
+                },
+                EventKind::Primitive(Primitive::String(Cow::Borrowed("a"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::List
+                },
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("None"))),
+                EventKind::Primitive(Primitive::Bool(true)),
+                EventKind::EndNested,
+                EventKind::Primitive(Primitive::String(Cow::Borrowed("b"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Map
+                },
+                EventKind::EndNested,
+                EventKind::EndNested,
+            ]
+        );
+
+        assert_error("[1, 2,]", 6..7, ErrorKind::ExpectedValue);
+        assert_error("{\"a\": 1,}", 8..9, ErrorKind::ExpectedKey);
+        assert_error("{true: 1}", 1..5, ErrorKind::ExpectedKey);
+        assert_error("{1: 1}", 1..2, ErrorKind::ExpectedKey);
+        assert_error(
+            "a: 1",
+            0..1,
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::Unexpected('a')),
+        );
+        assert_error(
+            "[1 /* two */]",
+            3..4,
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::Unexpected('/')),
+        );
+        assert_error("null null", 5..9, ErrorKind::TrailingData);
+    }
+
+    #[test]
+    fn ron() {
+        let config = Config::default()
+            .dialect(Dialect::Ron)
+            .include_comments(true);
+        let mut parser = Parser::new(
+            "#![enable(unwrap_newtypes,)] Named(a: (/**/ b: 1), c: (d, 2))",
+            config,
+        );
+        let events = parser
+            .by_ref()
+            .map(|event| event.map(|event| event.kind))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            parser.ron_extensions(),
+            RonExtensions {
+                unwrap_newtypes: true,
+                ..RonExtensions::default()
+            }
+        );
+        assert_eq!(
+            &events,
+            &[
+                EventKind::Comment(Cow::Borrowed("#![enable(unwrap_newtypes,)]")),
+                EventKind::BeginNested {
+                    name: Some(Name {
+                        location: 29..34,
+                        name: Cow::Borrowed("Named")
+                    }),
+                    kind: Nested::Map
+                },
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("a"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Map
+                },
+                EventKind::Comment(Cow::Borrowed("/**/")),
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("b"))),
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(1))),
+                EventKind::EndNested,
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("c"))),
+                EventKind::BeginNested {
+                    name: None,
+                    kind: Nested::Tuple
+                },
+                EventKind::Primitive(Primitive::Identifier(Cow::Borrowed("d"))),
+                EventKind::Primitive(Primitive::Integer(Integer::Usize(2))),
+                EventKind::EndNested,
+                EventKind::EndNested,
+            ]
+        );
+
+        let err = Parser::new("(a: 1 b: 2)", config)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            err,
+            Error::new(6..7, ErrorKind::ExpectedCommaOrEnd(Nested::Map))
+        );
+        let err = Parser::new("#![allow(dead_code)] ()", config)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err, Error::new(0..20, ErrorKind::InvalidAttribute));
+        // Rsn doesn't allow attributes.
+        assert!(!Parser::validate(
+            "#![enable(implicit_some)] ()",
+            Config::default()
+        ));
+    }
+
+    /// A deterministic pseudo-random number generator for generating inputs.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, max: usize) -> usize {
+            // xorshift64
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            usize::try_from(self.0 % max as u64).unwrap()
+        }
+    }
+
+    #[test]
+    fn depth_limit() {
+        const OPENERS: [(&str, usize, &str); 5] = [
+            ("[", 0, "]"),
+            ("(", 0, ")"),
+            ("{a: ", 0, "}"),
+            ("Name(", 4, ")"),
+            ("Name {b: ", 5, "}"),
+        ];
+        let mut rng = Rng(0x5EED);
+        for _ in 0..500 {
+            let depth = rng.below(200);
+            let max = rng.below(200);
+            let mut source = String::new();
+            let mut opened = Vec::new();
+            for _ in 0..depth {
+                let (open, offset, close) = OPENERS[rng.below(OPENERS.len())];
+                opened.push((source.len() + offset, close));
+                source.push_str(open);
+            }
+            source.push('1');
+            for (_, close) in opened.iter().rev() {
+                source.push_str(close);
+            }
+
+            let result = Parser::new(&source, Config::default().max_depth(Some(max)))
+                .collect::<Result<Vec<_>, _>>();
+            if depth > max {
+                let start = opened[max].0;
+                assert_eq!(
+                    result,
+                    Err(Error::new(start..start + 1, ErrorKind::TooDeeplyNested)),
+                    "{source}"
+                );
+            } else {
+                assert!(result.is_ok(), "{source}");
+            }
+        }
+
+        // The default limit prevents recursive parsing from overflowing the
+        // stack.
+        let err =
+            crate::value::Value::from_str(&"[".repeat(100_000), Config::default()).unwrap_err();
+        assert_eq!(err, Error::new(128..129, ErrorKind::TooDeeplyNested));
+    }
+
+    #[test]
+    fn string_length_limit() {
+        const PIECES: [(&str, usize); 5] =
+            [("a", 1), ("é", 2), ("\\n", 1), ("\\u{1F980}", 4), ("🦀", 4)];
+        const BYTE_PIECES: [(&str, usize); 3] = [("a", 1), ("\\x00", 1), ("\\n", 1)];
+        let mut rng = Rng(0x00C0_FFEE);
+        for _ in 0..500 {
+            let (prefix, pieces) = if rng.below(2) == 0 {
+                ("\"", &PIECES[..])
+            } else {
+                ("b\"", &BYTE_PIECES[..])
+            };
+            let mut source = String::from("[1, ");
+            let start = source.len();
+            source.push_str(prefix);
+            let mut length = 0;
+            for _ in 0..rng.below(20) {
+                let (piece, piece_length) = pieces[rng.below(pieces.len())];
+                source.push_str(piece);
+                length += piece_length;
+            }
+            source.push('"');
+            let end = source.len();
+            source.push(']');
+            let max = rng.below(30);
+
+            let result = Parser::new(&source, Config::default().max_string_length(Some(max)))
+                .collect::<Result<Vec<_>, _>>();
+            if length > max {
+                assert_eq!(
+                    result,
+                    Err(Error::new(start..end, ErrorKind::StringTooLong)),
+                    "{source}"
+                );
+            } else {
+                assert!(result.is_ok(), "{source}");
+            }
+        }
+    }
+
+    /// Returns a random document containing a collection with a random number
+    /// of entries, and the locations of the first token of each entry.
+    fn random_collection(rng: &mut Rng) -> (String, Vec<Range<usize>>) {
+        let (open, close, is_map) = [
+            ("[", "]", false),
+            ("(", ")", false),
+            ("{", "}", true),
+            ("Name {", "}", true),
+            ("", "", true),
+        ][rng.below(5)];
+        let mut source = String::from(open);
+        let mut entries = Vec::new();
+        let count = rng.below(20);
+        for index in 0..count {
+            if index > 0 {
+                source.push_str([",", ", ", "\n"][rng.below(if open.is_empty() { 3 } else { 2 })]);
+            }
+            let start = source.len();
+            if is_map {
+                write!(source, "key{index}").unwrap();
+                entries.push(start..source.len());
+                source.push_str(": ");
+            }
+            source.push_str(["1", "\"two\"", "[3]"][rng.below(3)]);
+            if !is_map {
+                entries.push(start..start + 1);
+                if source.ends_with('"') {
+                    entries.last_mut().unwrap().end = start + 5;
+                }
+            }
+        }
+        if count > 0 && rng.below(2) == 0 {
+            source.push(',');
+        }
+        source.push_str(close);
+        (source, entries)
+    }
+
+    #[test]
+    fn collection_length_limit() {
+        let mut rng = Rng(0xBAD_C0DE);
+        for _ in 0..500 {
+            let (source, entries) = random_collection(&mut rng);
+            let max = rng.below(20);
+
+            let result = Parser::new(
+                &source,
+                Config::default()
+                    .allow_implicit_map_at_root(true)
+                    .max_collection_length(Some(max)),
+            )
+            .collect::<Result<Vec<_>, _>>();
+            if entries.len() > max {
+                assert_eq!(
+                    result,
+                    Err(Error::new(
+                        entries[max].clone(),
+                        ErrorKind::CollectionTooLong
+                    )),
+                    "{source}"
+                );
+            } else {
+                assert!(result.is_ok(), "{source}");
+            }
+        }
+    }
+
+    #[test]
+    fn input_size_limit() {
+        let mut rng = Rng(0xFEED);
+        for _ in 0..500 {
+            let (mut source, _) = random_collection(&mut rng);
+            source.push_str(["", " ", "\n// comment\n"][rng.below(3)]);
+            let max = rng.below(source.len() + 5);
+            let config = Config::default()
+                .allow_implicit_map_at_root(true)
+                .max_input_size(Some(max));
+
+            let events = Parser::new(&source, config).collect::<Vec<_>>();
+            #[cfg(feature = "std")]
+            assert_eq!(
+                Parser::from_reader(source.as_bytes(), config)
+                    .map(|result| result.map_err(|err| err.kind))
+                    .last(),
+                events
+                    .last()
+                    .cloned()
+                    .map(|result| result.map(Event::into_owned).map_err(|err| err.kind)),
+                "{source}"
+            );
+            if source.len() > max {
+                let err = events.last().unwrap().as_ref().unwrap_err();
+                assert_eq!(err.kind, ErrorKind::InputTooLarge, "{source}");
+                assert_eq!(err.location.start, max, "{source}");
+                assert_eq!(
+                    events.iter().filter(|result| result.is_err()).count(),
+                    1,
+                    "{source}"
+                );
+            } else {
+                assert!(events.iter().all(Result::is_ok), "{source}");
+            }
+        }
+    }
 }
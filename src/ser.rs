@@ -1,6 +1,7 @@
 use alloc::borrow::Cow;
-use alloc::string::String;
-use core::fmt::Write;
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Write};
+use core::mem;
 
 use serde::ser::{
     SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
@@ -8,14 +9,24 @@ use serde::ser::{
 };
 use serde::Serialize;
 
+use crate::de::raw;
+use crate::value::{self, List, Map, Named, StructContents, ToValueError, Value};
 use crate::writer::{self, Writer};
 
 /// A Serde serializer that generates Rsn.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Serializer<'config, Output> {
     writer: Writer<'config, Output>,
     implicit_map_at_root: bool,
     anonymous_structs: bool,
+    comments: Option<CommentProvider>,
+    expecting_raw_value: bool,
+    starting_implicit_map_value: bool,
+    writing_implicit_map_value: bool,
+    entry_type_comment_written: bool,
+    strict: bool,
+    serializing_key: bool,
 }
 
 impl Default for Serializer<'static, String> {
@@ -24,6 +35,13 @@ impl Default for Serializer<'static, String> {
             writer: Writer::default(),
             implicit_map_at_root: false,
             anonymous_structs: false,
+            comments: None,
+            expecting_raw_value: false,
+            starting_implicit_map_value: false,
+            writing_implicit_map_value: false,
+            entry_type_comment_written: false,
+            strict: false,
+            serializing_key: false,
         }
     }
 }
@@ -38,6 +56,13 @@ where
             writer: Writer::new(output, &configuration.writer),
             implicit_map_at_root: configuration.implicit_map_at_root,
             anonymous_structs: configuration.anonymous_structs,
+            comments: configuration.comments,
+            expecting_raw_value: false,
+            starting_implicit_map_value: false,
+            writing_implicit_map_value: false,
+            entry_type_comment_written: false,
+            strict: configuration.strict,
+            serializing_key: false,
         }
     }
 
@@ -48,6 +73,232 @@ where
 
     fn mark_value_seen(&mut self) {
         self.implicit_map_at_root = false;
+        // Only the entry's value itself is written after the implicit map's
+        // `key: `, not the values nested within it.
+        self.writing_implicit_map_value = mem::take(&mut self.starting_implicit_map_value);
+    }
+
+    /// Returns the comment for the type or enum variant `name`, or for its
+    /// `field`.
+    fn comment(&self, name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+        self.comments.and_then(|comments| comments(name, field))
+    }
+
+    /// Writes the comment for the type or enum variant `name` before its
+    /// value. If a comment can't be written before the value, such as when
+    /// the value belongs to a map entry, the comment is returned so that it
+    /// can be written inside of the value instead.
+    fn write_type_comment(&mut self, name: &str) -> Result<Option<Cow<'static, str>>, Error> {
+        if mem::take(&mut self.entry_type_comment_written) {
+            // The comment was written before the map entry.
+            return Ok(None);
+        }
+        let Some(comment) = self.comment(name, None) else {
+            return Ok(None);
+        };
+        if self.writer.can_write_comment() && !self.writing_implicit_map_value {
+            self.writer.write_comment(&comment)?;
+            Ok(None)
+        } else {
+            Ok(Some(comment))
+        }
+    }
+
+    /// Writes the type comment of `value`, which is the value of a map entry,
+    /// before the entry's key. This keeps the comment from being mistaken for
+    /// the comment of the value's first field.
+    ///
+    /// Returns true if a comment was written.
+    fn write_entry_type_comment<T>(&mut self, value: &T) -> Result<bool, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.comments.is_none() {
+            return Ok(false);
+        }
+        match value.serialize(TypeNameProbe) {
+            Err(TypeName(name)) => self.write_entry_type_comment_for(name),
+            Ok(()) => Ok(false),
+        }
+    }
+
+    /// Writes the comment for the type or enum variant `name` of a map
+    /// entry's value before the entry's key, returning true if a comment was
+    /// written.
+    fn write_entry_type_comment_for(&mut self, name: Option<&str>) -> Result<bool, Error> {
+        match name.and_then(|name| self.comment(name, None)) {
+            Some(comment) => {
+                self.writer.write_comment(&comment)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn write_inner_comment(&mut self, comment: Option<Cow<'static, str>>) -> Result<(), Error> {
+        if let Some(comment) = comment {
+            self.writer.write_comment(&comment)?;
+        }
+        Ok(())
+    }
+
+    /// Serializes `value` as the value of an implicit map's entry.
+    fn serialize_implicit_map_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.write_implicit_map_value(|serializer| value.serialize(serializer))
+    }
+
+    /// Writes the value of an implicit map's entry using `write`.
+    fn write_implicit_map_value(
+        &mut self,
+        write: impl FnOnce(&mut Self) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.writer.write_raw_value(": ")?;
+        self.starting_implicit_map_value = true;
+        let result = write(&mut *self);
+        self.starting_implicit_map_value = false;
+        self.writing_implicit_map_value = false;
+        result?;
+        Ok(self.writer.insert_newline()?)
+    }
+
+    /// Writes `value`, which was serialized from a [`Value`].
+    ///
+    /// Nested values are checked, named, and commented in the same way as
+    /// the types they were serialized from. Named values containing fields
+    /// are written like structures.
+    fn serialize_value(&mut self, value: &Value<'_>) -> Result<(), Error> {
+        let is_implicit_map = self.implicit_map_at_root;
+        self.mark_value_seen();
+        match value {
+            Value::Named(Named {
+                name,
+                contents: StructContents::Map(map),
+            }) => {
+                self.check_key()?;
+                let comment = self.write_type_comment(name)?;
+                if !is_implicit_map {
+                    if self.anonymous_structs {
+                        self.writer.begin_map()?;
+                    } else {
+                        self.writer.begin_named_map(name)?;
+                    }
+                }
+                self.write_inner_comment(comment)?;
+                self.serialize_value_entries(map, Some(name), is_implicit_map)
+            }
+            Value::Map(map) => {
+                self.check_key()?;
+                if !is_implicit_map {
+                    self.writer.begin_map()?;
+                }
+                self.serialize_value_entries(map, None, is_implicit_map)
+            }
+            Value::Named(Named {
+                name,
+                contents: StructContents::Tuple(list),
+            }) => {
+                self.check_key()?;
+                let comment = self.write_type_comment(name)?;
+                self.writer.begin_named_tuple(name)?;
+                self.write_inner_comment(comment)?;
+                self.serialize_value_elements(list)
+            }
+            Value::Tuple(list) => {
+                self.check_key()?;
+                self.writer.begin_tuple()?;
+                self.serialize_value_elements(list)
+            }
+            Value::Array(list) => {
+                self.check_key()?;
+                self.writer.begin_list()?;
+                self.serialize_value_elements(list)
+            }
+            Value::Identifier(name) => {
+                self.write_type_comment(name)?;
+                Ok(self.writer.write_value(value)?)
+            }
+            Value::Float(float) => {
+                self.check_float(float.is_finite())?;
+                Ok(self.writer.write_value(value)?)
+            }
+            _ => Ok(self.writer.write_value(value)?),
+        }
+    }
+
+    /// Writes the elements of a tuple or list that was serialized from a
+    /// [`Value`], and then finishes the tuple or list.
+    fn serialize_value_elements(&mut self, list: &List<'_>) -> Result<(), Error> {
+        for value in &list.0 {
+            self.serialize_value(value)?;
+        }
+        Ok(self.writer.finish_nested()?)
+    }
+
+    /// Writes the entries of a map that was serialized from a [`Value`], and
+    /// then finishes the map. When `name` is set, identifier keys are treated
+    /// as the fields of the structure `name`.
+    fn serialize_value_entries(
+        &mut self,
+        map: &Map<'_>,
+        name: Option<&str>,
+        is_implicit_map: bool,
+    ) -> Result<(), Error> {
+        for (key, value) in map {
+            if let (Some(name), Value::Identifier(field)) = (name, key) {
+                if let Some(comment) = self.comment(name, Some(field)) {
+                    self.writer.write_comment(&comment)?;
+                }
+            }
+            let type_comment = self.write_entry_type_comment_for(match value {
+                Value::Named(Named { name, .. }) | Value::Identifier(name) => Some(name),
+                _ => None,
+            })?;
+
+            if let Value::Identifier(_) = key {
+                // Identifiers are written without looking up their comments,
+                // like the fields of a structure.
+                self.writer.write_value(key)?;
+            } else {
+                self.serializing_key = is_implicit_map || self.strict;
+                let result = self.serialize_value(key);
+                self.serializing_key = false;
+                result?;
+            }
+
+            self.entry_type_comment_written = type_comment;
+            if is_implicit_map {
+                self.write_implicit_map_value(|serializer| serializer.serialize_value(value))?;
+            } else {
+                self.serialize_value(value)?;
+            }
+        }
+        if !is_implicit_map {
+            self.writer.finish_nested()?;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if a map key is being serialized and keys are
+    /// restricted to primitive values.
+    fn check_key(&self) -> Result<(), Error> {
+        if self.serializing_key {
+            Err(Error::new(ErrorKind::UnsupportedKey))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns an error if `is_finite` is false and this serializer is
+    /// strict.
+    fn check_float(&self, is_finite: bool) -> Result<(), Error> {
+        if self.strict && !is_finite {
+            Err(Error::new(ErrorKind::NonFiniteFloat))
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -55,105 +306,112 @@ impl<'a, 'config, Output> serde::Serializer for &'a mut Serializer<'config, Outp
 where
     Output: Write,
 {
-    type Error = core::fmt::Error;
+    type Error = Error;
     type Ok = ();
     type SerializeMap = sealed::MapSerializer<'a, 'config, Output>;
     type SerializeSeq = Self;
     type SerializeStruct = sealed::MapSerializer<'a, 'config, Output>;
-    type SerializeStructVariant = Self;
+    type SerializeStructVariant = sealed::MapSerializer<'a, 'config, Output>;
     type SerializeTuple = Self;
     type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Self;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.check_float(v.is_finite())?;
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.check_float(v.is_finite())?;
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(&v)
+        Ok(self.writer.write_primitive(&v)?)
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(v)
+        if core::mem::take(&mut self.expecting_raw_value) {
+            raw::validate(v).map_err(|err| Error::new(ErrorKind::InvalidRawValue(err)))?;
+            return Ok(self.writer.write_raw_value(v)?);
+        }
+        Ok(self.writer.write_primitive(v)?)
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_primitive(v)
+        Ok(self.writer.write_primitive(v)?)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_raw_value("None")
+        Ok(self.writer.write_raw_value("None")?)
     }
 
     fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
         T: serde::Serialize + ?Sized,
     {
+        self.check_key()?;
         self.mark_value_seen();
         self.writer.begin_named_tuple("Some")?;
         value.serialize(&mut *self)?;
@@ -162,13 +420,15 @@ where
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.check_key()?;
         self.mark_value_seen();
-        self.writer.write_raw_value("()")
+        Ok(self.writer.write_raw_value("()")?)
     }
 
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_raw_value(name)
+        self.write_type_comment(name)?;
+        Ok(self.writer.write_raw_value(name)?)
     }
 
     fn serialize_unit_variant(
@@ -178,7 +438,8 @@ where
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
         self.mark_value_seen();
-        self.writer.write_raw_value(variant)
+        self.write_type_comment(variant)?;
+        Ok(self.writer.write_raw_value(variant)?)
     }
 
     fn serialize_newtype_struct<T>(
@@ -189,10 +450,24 @@ where
     where
         T: serde::Serialize + ?Sized,
     {
-        self.mark_value_seen();
+        self.check_key()?;
+        if name != value::NAME {
+            self.mark_value_seen();
+        }
+        if name == raw::NAME {
+            self.expecting_raw_value = true;
+            let result = value.serialize(&mut *self);
+            self.expecting_raw_value = false;
+            return result;
+        } else if name == value::NAME {
+            let value = Value::from_serialize(value)?;
+            return self.serialize_value(&value);
+        }
+        let comment = self.write_type_comment(name)?;
         self.writer.begin_named_tuple(name)?;
+        self.write_inner_comment(comment)?;
         value.serialize(&mut *self)?;
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
     }
 
     fn serialize_newtype_variant<T>(
@@ -205,19 +480,24 @@ where
     where
         T: serde::Serialize + ?Sized,
     {
+        self.check_key()?;
         self.mark_value_seen();
+        let comment = self.write_type_comment(variant)?;
         self.writer.begin_named_tuple(variant)?;
+        self.write_inner_comment(comment)?;
         value.serialize(&mut *self)?;
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.check_key()?;
         self.mark_value_seen();
         self.writer.begin_list()?;
         Ok(self)
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.check_key()?;
         self.mark_value_seen();
         self.writer.begin_tuple()?;
         Ok(self)
@@ -228,8 +508,11 @@ where
         name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.check_key()?;
         self.mark_value_seen();
+        let comment = self.write_type_comment(name)?;
         self.writer.begin_named_tuple(name)?;
+        self.write_inner_comment(comment)?;
         Ok(self)
     }
 
@@ -240,12 +523,16 @@ where
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.check_key()?;
         self.mark_value_seen();
+        let comment = self.write_type_comment(variant)?;
         self.writer.begin_named_tuple(variant)?;
+        self.write_inner_comment(comment)?;
         Ok(self)
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.check_key()?;
         let is_implicit_map = self.implicit_map_at_root;
         self.mark_value_seen();
         if !is_implicit_map {
@@ -253,6 +540,7 @@ where
         }
         Ok(sealed::MapSerializer {
             serializer: self,
+            name: None,
             is_implicit_map,
         })
     }
@@ -262,9 +550,11 @@ where
         name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.check_key()?;
         let is_implicit_map = self.implicit_map_at_root;
         self.mark_value_seen();
 
+        let comment = self.write_type_comment(name)?;
         if !is_implicit_map {
             if self.anonymous_structs {
                 self.writer.begin_map()?;
@@ -272,9 +562,11 @@ where
                 self.writer.begin_named_map(name)?;
             }
         }
+        self.write_inner_comment(comment)?;
 
         Ok(sealed::MapSerializer {
             serializer: self,
+            name: Some(name),
             is_implicit_map,
         })
     }
@@ -286,8 +578,16 @@ where
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.check_key()?;
+        self.mark_value_seen();
+        let comment = self.write_type_comment(variant)?;
         self.writer.begin_named_map(variant)?;
-        Ok(self)
+        self.write_inner_comment(comment)?;
+        Ok(sealed::MapSerializer {
+            serializer: self,
+            name: Some(variant),
+            is_implicit_map: false,
+        })
     }
 }
 
@@ -295,7 +595,7 @@ impl<'a, 'config, Output> SerializeSeq for &'a mut Serializer<'config, Output>
 where
     Output: Write,
 {
-    type Error = core::fmt::Error;
+    type Error = Error;
     type Ok = ();
 
     fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -306,7 +606,7 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
     }
 }
 
@@ -314,7 +614,7 @@ impl<'a, 'config, Output> SerializeTuple for &'a mut Serializer<'config, Output>
 where
     Output: Write,
 {
-    type Error = core::fmt::Error;
+    type Error = Error;
     type Ok = ();
 
     fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -325,7 +625,7 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
     }
 }
 
@@ -333,7 +633,7 @@ impl<'a, 'config, Output> SerializeTupleStruct for &'a mut Serializer<'config, O
 where
     Output: Write,
 {
-    type Error = core::fmt::Error;
+    type Error = Error;
     type Ok = ();
 
     fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -344,7 +644,7 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
     }
 }
 
@@ -352,7 +652,7 @@ impl<'a, 'config, Output> SerializeTupleVariant for &'a mut Serializer<'config,
 where
     Output: Write,
 {
-    type Error = core::fmt::Error;
+    type Error = Error;
     type Ok = ();
 
     fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -363,15 +663,179 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.finish_nested()
+        Ok(self.writer.finish_nested()?)
+    }
+}
+
+/// A serializer that stops at the first call, returning the name of the
+/// structure or enum variant being serialized as its error.
+struct TypeNameProbe;
+
+/// The result of serializing with [`TypeNameProbe`].
+#[derive(Debug)]
+struct TypeName(Option<&'static str>);
+
+impl serde::ser::Error for TypeName {
+    fn custom<T>(_msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(None)
+    }
+}
+
+impl serde::ser::StdError for TypeName {}
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0.unwrap_or("unnamed value"))
+    }
+}
+
+macro_rules! unnamed {
+    ($($method:ident($($arg:ty),*)),+) => {
+        $(fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+            Err(TypeName(None))
+        })+
+    };
+}
+
+impl serde::Serializer for TypeNameProbe {
+    type Error = TypeName;
+    type Ok = ();
+    type SerializeMap = serde::ser::Impossible<(), TypeName>;
+    type SerializeSeq = serde::ser::Impossible<(), TypeName>;
+    type SerializeStruct = serde::ser::Impossible<(), TypeName>;
+    type SerializeStructVariant = serde::ser::Impossible<(), TypeName>;
+    type SerializeTuple = serde::ser::Impossible<(), TypeName>;
+    type SerializeTupleStruct = serde::ser::Impossible<(), TypeName>;
+    type SerializeTupleVariant = serde::ser::Impossible<(), TypeName>;
+
+    unnamed!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit()
+    );
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Err(TypeName(None))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(TypeName(Some(name)))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(TypeName(Some(variant)))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        if name == raw::NAME || name == value::NAME {
+            Err(TypeName(None))
+        } else {
+            Err(TypeName(Some(name)))
+        }
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Err(TypeName(Some(variant)))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(TypeName(None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(TypeName(None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(TypeName(Some(name)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(TypeName(Some(variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(TypeName(None))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(TypeName(Some(name)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(TypeName(Some(variant)))
     }
 }
 
 mod sealed {
-    use super::{SerializeMap, SerializeStruct, SerializeStructVariant, Serializer, Write};
+    use super::{Error, SerializeMap, SerializeStruct, SerializeStructVariant, Serializer, Write};
 
     pub struct MapSerializer<'a, 'config, Output> {
         pub serializer: &'a mut Serializer<'config, Output>,
+        /// The name of the structure or enum variant being serialized.
+        pub name: Option<&'static str>,
         pub is_implicit_map: bool,
     }
 
@@ -379,23 +843,27 @@ mod sealed {
     where
         Output: Write,
     {
-        type Error = core::fmt::Error;
+        type Error = Error;
         type Ok = ();
 
         fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
         where
             T: serde::Serialize + ?Sized,
         {
+            if let Some(comment) = self
+                .name
+                .and_then(|name| self.serializer.comment(name, Some(key)))
+            {
+                self.serializer.writer.write_comment(&comment)?;
+            }
+            let type_comment = self.serializer.write_entry_type_comment(value)?;
+            self.serializer.writer.write_raw_value(key)?;
+            self.serializer.entry_type_comment_written = type_comment;
             if self.is_implicit_map {
-                self.serializer.writer.write_raw_value(key)?;
-                self.serializer.writer.write_raw_value(": ")?;
-                value.serialize(&mut *self.serializer)?;
-                self.serializer.writer.insert_newline()?;
+                self.serializer.serialize_implicit_map_value(value)
             } else {
-                self.serializer.writer.write_raw_value(key)?;
-                value.serialize(&mut *self.serializer)?;
+                value.serialize(&mut *self.serializer)
             }
-            Ok(())
         }
 
         fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -406,23 +874,22 @@ mod sealed {
         }
     }
 
-    impl<'a, 'config, Output> SerializeStructVariant for &'a mut Serializer<'config, Output>
+    impl<'a, 'config, Output> SerializeStructVariant for MapSerializer<'a, 'config, Output>
     where
         Output: Write,
     {
-        type Error = core::fmt::Error;
+        type Error = Error;
         type Ok = ();
 
         fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
         where
             T: serde::Serialize + ?Sized,
         {
-            self.writer.write_raw_value(key)?;
-            value.serialize(&mut **self)
+            SerializeStruct::serialize_field(self, key, value)
         }
 
         fn end(self) -> Result<Self::Ok, Self::Error> {
-            self.writer.finish_nested()
+            SerializeStruct::end(self)
         }
     }
 
@@ -430,14 +897,28 @@ mod sealed {
     where
         Output: Write,
     {
-        type Error = core::fmt::Error;
+        type Error = Error;
         type Ok = ();
 
+        fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+        where
+            K: serde::Serialize + ?Sized,
+            V: serde::Serialize + ?Sized,
+        {
+            let type_comment = self.serializer.write_entry_type_comment(value)?;
+            self.serialize_key(key)?;
+            self.serializer.entry_type_comment_written = type_comment;
+            self.serialize_value(value)
+        }
+
         fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
         where
             T: serde::Serialize + ?Sized,
         {
-            key.serialize(&mut *self.serializer)
+            self.serializer.serializing_key = self.is_implicit_map || self.serializer.strict;
+            let result = key.serialize(&mut *self.serializer);
+            self.serializer.serializing_key = false;
+            result
         }
 
         fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -445,9 +926,7 @@ mod sealed {
             T: serde::Serialize + ?Sized,
         {
             if self.is_implicit_map {
-                self.serializer.writer.write_raw_value(": ")?;
-                value.serialize(&mut *self.serializer)?;
-                self.serializer.writer.insert_newline()
+                self.serializer.serialize_implicit_map_value(value)
             } else {
                 value.serialize(&mut *self.serializer)
             }
@@ -473,8 +952,32 @@ pub struct Config {
     pub implicit_map_at_root: bool,
     /// Whether to include the names of structures in.
     pub anonymous_structs: bool,
+    /// Provides comments to write before structures, enum variants, and
+    /// structure fields.
+    pub comments: Option<CommentProvider>,
+    /// Whether to return errors for values that can be written but may not
+    /// be read back as expected.
+    ///
+    /// When enabled, map keys must be primitive values, and floating point
+    /// numbers must be finite.
+    pub strict: bool,
 }
 
+/// A function that returns the comment, if any, to write when serializing a
+/// type or one of its fields.
+///
+/// The first argument is the name of a structure or enum variant. The second
+/// argument is `None` when requesting the comment for the type itself, or the
+/// name of the field being serialized.
+///
+/// Field comments are written before the field's name. Type comments are
+/// written before the value. When the value is part of a structure field or a
+/// map entry, its type comment is written before the entry's key, after the
+/// field's comment. If a map's key and value are serialized separately, the
+/// value's type comment is written as the first line inside of the value
+/// instead, or omitted for unit values.
+pub type CommentProvider = fn(name: &str, field: Option<&str>) -> Option<Cow<'static, str>>;
+
 impl Config {
     /// Returns the default configuration.
     ///
@@ -483,6 +986,8 @@ impl Config {
     /// - `writer`: [`writer::Config::Compact`]
     /// - `implicit_map_at_root`: `false`
     /// - `anonymous_structs`: `false`
+    /// - `comments`: `None`
+    /// - `strict`: `false`
     ///
     /// ```rust
     /// use std::collections::HashMap;
@@ -498,6 +1003,8 @@ impl Config {
             writer: writer::Config::Compact,
             implicit_map_at_root: false,
             anonymous_structs: false,
+            comments: None,
+            strict: false,
         }
     }
 
@@ -522,11 +1029,116 @@ impl Config {
             writer: writer::Config::Pretty {
                 indentation: Cow::Borrowed("  "),
                 newline: Cow::Borrowed("\n"),
+                max_width: None,
+                align_map_values: false,
             },
             ..Default::default()
         }
     }
 
+    /// Returns a configuration that serializes values canonically, so that
+    /// values that are semantically equal always produce identical output.
+    /// This is useful when the output is hashed or signed.
+    ///
+    /// Values are first serialized to a [`Value`], which is then
+    /// canonicalized using [`Value::canonicalize`] and written using
+    /// [`writer::Config::Canonical`]. This sorts the entries of all maps and
+    /// structures, and writes primitives using fixed formatting rules. The
+    /// other options of this configuration are not used.
+    ///
+    /// Only the serialization functions of [`Config`] produce sorted output.
+    /// A [`Serializer`] created with this configuration writes entries in the
+    /// order they are serialized.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// let serialized = rsn::ser::Config::canonical()
+    ///     .serialize(&HashMap::from([("b", 2.0), ("a", -0.0)]))
+    ///     .unwrap();
+    /// assert_eq!(serialized, r#"{"a":0.0,"b":2.0}"#);
+    /// ```
+    #[must_use]
+    pub const fn canonical() -> Self {
+        Self {
+            writer: writer::Config::Canonical,
+            implicit_map_at_root: false,
+            anonymous_structs: false,
+            comments: None,
+            strict: false,
+        }
+    }
+
+    /// Sets the maximum line width of a [`writer::Config::Pretty`] writer
+    /// configuration and returns self. Nested values that fit within the
+    /// remaining width of a line are written on a single line.
+    ///
+    /// This setting has no effect when using [`writer::Config::Compact`].
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// let points = BTreeMap::from([("a", vec![(1, 2), (3, 4)]), ("b", vec![])]);
+    /// let serialized = rsn::ser::Config::pretty()
+    ///     .max_width(Some(30))
+    ///     .serialize(&points)
+    ///     .unwrap();
+    /// assert_eq!(serialized, "{\n  \"a\": [(1, 2), (3, 4)],\n  \"b\": []\n}");
+    /// ```
+    #[must_use]
+    pub fn max_width(mut self, max_width: Option<usize>) -> Self {
+        if let writer::Config::Pretty {
+            max_width: width, ..
+        } = &mut self.writer
+        {
+            *width = max_width;
+        }
+        self
+    }
+
+    /// Sets whether a [`writer::Config::Pretty`] writer configuration aligns
+    /// the values of maps written on multiple lines, and returns self.
+    ///
+    /// This setting has no effect when using [`writer::Config::Compact`].
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Server {
+    ///     host: &'static str,
+    ///     port: u16,
+    ///     timeout: u32,
+    /// }
+    ///
+    /// let serialized = rsn::ser::Config::pretty()
+    ///     .align_map_values(true)
+    ///     .serialize(&Server {
+    ///         host: "localhost",
+    ///         port: 80,
+    ///         timeout: 30,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     serialized,
+    ///     r#"Server {
+    ///   host   : "localhost",
+    ///   port   : 80,
+    ///   timeout: 30
+    /// }"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn align_map_values(mut self, align: bool) -> Self {
+        if let writer::Config::Pretty {
+            align_map_values, ..
+        } = &mut self.writer
+        {
+            *align_map_values = align;
+        }
+        self
+    }
+
     /// Sets [`Config::implicit_map_at_root`] and returns self.
     ///
     /// ```rust
@@ -573,6 +1185,72 @@ impl Config {
         self
     }
 
+    /// Sets [`Config::comments`] to `provider` and returns self.
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    ///
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Server {
+    ///     host: &'static str,
+    ///     port: u16,
+    /// }
+    ///
+    /// fn comments(name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+    ///     match (name, field) {
+    ///         ("Server", None) => Some(Cow::Borrowed("Generated by rsn")),
+    ///         ("Server", Some("port")) => Some(Cow::Borrowed("The port to listen on.")),
+    ///         _ => None,
+    ///     }
+    /// }
+    ///
+    /// let serialized = rsn::ser::Config::pretty()
+    ///     .comments(comments)
+    ///     .serialize(&Server {
+    ///         host: "localhost",
+    ///         port: 80,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     serialized,
+    ///     r#"// Generated by rsn
+    /// Server {
+    ///   host: "localhost",
+    ///   // The port to listen on.
+    ///   port: 80
+    /// }"#
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn comments(mut self, provider: CommentProvider) -> Self {
+        self.comments = Some(provider);
+        self
+    }
+
+    /// Sets [`Config::strict`] and returns self.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use rsn::ser::{Config, ErrorKind};
+    ///
+    /// let err = Config::new().strict(true).serialize(&f32::NAN).unwrap_err();
+    /// assert_eq!(err.kind, ErrorKind::NonFiniteFloat);
+    ///
+    /// let err = Config::new()
+    ///     .strict(true)
+    ///     .serialize(&HashMap::from([((1, 2), "tuple")]))
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind, ErrorKind::UnsupportedKey);
+    /// ```
+    #[must_use]
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Returns `value` serialized as Rsn with this configuration.
     ///
     /// ```rust
@@ -582,13 +1260,11 @@ impl Config {
     ///
     /// # Errors
     ///
-    /// Rsn itself does not produce any errors while serializing values. This
-    /// function will return errors that arise within `Serialize` implementations
-    /// encountered while serializing `value`.
-    pub fn serialize<S: Serialize>(&self, value: &S) -> Result<String, core::fmt::Error> {
-        let mut serializer = Serializer::new(String::new(), self);
-        value.serialize(&mut serializer)?;
-        Ok(serializer.finish())
+    /// Returns an error if `value` cannot be represented as Rsn with this
+    /// configuration, or if an error arises within a `Serialize`
+    /// implementation encountered while serializing `value`.
+    pub fn serialize<S: Serialize>(&self, value: &S) -> Result<String, Error> {
+        self.serialize_into(value, String::new())
     }
 
     /// Returns `value` serialized as Rsn with this configuration.
@@ -602,20 +1278,37 @@ impl Config {
     ///
     /// # Errors
     ///
-    /// Rsn itself does not produce any errors while serializing values. This
-    /// function will return errors that arise within `Serialize` implementations
-    /// encountered while serializing `value`.
-    pub fn serialize_to_vec<S: Serialize>(
+    /// Returns an error if `value` cannot be represented as Rsn with this
+    /// configuration, or if an error arises within a `Serialize`
+    /// implementation encountered while serializing `value`.
+    pub fn serialize_to_vec<S: Serialize>(&self, value: &S) -> Result<alloc::vec::Vec<u8>, Error> {
+        self.serialize(value).map(String::into_bytes)
+    }
+
+    fn serialize_into<S: Serialize, Output: Write>(
         &self,
         value: &S,
-    ) -> Result<alloc::vec::Vec<u8>, core::fmt::Error> {
-        self.serialize(value).map(String::into_bytes)
+        output: Output,
+    ) -> Result<Output, Error> {
+        if matches!(self.writer, writer::Config::Canonical) {
+            let mut value = Value::from_serialize(value)?;
+            value.canonicalize();
+            let mut writer = Writer::new(output, &self.writer);
+            writer.write_value(&value)?;
+            Ok(writer.finish())
+        } else {
+            let mut serializer = Serializer::new(output, self);
+            value.serialize(&mut serializer)?;
+            Ok(serializer.finish())
+        }
     }
 }
 
 #[cfg(feature = "std")]
 mod serialize_writer {
-    use super::{Config, Serialize, Serializer, Write};
+    use std::sync::Arc;
+
+    use super::{Config, Error, ErrorKind, Serialize, Write};
 
     struct Writer<T> {
         inner: T,
@@ -654,27 +1347,145 @@ mod serialize_writer {
         /// # Errors
         ///
         /// Returns any errors occurring while serializing `value` or while
-        /// writing to `writer`.
-        #[allow(clippy::missing_panics_doc)]
+        /// writing to `writer`. Errors from `writer` are returned as
+        /// [`ErrorKind::Io`].
         pub fn serialize_to_writer<S: Serialize, W: std::io::Write>(
             &self,
             value: &S,
             writer: W,
-        ) -> std::io::Result<usize> {
+        ) -> Result<usize, Error> {
             let mut writer = Writer {
                 inner: writer,
                 written: 0,
                 error: None,
             };
-            let mut serializer = Serializer::new(&mut writer, self);
-            value
-                .serialize(&mut serializer)
-                .map_err(|_| writer.error.expect("should store error on error"))?;
+            let result = self.serialize_into(value, &mut writer).map(|_| ());
+            result.map_err(|err| match (writer.error.take(), &err.kind) {
+                (Some(io), ErrorKind::Write) => Error::new(ErrorKind::Io(Arc::new(io))),
+                _ => err,
+            })?;
             Ok(writer.written)
         }
     }
 }
 
+/// An error that occurred while serializing Rsn.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Error {
+    /// The kind of error that occurred.
+    pub kind: ErrorKind,
+}
+
+impl Error {
+    fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self::new(ErrorKind::Message(msg.to_string()))
+    }
+}
+
+impl serde::ser::StdError for Error {
+    fn source(&self) -> Option<&(dyn serde::ser::StdError + 'static)> {
+        match &self.kind {
+            #[cfg(feature = "std")]
+            ErrorKind::Io(err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl From<core::fmt::Error> for Error {
+    fn from(_: core::fmt::Error) -> Self {
+        Self::new(ErrorKind::Write)
+    }
+}
+
+impl From<ToValueError> for Error {
+    fn from(err: ToValueError) -> Self {
+        match err {
+            ToValueError::Message(message) => Self::new(ErrorKind::Message(message)),
+            ToValueError::IntegerTooLarge(err) => Self::new(ErrorKind::Message(err.to_string())),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+/// The kind of an error returned while serializing Rsn.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A custom error message from a `Serialize` implementation.
+    Message(String),
+    /// The output could not be written to.
+    Write,
+    /// An error occurred writing to the underlying writer.
+    ///
+    /// This error is only equal to its clones, because [`std::io::Error`]
+    /// can't be compared.
+    #[cfg(feature = "std")]
+    Io(std::sync::Arc<std::io::Error>),
+    /// A map key was not a primitive value.
+    ///
+    /// Keys of implicit maps must always be primitive values. Keys of other
+    /// maps are only checked when [`Config::strict`] is enabled.
+    UnsupportedKey,
+    /// A floating point number was infinite or NaN while
+    /// [`Config::strict`] was enabled.
+    NonFiniteFloat,
+    /// A [`RawValue`](crate::RawValue) did not contain a single valid Rsn
+    /// value.
+    InvalidRawValue(crate::parser::Error),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ErrorKind::Message(message) => f.write_str(message),
+            ErrorKind::Write => f.write_str("error writing output"),
+            #[cfg(feature = "std")]
+            ErrorKind::Io(err) => write!(f, "io error: {err}"),
+            ErrorKind::UnsupportedKey => f.write_str("map keys must be primitive values"),
+            ErrorKind::NonFiniteFloat => f.write_str("float is not finite"),
+            ErrorKind::InvalidRawValue(err) => write!(f, "invalid raw value: {err}"),
+        }
+    }
+}
+
+impl PartialEq for ErrorKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ErrorKind::Message(a), ErrorKind::Message(b)) => a == b,
+            #[cfg(feature = "std")]
+            (ErrorKind::Io(a), ErrorKind::Io(b)) => std::sync::Arc::ptr_eq(a, b),
+            (ErrorKind::InvalidRawValue(a), ErrorKind::InvalidRawValue(b)) => a == b,
+            (ErrorKind::Write, ErrorKind::Write)
+            | (ErrorKind::UnsupportedKey, ErrorKind::UnsupportedKey)
+            | (ErrorKind::NonFiniteFloat, ErrorKind::NonFiniteFloat) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ErrorKind {}
+
 #[test]
 fn serialization_test() {
     #[derive(Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
@@ -686,3 +1497,373 @@ fn serialization_test() {
     let rendered = crate::to_string(&BasicNamed { a: 1, b: -1 }).expect("no errors");
     assert_eq!(rendered, "BasicNamed{a:1,b:-1}");
 }
+
+#[test]
+fn comments() {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::collections::BTreeMap;
+
+    #[derive(serde::Serialize)]
+    struct Settings {
+        server: Server,
+        plugins: Vec<Plugin>,
+        tags: BTreeMap<&'static str, Tag>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Server {
+        port: u16,
+    }
+
+    #[derive(serde::Serialize)]
+    enum Plugin {
+        Resize { width: u32 },
+        Blur(f32),
+        Disabled,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Tag(u8);
+
+    fn comments(name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+        Some(Cow::Borrowed(match (name, field) {
+            ("Settings", None) => "Generated",
+            ("Settings", Some("server")) => "The server\nsettings.",
+            ("Server", None) => "A server.",
+            ("Server", Some("port")) => "The port.",
+            ("Resize", None) => "Resizes.",
+            ("Resize", Some("width")) => "The width.",
+            ("Blur", None) => "Blurs.",
+            ("Disabled", None) => "Does nothing.",
+            ("Tag", None) => "A tag.",
+            _ => return None,
+        }))
+    }
+
+    let value = Settings {
+        server: Server { port: 80 },
+        plugins: vec![
+            Plugin::Resize { width: 640 },
+            Plugin::Blur(0.5),
+            Plugin::Disabled,
+        ],
+        tags: BTreeMap::from([("a", Tag(1))]),
+    };
+    let pretty = Config::pretty()
+        .comments(comments)
+        .serialize(&value)
+        .unwrap();
+    assert_eq!(
+        pretty,
+        r#"// Generated
+Settings {
+  // The server
+  // settings.
+  // A server.
+  server: Server {
+    // The port.
+    port: 80
+  },
+  plugins: [
+    // Resizes.
+    Resize {
+      // The width.
+      width: 640
+    },
+    // Blurs.
+    Blur(
+      0.5
+    ),
+    // Does nothing.
+    Disabled
+  ],
+  tags: {
+    // A tag.
+    "a": Tag(
+      1
+    )
+  }
+}"#
+    );
+    let compact = Config::new().comments(comments).serialize(&value).unwrap();
+    let implicit = Config::pretty()
+        .implicit_map_at_root(true)
+        .comments(comments)
+        .serialize(&value)
+        .unwrap();
+    assert!(implicit.starts_with(
+        "// Generated\n// The server\n// settings.\n// A server.\nserver: Server {\n  // The port.\n"
+    ));
+
+    for (rendered, implicit_map) in [(pretty, false), (compact, false), (implicit, true)] {
+        assert!(
+            crate::parser::Parser::validate(
+                &rendered,
+                crate::parser::Config::default().allow_implicit_map_at_root(implicit_map)
+            ),
+            "{rendered}"
+        );
+    }
+}
+
+#[test]
+fn separate_map_entry_comments() {
+    // A value serialized separately from its key has its comment written
+    // inside of it.
+    struct Separate;
+
+    impl Serialize for Separate {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_key("a")?;
+            map.serialize_value(&Tag(1))?;
+            map.end()
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct Tag(u8);
+
+    fn comments(name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+        (name == "Tag" && field.is_none()).then_some(Cow::Borrowed("A tag."))
+    }
+
+    assert_eq!(
+        Config::pretty()
+            .comments(comments)
+            .serialize(&Separate)
+            .unwrap(),
+        "{\n  \"a\": Tag(\n    // A tag.\n    1\n  )\n}"
+    );
+}
+
+#[test]
+fn values() {
+    use crate::parser;
+
+    fn comments(name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+        Some(Cow::Borrowed(match (name, field) {
+            ("Server", None) => "A server.",
+            ("Server", Some("port")) => "The port.",
+            ("Disabled", None) => "Does nothing.",
+            _ => return None,
+        }))
+    }
+
+    let parse = |source| Value::from_str(source, parser::Config::default()).unwrap();
+
+    // Values are checked, named, and commented like the types they represent.
+    let strict = Config::new().strict(true);
+    assert_eq!(
+        strict.serialize(&parse("[1, NaN]")).unwrap_err().kind,
+        ErrorKind::NonFiniteFloat
+    );
+    assert_eq!(
+        strict
+            .serialize(&parse("[1, {(1, 2): 3}]"))
+            .unwrap_err()
+            .kind,
+        ErrorKind::UnsupportedKey
+    );
+    assert_eq!(
+        Config::new()
+            .anonymous_structs(true)
+            .serialize(&parse("[Server { port: 80 }, Point(1, 2)]"))
+            .unwrap(),
+        "[{port:80},Point(1,2)]"
+    );
+    assert_eq!(
+        Config::pretty()
+            .comments(comments)
+            .serialize(&parse("{a: Server { port: 80 }, b: Disabled}"))
+            .unwrap(),
+        "{\n  // A server.\n  a: Server {\n    // The port.\n    port: 80\n  },\n  // Does nothing.\n  b: Disabled\n}"
+    );
+    assert_eq!(
+        Config::pretty()
+            .implicit_map_at_root(true)
+            .comments(comments)
+            .serialize(&parse("Server { port: 80 }"))
+            .unwrap(),
+        "// A server.\n// The port.\nport: 80\n"
+    );
+}
+
+#[test]
+fn nested_implicit_map_comments() {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[derive(Serialize)]
+    struct Shape {
+        points: Vec<Point>,
+    }
+
+    #[derive(Serialize)]
+    struct Point {
+        x: i32,
+    }
+
+    fn comments(name: &str, field: Option<&str>) -> Option<Cow<'static, str>> {
+        match (name, field) {
+            ("Point", None) => Some(Cow::Borrowed("A point.")),
+            _ => None,
+        }
+    }
+
+    let shape = Shape {
+        points: vec![Point { x: 1 }],
+    };
+    let config = Config::pretty().comments(comments);
+    assert_eq!(
+        config.serialize(&shape).unwrap(),
+        "Shape {\n  points: [\n    // A point.\n    Point {\n      x: 1\n    }\n  ]\n}"
+    );
+    assert_eq!(
+        config.implicit_map_at_root(true).serialize(&shape).unwrap(),
+        "points: [\n  // A point.\n  Point {\n    x: 1\n  }\n]\n"
+    );
+}
+
+#[test]
+fn errors() {
+    use std::collections::BTreeMap;
+
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            Err(serde::ser::Error::custom("not today"))
+        }
+    }
+
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "reader hung up",
+            ))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = crate::to_string(&Failing).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Message(String::from("not today")));
+    assert_eq!(err.to_string(), "not today");
+
+    let err = crate::to_writer(&1, FailingWriter).unwrap_err();
+    let ErrorKind::Io(io) = &err.kind else {
+        unreachable!("{err:?}")
+    };
+    assert_eq!(io.kind(), std::io::ErrorKind::BrokenPipe);
+    assert_eq!(err.to_string(), "io error: reader hung up");
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "reader hung up");
+    assert_eq!(err, err.clone());
+
+    // Non-finite floats and nested keys are allowed unless strict.
+    let nested_keys = BTreeMap::from([((1, 2), 3)]);
+    assert_eq!(crate::to_string(&f64::INFINITY).unwrap(), "inf");
+    assert_eq!(crate::to_string(&nested_keys).unwrap(), "{(1,2):3}");
+    let strict = Config::new().strict(true);
+    assert_eq!(
+        strict.serialize(&f64::INFINITY).unwrap_err().kind,
+        ErrorKind::NonFiniteFloat
+    );
+    assert_eq!(
+        strict.serialize(&[f32::NAN]).unwrap_err().kind,
+        ErrorKind::NonFiniteFloat
+    );
+    assert_eq!(
+        strict.serialize(&nested_keys).unwrap_err().kind,
+        ErrorKind::UnsupportedKey
+    );
+    assert_eq!(
+        strict
+            .serialize(&BTreeMap::from([(Some(1), 1)]))
+            .unwrap_err()
+            .kind,
+        ErrorKind::UnsupportedKey
+    );
+    assert_eq!(
+        strict
+            .serialize(&BTreeMap::from([("a", Some(1.5))]))
+            .unwrap(),
+        r#"{"a":Some(1.5)}"#
+    );
+
+    // Implicit maps can't contain nested keys.
+    let err = Config::new()
+        .implicit_map_at_root(true)
+        .serialize(&nested_keys)
+        .unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnsupportedKey);
+}
+
+#[test]
+fn canonical() {
+    use alloc::vec::Vec;
+    use std::collections::HashMap;
+
+    #[derive(serde::Serialize)]
+    struct Signed<'a> {
+        version: u8,
+        tags: HashMap<&'a str, f32>,
+        payload: crate::RawValue<'a>,
+    }
+
+    let value = Signed {
+        version: 1,
+        tags: HashMap::from([("zeta", 1.0), ("alpha", 0.5)]),
+        payload: crate::RawValue::new("Raw { z: 1, a: [ 2 ] }").unwrap(),
+    };
+    let serialized = Config::canonical().serialize(&value).unwrap();
+    assert_eq!(
+        serialized,
+        r#"Signed{payload:Raw{a:[2],z:1},tags:{"alpha":0.5,"zeta":1.0},version:1}"#
+    );
+    let mut written = Vec::new();
+    Config::canonical()
+        .serialize_to_writer(&value, &mut written)
+        .unwrap();
+    assert_eq!(written, serialized.as_bytes());
+}
+
+#[test]
+fn canonical_floats() {
+    for (value, expected) in [
+        (1e39, "1.0e39"),
+        (1e16, "1.0e16"),
+        (9_999_999_999_999_998., "9999999999999998.0"),
+        (-2.5e-8, "-2.5e-8"),
+        (0.000_099_9, "9.99e-5"),
+        (1e-4, "0.0001"),
+        (f64::MAX, "1.7976931348623157e308"),
+        (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+        (5e-324, "5.0e-324"),
+    ] {
+        let serialized = Config::canonical().serialize(&value).unwrap();
+        assert_eq!(serialized, expected);
+        let parsed = crate::from_str::<f64>(&serialized).unwrap();
+        assert_eq!(parsed.to_bits(), value.to_bits(), "{serialized}");
+    }
+
+    let serialized = Config::canonical().serialize(&f32::MAX).unwrap();
+    assert_eq!(serialized, "3.4028234663852886e38");
+    assert_eq!(
+        crate::from_str::<f32>(&serialized).unwrap().to_bits(),
+        f32::MAX.to_bits()
+    );
+}
@@ -7,8 +7,14 @@ use core::ops::Range;
 use unicode_ident::{is_xid_continue, is_xid_start};
 
 use crate::tokenizer::char_iterator::CharIterator;
+#[cfg(feature = "std")]
+pub use crate::tokenizer::reader::ReaderTokenizer;
 
 mod char_iterator;
+mod json;
+#[cfg(feature = "std")]
+mod reader;
+mod ron;
 
 /// A token in an Rsn document.
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -24,6 +30,16 @@ impl<'a> Token<'a> {
     const fn new(location: Range<usize>, kind: TokenKind<'a>) -> Self {
         Self { location, kind }
     }
+
+    /// Returns an owned representation of this token, copying any borrowed
+    /// data to the heap.
+    #[must_use]
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            location: self.location,
+            kind: self.kind.into_owned(),
+        }
+    }
 }
 
 /// A kind of a token in an Rsn document.
@@ -48,15 +64,39 @@ pub enum TokenKind<'a> {
     /// A byte string literal.
     Bytes(Cow<'a, [u8]>),
     /// An identifier (name).
-    Identifier(&'a str),
+    Identifier(Cow<'a, str>),
     /// The opening variant of a [`Balanced`] token.
     Open(Balanced),
     /// The closing variant of a [`Balanced`] token.
     Close(Balanced),
     /// A comment
-    Comment(&'a str),
+    Comment(Cow<'a, str>),
     /// Whitespace between other tokens.
-    Whitespace(&'a str),
+    Whitespace(Cow<'a, str>),
+}
+
+impl TokenKind<'_> {
+    /// Returns an owned representation of this token kind, copying any
+    /// borrowed data to the heap.
+    #[must_use]
+    pub fn into_owned(self) -> TokenKind<'static> {
+        match self {
+            TokenKind::Integer(value) => TokenKind::Integer(value),
+            TokenKind::Float(value) => TokenKind::Float(value),
+            TokenKind::Bool(value) => TokenKind::Bool(value),
+            TokenKind::Character(value) => TokenKind::Character(value),
+            TokenKind::Colon => TokenKind::Colon,
+            TokenKind::Comma => TokenKind::Comma,
+            TokenKind::Byte(value) => TokenKind::Byte(value),
+            TokenKind::String(value) => TokenKind::String(Cow::Owned(value.into_owned())),
+            TokenKind::Bytes(value) => TokenKind::Bytes(Cow::Owned(value.into_owned())),
+            TokenKind::Identifier(value) => TokenKind::Identifier(Cow::Owned(value.into_owned())),
+            TokenKind::Open(value) => TokenKind::Open(value),
+            TokenKind::Close(value) => TokenKind::Close(value),
+            TokenKind::Comment(value) => TokenKind::Comment(Cow::Owned(value.into_owned())),
+            TokenKind::Whitespace(value) => TokenKind::Whitespace(Cow::Owned(value.into_owned())),
+        }
+    }
 }
 
 impl<'a> Eq for TokenKind<'a> {}
@@ -69,10 +109,11 @@ impl<'a> PartialEq for TokenKind<'a> {
             (Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
             (Self::Character(l0), Self::Character(r0)) => l0 == r0,
             (Self::Byte(l0), Self::Byte(r0)) => l0 == r0,
-            (Self::String(l0), Self::String(r0)) => l0 == r0,
             (Self::Bytes(l0), Self::Bytes(r0)) => l0 == r0,
-            (Self::Comment(l0), Self::Comment(r0))
-            | (Self::Identifier(l0), Self::Identifier(r0)) => l0 == r0,
+            (Self::String(l0), Self::String(r0))
+            | (Self::Comment(l0), Self::Comment(r0))
+            | (Self::Identifier(l0), Self::Identifier(r0))
+            | (Self::Whitespace(l0), Self::Whitespace(r0)) => l0 == r0,
             (Self::Open(l0), Self::Open(r0)) | (Self::Close(l0), Self::Close(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
@@ -246,11 +287,43 @@ pub enum Balanced {
     Bracket,
 }
 
+/// The syntax accepted by a [`Tokenizer`] and
+/// [`Parser`](crate::parser::Parser).
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Dialect {
+    /// Rsn.
+    #[default]
+    Rsn,
+    /// JSON, as specified by [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259).
+    ///
+    /// Only JSON's syntax is accepted: comments, trailing commas, non-string
+    /// map keys, and Rsn-only literals are errors. Strings support JSON's
+    /// escape sequences, including `\uXXXX` escapes of UTF-16 surrogate pairs.
+    /// Integers that are too large to be represented are tokenized as floats.
+    ///
+    /// `null` is tokenized as the identifier `null`, which a
+    /// [`Parser`](crate::parser::Parser) converts to the identifier `None`.
+    Json,
+    /// [Rusty Object Notation](https://github.com/ron-rs/ron).
+    ///
+    /// In addition to Rsn's syntax, floats may omit their integer part
+    /// (`.5`), and numbers may have a type suffix such as `1u8` or `2f32`.
+    /// Integers are checked to fit within the type of their suffix.
+    ///
+    /// Inner attributes such as `#![enable(implicit_some)]` are tokenized as
+    /// [`TokenKind::Comment`]s. A [`Parser`](crate::parser::Parser) reads the
+    /// extensions they enable, and converts RON's `(field: value)` structure
+    /// syntax into maps.
+    Ron,
+}
+
 /// Parses Rsn into a sequence of [`Token`]s.
 #[derive(Debug, Clone)]
 pub struct Tokenizer<'a, const INCLUDE_ALL: bool> {
     chars: CharIterator<'a>,
     scratch: String,
+    dialect: Dialect,
 }
 
 impl<'a> Tokenizer<'a, false> {
@@ -274,6 +347,23 @@ impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
         Self {
             chars: CharIterator::new(source),
             scratch: String::new(),
+            dialect: Dialect::Rsn,
+        }
+    }
+
+    /// Sets the syntax accepted by this tokenizer, and returns self.
+    ///
+    /// The default dialect is [`Dialect::Rsn`].
+    #[must_use]
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    fn is_whitespace(&self, ch: char) -> bool {
+        match self.dialect {
+            Dialect::Rsn | Dialect::Ron => is_rust_whitespace(ch),
+            Dialect::Json => matches!(ch, ' ' | '\t' | '\n' | '\r'),
         }
     }
 
@@ -1109,7 +1199,7 @@ impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
                     "NaN" | "+NaN" if !is_raw => TokenKind::Float(f64::NAN),
                     "-inf" if !is_raw => TokenKind::Float(-f64::INFINITY),
                     "-NaN" if !is_raw => TokenKind::Float(-f64::NAN),
-                    _ => TokenKind::Identifier(source),
+                    _ => TokenKind::Identifier(Cow::Borrowed(source)),
                 },
             ))
         } else {
@@ -1156,7 +1246,7 @@ impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
 
         Ok(Token::new(
             self.chars.marked_range(),
-            TokenKind::Comment(&self.chars.source[self.chars.marked_range()]),
+            TokenKind::Comment(Cow::Borrowed(&self.chars.source[self.chars.marked_range()])),
         ))
     }
 
@@ -1176,7 +1266,7 @@ impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
         let range = self.chars.marked_range();
         Ok(Token::new(
             range.clone(),
-            TokenKind::Comment(&self.chars.source[range]),
+            TokenKind::Comment(Cow::Borrowed(&self.chars.source[range])),
         ))
     }
 }
@@ -1188,7 +1278,22 @@ impl<'a, const INCLUDE_ALL: bool> Iterator for Tokenizer<'a, INCLUDE_ALL> {
         loop {
             self.chars.mark_start();
             let ch = self.chars.next()?;
+            let json = self.dialect == Dialect::Json;
+            let ron = self.dialect == Dialect::Ron;
             let result = match ch {
+                '"' if json => self.tokenize_json_string(),
+                '0'..='9' | '-' if json => self.tokenize_json_number(ch),
+                ch if json
+                    && !matches!(ch, '{' | '}' | '[' | ']' | ':' | ',')
+                    && !self.is_whitespace(ch) =>
+                {
+                    self.tokenize_json_literal(ch)
+                }
+                '#' if ron => self.tokenize_ron_attribute(),
+                '0'..='9' | '-' | '+' if ron => self.tokenize_ron_number(ch),
+                '.' if ron && matches!(self.chars.peek(), Some('0'..='9')) => {
+                    self.tokenize_ron_number(ch)
+                }
                 '0'..='9' | '-' | '+' => self.tokenize_number(ch as u8),
                 '"' => self.tokenize_string(),
                 '\'' => self.tokenize_char(),
@@ -1237,10 +1342,10 @@ impl<'a, const INCLUDE_ALL: bool> Iterator for Tokenizer<'a, INCLUDE_ALL> {
                 )),
                 ':' => Ok(Token::new(self.chars.marked_range(), TokenKind::Colon)),
                 ',' => Ok(Token::new(self.chars.marked_range(), TokenKind::Comma)),
-                ch if is_rust_whitespace(ch) => {
+                ch if self.is_whitespace(ch) => {
                     loop {
                         match self.chars.peek() {
-                            Some(ch) if is_rust_whitespace(ch) => {
+                            Some(ch) if self.is_whitespace(ch) => {
                                 self.chars.next();
                             }
                             _ => break,
@@ -1249,7 +1354,7 @@ impl<'a, const INCLUDE_ALL: bool> Iterator for Tokenizer<'a, INCLUDE_ALL> {
                     if INCLUDE_ALL {
                         Ok(Token::new(
                             self.chars.marked_range(),
-                            TokenKind::Whitespace(self.chars.marked_str()),
+                            TokenKind::Whitespace(Cow::Borrowed(self.chars.marked_str())),
                         ))
                     } else {
                         continue;
@@ -1263,6 +1368,33 @@ impl<'a, const INCLUDE_ALL: bool> Iterator for Tokenizer<'a, INCLUDE_ALL> {
     }
 }
 
+impl<'a, const INCLUDE_ALL: bool> TokenSource<'a> for Tokenizer<'a, INCLUDE_ALL> {
+    fn current_offset(&self) -> usize {
+        self.chars.current_offset()
+    }
+
+    fn source(&self, range: Range<usize>) -> Option<&'a str> {
+        self.chars.source.get(range)
+    }
+}
+
+/// A source of [`Token`]s that keeps track of its position in the input.
+///
+/// A [`Parser`](crate::parser::Parser) can read its tokens from any
+/// `TokenSource` that does not produce [`TokenKind::Whitespace`] tokens.
+pub trait TokenSource<'a>: Iterator<Item = Result<Token<'a>, Error>> {
+    /// Returns the current byte offset of this source.
+    fn current_offset(&self) -> usize;
+
+    /// Returns the input in the byte range `range`, if this source borrows
+    /// its input.
+    ///
+    /// Returns `None` by default.
+    fn source(&self, _range: Range<usize>) -> Option<&'a str> {
+        None
+    }
+}
+
 fn is_rust_whitespace(ch: char) -> bool {
     // https://doc.rust-lang.org/reference/whitespace.html
     matches!(
@@ -1291,7 +1423,7 @@ pub struct Error {
 
 impl Error {
     #[must_use]
-    const fn new(location: Range<usize>, kind: ErrorKind) -> Self {
+    pub(crate) const fn new(location: Range<usize>, kind: ErrorKind) -> Self {
         Self { location, kind }
     }
 }
@@ -1325,6 +1457,11 @@ pub enum ErrorKind {
     InvalidFloat,
     /// A carriage return without a corresponding `\n` was encountered.
     IsolatedCarriageReturn,
+    /// The input contained invalid UTF-8.
+    InvalidUtf8,
+    /// An error occurred while reading from the input.
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
 }
 
 impl Display for ErrorKind {
@@ -1338,6 +1475,9 @@ impl Display for ErrorKind {
             ErrorKind::IsolatedCarriageReturn => f.write_str("unexpected isolated carriage return"),
             ErrorKind::IntegerTooLarge => f.write_str("value overflowed the maximum size"),
             ErrorKind::InvalidFloat => f.write_str("invalid floating point literal"),
+            ErrorKind::InvalidUtf8 => f.write_str("invalid utf-8"),
+            #[cfg(feature = "std")]
+            ErrorKind::Io(kind) => write!(f, "io error: {kind}"),
         }
     }
 }
@@ -1471,15 +1611,30 @@ mod tests {
         test_tokens("true", &[Token::new(0..4, TokenKind::Bool(true))]);
         test_tokens("false", &[Token::new(0..5, TokenKind::Bool(false))]);
 
-        test_tokens("r#true", &[Token::new(0..6, TokenKind::Identifier("true"))]);
+        test_tokens(
+            "r#true",
+            &[Token::new(
+                0..6,
+                TokenKind::Identifier(Cow::Borrowed("true")),
+            )],
+        );
         test_tokens(
             "r#false",
-            &[Token::new(0..7, TokenKind::Identifier("false"))],
+            &[Token::new(
+                0..7,
+                TokenKind::Identifier(Cow::Borrowed("false")),
+            )],
         );
 
-        test_tokens("_", &[Token::new(0..1, TokenKind::Identifier("_"))]);
+        test_tokens(
+            "_",
+            &[Token::new(0..1, TokenKind::Identifier(Cow::Borrowed("_")))],
+        );
 
-        test_tokens("_0", &[Token::new(0..2, TokenKind::Identifier("_0"))]);
+        test_tokens(
+            "_0",
+            &[Token::new(0..2, TokenKind::Identifier(Cow::Borrowed("_0")))],
+        );
 
         test_tokens_err("=", 0..1, &ErrorKind::Unexpected('='));
     }
@@ -2079,11 +2234,11 @@ mod tests {
             "{a:1,b:2}",
             &[
                 Token::new(0..1, TokenKind::Open(Balanced::Brace)),
-                Token::new(1..2, TokenKind::Identifier("a")),
+                Token::new(1..2, TokenKind::Identifier(Cow::Borrowed("a"))),
                 Token::new(2..3, TokenKind::Colon),
                 Token::new(3..4, TokenKind::Integer(Integer::Usize(1))),
                 Token::new(4..5, TokenKind::Comma),
-                Token::new(5..6, TokenKind::Identifier("b")),
+                Token::new(5..6, TokenKind::Identifier(Cow::Borrowed("b"))),
                 Token::new(6..7, TokenKind::Colon),
                 Token::new(7..8, TokenKind::Integer(Integer::Usize(2))),
                 Token::new(8..9, TokenKind::Close(Balanced::Brace)),
@@ -2230,7 +2385,10 @@ mod tests {
             ($comment:tt) => {
                 test_tokens_full(
                     $comment,
-                    &[Token::new(0..$comment.len(), TokenKind::Comment($comment))],
+                    &[Token::new(
+                        0..$comment.len(),
+                        TokenKind::Comment(Cow::Borrowed($comment)),
+                    )],
                 );
             };
         }
@@ -2242,13 +2400,16 @@ mod tests {
     fn single_line_comments() {
         test_tokens_full(
             "// test",
-            &[Token::new(0..7, TokenKind::Comment("// test"))],
+            &[Token::new(
+                0..7,
+                TokenKind::Comment(Cow::Borrowed("// test")),
+            )],
         );
         test_tokens_full(
             "// test\n",
             &[
-                Token::new(0..7, TokenKind::Comment("// test")),
-                Token::new(7..8, TokenKind::Whitespace("\n")),
+                Token::new(0..7, TokenKind::Comment(Cow::Borrowed("// test"))),
+                Token::new(7..8, TokenKind::Whitespace(Cow::Borrowed("\n"))),
             ],
         );
     }
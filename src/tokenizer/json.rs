@@ -0,0 +1,306 @@
+use alloc::borrow::Cow;
+
+use super::{Error, ErrorKind, Integer, Token, TokenKind, Tokenizer};
+
+impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
+    /// Tokenizes a JSON string, whose opening quote has already been read.
+    pub(super) fn tokenize_json_string(&mut self) -> Result<Token<'a>, Error> {
+        loop {
+            match self.next_or_eof()? {
+                '"' => {
+                    // This string had no escapes, we can borrow.
+                    let range = self.chars.marked_range();
+                    let contents = &self.chars.source[range.start + 1..range.end - 1];
+                    return Ok(Token::new(
+                        range,
+                        TokenKind::String(Cow::Borrowed(contents)),
+                    ));
+                }
+                '\\' => break,
+                ch if ch < ' ' => return Err(self.error_at_last_char(ErrorKind::Unexpected(ch))),
+                _ => {}
+            }
+        }
+
+        self.scratch.clear();
+        let starting_range = self.chars.marked_range();
+        self.scratch
+            .push_str(&self.chars.source[starting_range.start + 1..starting_range.end - 1]);
+        loop {
+            let escaped = self.tokenize_json_escape()?;
+            self.scratch.push(escaped);
+            loop {
+                match self.next_or_eof()? {
+                    '"' => {
+                        return Ok(Token::new(
+                            self.chars.marked_range(),
+                            TokenKind::String(Cow::Owned(self.scratch.clone())),
+                        ));
+                    }
+                    '\\' => break,
+                    ch if ch < ' ' => {
+                        return Err(self.error_at_last_char(ErrorKind::Unexpected(ch)));
+                    }
+                    ch => self.scratch.push(ch),
+                }
+            }
+        }
+    }
+
+    fn tokenize_json_escape(&mut self) -> Result<char, Error> {
+        match self.next_or_eof()? {
+            ch @ ('"' | '\\' | '/') => Ok(ch),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'u' => {
+                // The escape begins at the backslash.
+                let start = self.chars.last_offset() - 1;
+                let first = self.tokenize_json_hex4()?;
+                let code_point = if (0xD800..0xDC00).contains(&first) {
+                    // A high surrogate must be followed by an escaped low
+                    // surrogate.
+                    if self.next_or_eof()? != '\\' || self.next_or_eof()? != 'u' {
+                        return Err(Error::new(
+                            start..self.chars.current_offset(),
+                            ErrorKind::InvalidUnicode,
+                        ));
+                    }
+                    let second = self.tokenize_json_hex4()?;
+                    if !(0xDC00..0xE000).contains(&second) {
+                        return Err(Error::new(
+                            start..self.chars.current_offset(),
+                            ErrorKind::InvalidUnicode,
+                        ));
+                    }
+                    0x10000 + ((first - 0xD800) << 10) + (second - 0xDC00)
+                } else {
+                    first
+                };
+                char::from_u32(code_point).ok_or_else(|| {
+                    Error::new(
+                        start..self.chars.current_offset(),
+                        ErrorKind::InvalidUnicode,
+                    )
+                })
+            }
+            ch => Err(self.error_at_last_char(ErrorKind::Unexpected(ch))),
+        }
+    }
+
+    fn tokenize_json_hex4(&mut self) -> Result<u32, Error> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next_or_eof()?
+                .to_digit(16)
+                .ok_or_else(|| self.error_at_last_char(ErrorKind::InvalidUnicode))?;
+            value = (value << 4) | digit;
+        }
+        Ok(value)
+    }
+
+    /// Tokenizes a JSON number beginning with `start_char`, which is either
+    /// `-` or a digit.
+    pub(super) fn tokenize_json_number(&mut self, start_char: char) -> Result<Token<'a>, Error> {
+        let first_digit = if start_char == '-' {
+            match self.chars.peek() {
+                Some(ch @ '0'..='9') => {
+                    self.chars.next();
+                    ch
+                }
+                _ => return Err(self.error_at_next_char(ErrorKind::ExpectedDigit)),
+            }
+        } else {
+            start_char
+        };
+        // Leading zeros are not allowed.
+        if first_digit != '0' {
+            self.skip_json_digits();
+        }
+
+        let mut is_float = false;
+        if self.chars.peek() == Some('.') {
+            self.chars.next();
+            is_float = true;
+            if !self.skip_json_digits() {
+                return Err(self.error_at_next_char(ErrorKind::ExpectedDigit));
+            }
+        }
+        if matches!(self.chars.peek(), Some('e' | 'E')) {
+            self.chars.next();
+            is_float = true;
+            if matches!(self.chars.peek(), Some('+' | '-')) {
+                self.chars.next();
+            }
+            if !self.skip_json_digits() {
+                return Err(self.error_at_next_char(ErrorKind::ExpectedDigit));
+            }
+        }
+
+        let number = self.chars.marked_str();
+        if !is_float {
+            // Integers that are too large to be represented are parsed as
+            // floats, matching the behavior of most JSON parsers.
+            let integer = if start_char == '-' {
+                number
+                    .parse::<i128>()
+                    .ok()
+                    .and_then(|value| Integer::try_from(value).ok())
+            } else {
+                number
+                    .parse::<u128>()
+                    .ok()
+                    .and_then(|value| Integer::try_from(value).ok())
+            };
+            if let Some(integer) = integer {
+                return Ok(Token::new(
+                    self.chars.marked_range(),
+                    TokenKind::Integer(integer),
+                ));
+            }
+        }
+
+        let parsed = number
+            .parse::<f64>()
+            .map_err(|_| self.error(ErrorKind::InvalidFloat))?;
+        Ok(Token::new(
+            self.chars.marked_range(),
+            TokenKind::Float(parsed),
+        ))
+    }
+
+    /// Skips ASCII digits, returning true if at least one was skipped.
+    fn skip_json_digits(&mut self) -> bool {
+        let mut skipped = false;
+        while matches!(self.chars.peek(), Some('0'..='9')) {
+            self.chars.next();
+            skipped = true;
+        }
+        skipped
+    }
+
+    /// Tokenizes `true`, `false`, or `null`. `null` is returned as an
+    /// identifier.
+    pub(super) fn tokenize_json_literal(&mut self, start_char: char) -> Result<Token<'a>, Error> {
+        if !start_char.is_ascii_alphabetic() {
+            return Err(self.error_at_last_char(ErrorKind::Unexpected(start_char)));
+        }
+        let token = self.tokenize_identifier(start_char, false)?;
+        match &token.kind {
+            TokenKind::Bool(_) => Ok(token),
+            TokenKind::Identifier(name) if name == "null" => Ok(token),
+            _ => Err(Error::new(
+                token.location,
+                ErrorKind::Unexpected(start_char),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::tokenizer::{Balanced, Dialect};
+
+    #[track_caller]
+    fn test_tokens(source: &str, tokens: &[Token<'_>]) {
+        assert_eq!(
+            &Tokenizer::minified(source)
+                .with_dialect(Dialect::Json)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            tokens
+        );
+    }
+
+    #[track_caller]
+    fn test_tokens_err(source: &str, location: core::ops::Range<usize>, kind: &ErrorKind) {
+        let err = Tokenizer::minified(source)
+            .with_dialect(Dialect::Json)
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("source did not error");
+        assert_eq!(&err.kind, kind);
+        assert_eq!(err.location, location);
+    }
+
+    #[test]
+    fn literals() {
+        test_tokens(
+            "[true, false, null]",
+            &[
+                Token::new(0..1, TokenKind::Open(Balanced::Bracket)),
+                Token::new(1..5, TokenKind::Bool(true)),
+                Token::new(5..6, TokenKind::Comma),
+                Token::new(7..12, TokenKind::Bool(false)),
+                Token::new(12..13, TokenKind::Comma),
+                Token::new(14..18, TokenKind::Identifier(Cow::Borrowed("null"))),
+                Token::new(18..19, TokenKind::Close(Balanced::Bracket)),
+            ],
+        );
+        test_tokens_err("None", 0..4, &ErrorKind::Unexpected('N'));
+        test_tokens_err("inf", 0..3, &ErrorKind::Unexpected('i'));
+        test_tokens_err("'a'", 0..1, &ErrorKind::Unexpected('\''));
+        test_tokens_err("(", 0..1, &ErrorKind::Unexpected('('));
+        test_tokens_err("// comment", 0..1, &ErrorKind::Unexpected('/'));
+        test_tokens_err("\u{b}", 0..1, &ErrorKind::Unexpected('\u{b}'));
+    }
+
+    #[test]
+    fn numbers() {
+        test_tokens(
+            "0",
+            &[Token::new(0..1, TokenKind::Integer(Integer::Usize(0)))],
+        );
+        test_tokens(
+            "-12",
+            &[Token::new(0..3, TokenKind::Integer(Integer::Isize(-12)))],
+        );
+        test_tokens("1.5", &[Token::new(0..3, TokenKind::Float(1.5))]);
+        test_tokens("-1E+2", &[Token::new(0..5, TokenKind::Float(-100.))]);
+        test_tokens("1e-2", &[Token::new(0..4, TokenKind::Float(0.01))]);
+        test_tokens(
+            "1000000000000000000000000000000000000000",
+            &[Token::new(0..40, TokenKind::Float(1e39))],
+        );
+        // A leading zero ends the number.
+        test_tokens(
+            "01",
+            &[
+                Token::new(0..1, TokenKind::Integer(Integer::Usize(0))),
+                Token::new(1..2, TokenKind::Integer(Integer::Usize(1))),
+            ],
+        );
+        test_tokens_err("-", 1..1, &ErrorKind::ExpectedDigit);
+        test_tokens_err("1.", 2..2, &ErrorKind::ExpectedDigit);
+        test_tokens_err("1.e1", 2..3, &ErrorKind::ExpectedDigit);
+        test_tokens_err("1e", 2..2, &ErrorKind::ExpectedDigit);
+        test_tokens_err("+1", 0..1, &ErrorKind::Unexpected('+'));
+        test_tokens_err("0x1", 1..3, &ErrorKind::Unexpected('x'));
+    }
+
+    #[test]
+    fn strings() {
+        test_tokens(
+            r#""a/b""#,
+            &[Token::new(0..5, TokenKind::String(Cow::Borrowed("a/b")))],
+        );
+        test_tokens(
+            r#""\"\\\/\b\f\n\r\t\u00e9\ud83e\udd80""#,
+            &[Token::new(
+                0..36,
+                TokenKind::String(Cow::Borrowed("\"\\/\u{8}\u{c}\n\r\té🦀")),
+            )],
+        );
+        test_tokens_err(r#""\x41""#, 2..3, &ErrorKind::Unexpected('x'));
+        test_tokens_err(r#""\u{41}""#, 3..4, &ErrorKind::InvalidUnicode);
+        test_tokens_err(r#""\ud83e""#, 1..8, &ErrorKind::InvalidUnicode);
+        test_tokens_err(r#""\ud83eA""#, 1..8, &ErrorKind::InvalidUnicode);
+        test_tokens_err(r#""\ud83e\u0041""#, 1..13, &ErrorKind::InvalidUnicode);
+        test_tokens_err("\"\t\"", 1..2, &ErrorKind::Unexpected('\t'));
+    }
+}
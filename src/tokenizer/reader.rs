@@ -0,0 +1,405 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::io::{self, Read};
+
+use super::{Dialect, Error, ErrorKind, Token, TokenKind, TokenSource, Tokenizer};
+
+/// The number of bytes requested from the reader when more input is needed.
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Parses Rsn from a [`Read`] implementor into a sequence of owned
+/// [`Token`]s.
+///
+/// Unlike [`Tokenizer`], this type does not require the entire input to be in
+/// memory. Input is read in chunks, and only the portion of the input that has
+/// not yet been tokenized is retained. The memory used is bounded by the chunk
+/// size and the length of the largest single token in the input.
+#[derive(Debug)]
+pub struct ReaderTokenizer<R, const INCLUDE_ALL: bool> {
+    reader: R,
+    /// Validated UTF-8 that has been read but not necessarily tokenized.
+    buffer: String,
+    /// Bytes at the end of the last read that form an incomplete UTF-8
+    /// sequence.
+    incomplete: Vec<u8>,
+    /// The number of bytes at the start of `buffer` that have been tokenized.
+    consumed: usize,
+    /// The offset of `buffer[0]` within the input.
+    buffer_offset: usize,
+    chunk_size: usize,
+    /// The maximum number of bytes to read from `reader`.
+    read_limit: Option<usize>,
+    eof: bool,
+    dialect: Dialect,
+}
+
+impl<R> ReaderTokenizer<R, false> {
+    /// Returns a tokenizer that reads from `reader` and ignores whitespace and
+    /// comments.
+    #[must_use]
+    pub fn minified(reader: R) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl<R> ReaderTokenizer<R, true> {
+    /// Returns a tokenizer that reads from `reader` and includes whitespace
+    /// and comments.
+    #[must_use]
+    pub fn full(reader: R) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl<R, const INCLUDE_ALL: bool> ReaderTokenizer<R, INCLUDE_ALL> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            incomplete: Vec::new(),
+            consumed: 0,
+            buffer_offset: 0,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            read_limit: None,
+            eof: false,
+            dialect: Dialect::Rsn,
+        }
+    }
+
+    /// Sets the minimum number of bytes requested from the reader each time
+    /// more input is needed, and returns self.
+    ///
+    /// The default chunk size is 8 KiB.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than 0");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the syntax accepted by this tokenizer, and returns self.
+    ///
+    /// The default dialect is [`Dialect::Rsn`].
+    #[must_use]
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Stops reading from the reader after `limit` bytes have been read,
+    /// treating the remaining input as if it didn't exist.
+    #[must_use]
+    pub(crate) const fn with_read_limit(mut self, limit: usize) -> Self {
+        self.read_limit = Some(limit);
+        self
+    }
+
+    /// Returns the current byte offset of the tokenizer.
+    #[must_use]
+    pub const fn current_offset(&self) -> usize {
+        self.buffer_offset + self.consumed
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Any input that has been read but not yet tokenized is discarded.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, const INCLUDE_ALL: bool> ReaderTokenizer<R, INCLUDE_ALL>
+where
+    R: Read,
+{
+    /// Reads at least one more chunk of input into the buffer, discarding the
+    /// already tokenized portion of the buffer.
+    fn fill_buffer(&mut self) -> Result<(), Error> {
+        if self.consumed > 0 {
+            self.buffer.drain(..self.consumed);
+            self.buffer_offset += self.consumed;
+            self.consumed = 0;
+        }
+
+        // Grow the request along with the pending input so that very large
+        // tokens don't need to be re-tokenized once per chunk.
+        let mut requested = self.chunk_size.max(self.buffer.len());
+        let mut bytes = core::mem::take(&mut self.incomplete);
+        let existing = bytes.len();
+        if let Some(limit) = self.read_limit {
+            let read = self.buffer_offset + self.buffer.len() + existing;
+            requested = requested.min(limit.saturating_sub(read));
+        }
+        bytes.resize(existing + requested, 0);
+        let read = loop {
+            match self.reader.read(&mut bytes[existing..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    bytes.truncate(existing);
+                    self.incomplete = bytes;
+                    return Err(self.error_at_end(ErrorKind::Io(err.kind())));
+                }
+            }
+        };
+        bytes.truncate(existing + read);
+        self.eof = read == 0;
+
+        match core::str::from_utf8(&bytes) {
+            Ok(valid) => {
+                self.buffer.push_str(valid);
+                Ok(())
+            }
+            Err(err) => {
+                let valid_up_to = err.valid_up_to();
+                self.buffer.push_str(
+                    core::str::from_utf8(&bytes[..valid_up_to]).expect("validated above"),
+                );
+                if err.error_len().is_none() && !self.eof {
+                    // The read ended partway through a multi-byte character.
+                    bytes.drain(..valid_up_to);
+                    self.incomplete = bytes;
+                    Ok(())
+                } else {
+                    let start = self.buffer_offset + self.buffer.len();
+                    let end = start + err.error_len().unwrap_or(bytes.len() - valid_up_to);
+                    self.eof = true;
+                    Err(Error::new(start..end, ErrorKind::InvalidUtf8))
+                }
+            }
+        }
+    }
+
+    fn error_at_end(&self, kind: ErrorKind) -> Error {
+        let offset = self.buffer_offset + self.buffer.len();
+        Error::new(offset..offset, kind)
+    }
+
+    fn next_token(&mut self) -> Option<Result<Token<'static>, Error>> {
+        loop {
+            let window = &self.buffer[self.consumed..];
+            let window_start = self.current_offset();
+            let mut tokenizer = Tokenizer::<INCLUDE_ALL>::new(window).with_dialect(self.dialect);
+            let result = tokenizer.next();
+            let advanced = tokenizer.current_offset();
+            let needs_more_input = !self.eof
+                && match &result {
+                    // The remaining input was empty or only whitespace.
+                    None => true,
+                    // Tokens that end at the edge of the buffer may continue
+                    // in the next chunk.
+                    Some(Ok(token)) => {
+                        token.location.end == window.len() && may_continue(&token.kind)
+                    }
+                    Some(Err(err)) => {
+                        err.kind == ErrorKind::UnexpectedEof || err.location.end >= window.len()
+                    }
+                };
+
+            if needs_more_input {
+                if result.is_none() {
+                    // Nothing but whitespace, which can be discarded.
+                    self.consumed += advanced;
+                }
+                if let Err(err) = self.fill_buffer() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+
+            self.consumed += advanced;
+            return result.map(|result| match result {
+                Ok(mut token) => {
+                    token.location.start += window_start;
+                    token.location.end += window_start;
+                    Ok(token.into_owned())
+                }
+                Err(mut err) => {
+                    err.location.start += window_start;
+                    err.location.end += window_start;
+                    Err(err)
+                }
+            });
+        }
+    }
+}
+
+/// Returns true if a token of `kind` could be extended by additional input.
+fn may_continue(kind: &TokenKind<'_>) -> bool {
+    matches!(
+        kind,
+        TokenKind::Integer(_)
+            | TokenKind::Float(_)
+            | TokenKind::Bool(_)
+            | TokenKind::Identifier(_)
+            | TokenKind::Comment(_)
+            | TokenKind::Whitespace(_)
+    )
+}
+
+impl<R, const INCLUDE_ALL: bool> Iterator for ReaderTokenizer<R, INCLUDE_ALL>
+where
+    R: Read,
+{
+    type Item = Result<Token<'static>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+impl<R, const INCLUDE_ALL: bool> TokenSource<'static> for ReaderTokenizer<R, INCLUDE_ALL>
+where
+    R: Read,
+{
+    fn current_offset(&self) -> usize {
+        self.buffer_offset + self.consumed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::tokenizer::{Balanced, Integer};
+
+    /// A reader that returns at most `max_read` bytes per call to `read`.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        max_read: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.max_read).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[track_caller]
+    fn assert_matches_str_tokenizer(source: &str) {
+        assert_matches_str_tokenizer_in(source, Dialect::Rsn);
+    }
+
+    #[track_caller]
+    fn assert_matches_str_tokenizer_in(source: &str, dialect: Dialect) {
+        let expected = Tokenizer::full(source)
+            .with_dialect(dialect)
+            .map(|token| token.map(Token::into_owned))
+            .collect::<Vec<_>>();
+        for max_read in [1, 2, 3, 7, 64] {
+            for chunk_size in [1, 4, 1024] {
+                let tokens = ReaderTokenizer::full(Trickle {
+                    data: source.as_bytes(),
+                    max_read,
+                })
+                .with_chunk_size(chunk_size)
+                .with_dialect(dialect)
+                .collect::<Vec<_>>();
+                assert_eq!(
+                    tokens, expected,
+                    "max_read: {max_read}, chunk: {chunk_size}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_str_tokenizer() {
+        assert_matches_str_tokenizer(
+            "Named { a: [1, -2, 3.5e10, 0xFF], b: \"hello \\u{1F980} world\", \
+             c: r#\"raw\"#, d: b\"bytes\\x00\", e: 'c', f: b'b', g: true }",
+        );
+        assert_matches_str_tokenizer("// line comment\n/* block /* nested */ */ ident\r\n");
+        assert_matches_str_tokenizer("🦀 \"🦀🦀🦀\" '🦀'");
+        assert_matches_str_tokenizer("12345678901234567890123 -inf +NaN");
+        assert_matches_str_tokenizer("\"unterminated");
+        assert_matches_str_tokenizer("1e");
+    }
+
+    #[test]
+    fn matches_str_tokenizer_dialects() {
+        assert_matches_str_tokenizer_in(
+            "{\"a\": [1, -2.5e3, true, null], \"b\": \"\\ud83e\\udd80\"}",
+            Dialect::Json,
+        );
+        assert_matches_str_tokenizer_in(
+            "#![enable(implicit_some)]\n(a: [1u16, .5, 2f32, 300u8], b: 1u1",
+            Dialect::Ron,
+        );
+    }
+
+    #[test]
+    fn minified_offsets() {
+        let tokens = ReaderTokenizer::minified(Trickle {
+            data: b"  [ 1 ,\n abc ]",
+            max_read: 1,
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(
+            tokens,
+            &[
+                Token::new(2..3, TokenKind::Open(Balanced::Bracket)),
+                Token::new(4..5, TokenKind::Integer(Integer::Usize(1))),
+                Token::new(6..7, TokenKind::Comma),
+                Token::new(9..12, TokenKind::Identifier(Cow::Borrowed("abc"))),
+                Token::new(13..14, TokenKind::Close(Balanced::Bracket)),
+            ]
+        );
+    }
+
+    #[test]
+    fn bounded_buffer() {
+        let mut source = String::from("[");
+        for i in 0_u32..100_000 {
+            source.push_str(&i.to_string());
+            source.push_str(", ");
+        }
+        source.push(']');
+
+        let mut tokenizer = ReaderTokenizer::minified(source.as_bytes()).with_chunk_size(64);
+        let mut count = 0;
+        while let Some(token) = tokenizer.next() {
+            token.unwrap();
+            count += 1;
+            assert!(tokenizer.buffer.capacity() <= 1024);
+        }
+        assert_eq!(count, 200_002);
+        assert_eq!(tokenizer.current_offset(), source.len());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = ReaderTokenizer::minified(&b"\"ab\xFF\""[..])
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err, Error::new(3..4, ErrorKind::InvalidUtf8));
+    }
+
+    #[test]
+    fn io_error() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::from(io::ErrorKind::ConnectionReset))
+            }
+        }
+
+        let err = ReaderTokenizer::minified(Failing)
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Io(io::ErrorKind::ConnectionReset));
+    }
+}
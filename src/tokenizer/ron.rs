@@ -0,0 +1,185 @@
+use alloc::borrow::Cow;
+
+use unicode_ident::is_xid_continue;
+
+use super::{Error, ErrorKind, Integer, Token, TokenKind, Tokenizer};
+
+impl<'a, const INCLUDE_ALL: bool> Tokenizer<'a, INCLUDE_ALL> {
+    /// Tokenizes an inner attribute such as `#![enable(implicit_some)]`,
+    /// whose `#` has already been read. Attributes are returned as comments.
+    pub(super) fn tokenize_ron_attribute(&mut self) -> Result<Token<'a>, Error> {
+        for expected in ['!', '['] {
+            let ch = self.next_or_eof()?;
+            if ch != expected {
+                return Err(self.error_at_last_char(ErrorKind::Unexpected(ch)));
+            }
+        }
+
+        let mut nests = 1;
+        while nests > 0 {
+            match self.next_or_eof()? {
+                '[' => nests += 1,
+                ']' => nests -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(Token::new(
+            self.chars.marked_range(),
+            TokenKind::Comment(Cow::Borrowed(self.chars.marked_str())),
+        ))
+    }
+
+    /// Tokenizes a number beginning with `start_char`, allowing floats
+    /// without an integer part and type suffixes such as `1u8` or `2f32`.
+    pub(super) fn tokenize_ron_number(&mut self, start_char: char) -> Result<Token<'a>, Error> {
+        let token = if start_char == '.'
+            || (matches!(start_char, '+' | '-') && self.chars.peek() == Some('.'))
+        {
+            if start_char != '.' {
+                self.chars.next();
+            }
+            self.tokenize_float(false, false)?
+        } else {
+            self.tokenize_number(start_char as u8)?
+        };
+
+        let rest = &self.chars.source[self.chars.current_offset()..];
+        let suffix = &rest[..rest.find(|ch| !is_xid_continue(ch)).unwrap_or(rest.len())];
+        if !suffix.is_empty()
+            && suffix.len() == rest.len()
+            && SUFFIXES
+                .iter()
+                .any(|known| known.len() > suffix.len() && known.starts_with(suffix))
+        {
+            // The suffix may be incomplete. Reporting this allows a
+            // `ReaderTokenizer` to read more input before trying again.
+            self.skip_suffix(suffix);
+            return Err(self.error(ErrorKind::UnexpectedEof));
+        }
+        let kind = match (token.kind, suffix) {
+            (TokenKind::Integer(integer), "f32" | "f64") => TokenKind::Float(integer.as_f64()),
+            (TokenKind::Float(float), "f32" | "f64") => TokenKind::Float(float),
+            (TokenKind::Integer(integer), suffix) => match integer_fits(integer, suffix) {
+                Some(true) => TokenKind::Integer(integer),
+                Some(false) => {
+                    self.skip_suffix(suffix);
+                    return Err(self.error(ErrorKind::IntegerTooLarge));
+                }
+                // Not a suffix: the next token begins here.
+                None => return Ok(Token::new(token.location, TokenKind::Integer(integer))),
+            },
+            (kind, _) => return Ok(Token::new(token.location, kind)),
+        };
+        self.skip_suffix(suffix);
+        Ok(Token::new(self.chars.marked_range(), kind))
+    }
+
+    fn skip_suffix(&mut self, suffix: &str) {
+        for _ in suffix.chars() {
+            self.chars.next();
+        }
+    }
+}
+
+/// The type suffixes a number may have.
+const SUFFIXES: [&str; 14] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64",
+];
+
+/// Returns whether `integer` fits in the integer type named `suffix`, or
+/// `None` if `suffix` isn't the name of an integer type.
+fn integer_fits(integer: Integer, suffix: &str) -> Option<bool> {
+    Some(match suffix {
+        "u8" => integer.as_u8().is_some(),
+        "u16" => integer.as_u16().is_some(),
+        "u32" => integer.as_u32().is_some(),
+        "u64" => integer.as_u64().is_some(),
+        "u128" => integer.as_u128().is_some(),
+        "usize" => integer.as_usize().is_some(),
+        "i8" => integer.as_i8().is_some(),
+        "i16" => integer.as_i16().is_some(),
+        "i32" => integer.as_i32().is_some(),
+        "i64" => integer.as_i64().is_some(),
+        "i128" => integer.as_i128().is_some(),
+        "isize" => integer.as_isize().is_some(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::tokenizer::{Balanced, Dialect};
+
+    #[track_caller]
+    fn test_tokens(source: &str, tokens: &[Token<'_>]) {
+        assert_eq!(
+            &Tokenizer::minified(source)
+                .with_dialect(Dialect::Ron)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            tokens
+        );
+    }
+
+    #[track_caller]
+    fn test_tokens_err(source: &str, location: core::ops::Range<usize>, kind: &ErrorKind) {
+        let err = Tokenizer::minified(source)
+            .with_dialect(Dialect::Ron)
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("source did not error");
+        assert_eq!(&err.kind, kind);
+        assert_eq!(err.location, location);
+    }
+
+    #[test]
+    fn attributes() {
+        test_tokens(
+            "#![enable(implicit_some)] ()",
+            &[
+                Token::new(
+                    0..25,
+                    TokenKind::Comment(Cow::Borrowed("#![enable(implicit_some)]")),
+                ),
+                Token::new(26..27, TokenKind::Open(Balanced::Paren)),
+                Token::new(27..28, TokenKind::Close(Balanced::Paren)),
+            ],
+        );
+        test_tokens_err("#[enable()]", 1..2, &ErrorKind::Unexpected('['));
+        test_tokens_err("#![enable()", 10..11, &ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn numbers() {
+        test_tokens(".5", &[Token::new(0..2, TokenKind::Float(0.5))]);
+        test_tokens("-.5", &[Token::new(0..3, TokenKind::Float(-0.5))]);
+        test_tokens("1.", &[Token::new(0..2, TokenKind::Float(1.))]);
+        test_tokens(
+            "255u8",
+            &[Token::new(0..5, TokenKind::Integer(Integer::Usize(255)))],
+        );
+        test_tokens(
+            "-1i32",
+            &[Token::new(0..5, TokenKind::Integer(Integer::Isize(-1)))],
+        );
+        test_tokens("2f32", &[Token::new(0..4, TokenKind::Float(2.))]);
+        test_tokens("1.5e1f64", &[Token::new(0..8, TokenKind::Float(15.))]);
+        test_tokens(
+            "[1,inf]",
+            &[
+                Token::new(0..1, TokenKind::Open(Balanced::Bracket)),
+                Token::new(1..2, TokenKind::Integer(Integer::Usize(1))),
+                Token::new(2..3, TokenKind::Comma),
+                Token::new(3..6, TokenKind::Float(f64::INFINITY)),
+                Token::new(6..7, TokenKind::Close(Balanced::Bracket)),
+            ],
+        );
+        test_tokens_err("256u8", 0..5, &ErrorKind::IntegerTooLarge);
+        test_tokens_err("-1u32", 0..5, &ErrorKind::IntegerTooLarge);
+        test_tokens_err("1u1", 0..3, &ErrorKind::UnexpectedEof);
+    }
+}
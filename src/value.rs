@@ -1,12 +1,28 @@
 use alloc::borrow::Cow;
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::fmt::Display;
 use core::str::{self, FromStr};
 
-use crate::parser::{Config, Error, ErrorKind, Event, EventKind, Name, Nested, Parser, Primitive};
-use crate::tokenizer::Integer;
+use crate::parser::{
+    Config, DuplicateKeys, Error, ErrorKind, Event, EventKind, Name, Nested, Parser, Primitive,
+};
+use crate::tokenizer::{Dialect, Integer};
 use crate::writer::{self, Writer};
 
+mod diff;
+mod json;
+mod map;
+mod merge;
+mod query;
+
+pub use self::diff::{diff, Operation, Patch, PatchError, PatchErrorKind};
+pub use self::json::{JsonConfig, JsonError, JsonErrorKind};
+pub use self::map::{Entry, Iter, IterMut, Map, OccupiedEntry, VacantEntry};
+pub use self::merge::{ListMerge, MergeStrategy};
+pub use self::query::{QueryError, QueryErrorKind};
+
 /// A value with a static lifetime.
 pub type OwnedValue = Value<'static>;
 
@@ -87,10 +103,190 @@ impl<'a> Value<'a> {
     ///
     /// Returns any error encountered while parsing `source`.
     pub fn from_str(source: &'a str, config: Config) -> Result<Self, Error> {
-        let mut parser = Parser::new(source, config.include_comments(false));
+        let mut parser = Parser::new(
+            source,
+            config.include_comments(false).recover_from_errors(false),
+        );
         Self::parse(&mut parser)
     }
 
+    /// Parses `json` as a [`Value`], using the mapping described by
+    /// [`JsonConfig`].
+    ///
+    /// Strings that contain no escape sequences borrow from `json`.
+    ///
+    /// ```rust
+    /// use rsn::value::{JsonConfig, Value};
+    ///
+    /// let value =
+    ///     Value::from_json(r#"{"name": "rsn", "tags": null}"#, JsonConfig::default()).unwrap();
+    /// assert_eq!(value.to_string(), r#"{"name":"rsn","tags":()}"#);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not valid JSON, if it exceeds the limits
+    /// of the default [`Config`], such as [`Config::max_depth`], or if
+    /// [`JsonConfig::annotated`] is true and `json` contains an invalid
+    /// annotation.
+    pub fn from_json(json: &'a str, config: JsonConfig) -> Result<Self, JsonError> {
+        json::from_json(json, config)
+    }
+
+    /// Returns this value as JSON, using the mapping described by
+    /// [`JsonConfig`].
+    #[must_use]
+    pub fn to_json_string(&self, config: JsonConfig) -> String {
+        json::to_json_string(self, config)
+    }
+
+    /// Returns the first value matching `path`, such as
+    /// `servers[0].tls.cert`. See [`Value::query`] for the syntax of `path`.
+    ///
+    /// ```rust
+    /// use rsn::parser::Config;
+    /// use rsn::value::Value;
+    ///
+    /// let value = Value::from_str(
+    ///     r#"Config { servers: [Server { tls: { cert: "a.pem" } }] }"#,
+    ///     Config::default(),
+    /// )
+    /// .unwrap();
+    /// let cert = value.get_path("servers[0].tls.cert").unwrap();
+    /// assert_eq!(cert.unwrap().to_string(), r#""a.pem""#);
+    /// assert!(value.get_path("servers[1]").unwrap().is_none());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` is not a valid query.
+    pub fn get_path(&self, path: &str) -> Result<Option<&Self>, QueryError> {
+        let query = query::Query::parse(path)?;
+        let mut first = None;
+        query.visit(self, |value, _| {
+            first = Some(value);
+            false
+        });
+        Ok(first)
+    }
+
+    /// Returns the first value matching `path` mutably. See [`Value::query`]
+    /// for the syntax of `path`.
+    ///
+    /// ```rust
+    /// use rsn::parser::Config;
+    /// use rsn::value::Value;
+    ///
+    /// let mut value = Value::from_str("{ports: [80, 443]}", Config::default()).unwrap();
+    /// *value.get_path_mut("ports[0]").unwrap().unwrap() = Value::Integer(8080_u16.into());
+    /// assert_eq!(value.to_string(), "{ports:[8080,443]}");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` is not a valid query.
+    pub fn get_path_mut(&mut self, path: &str) -> Result<Option<&mut Self>, QueryError> {
+        let query = query::Query::parse(path)?;
+        let Some(location) = query.first_location(self) else {
+            return Ok(None);
+        };
+        Ok(location
+            .into_iter()
+            .try_fold(self, |value, position| query::nested_mut(value, position)))
+    }
+
+    /// Returns every value matching `query`, in the order they appear.
+    ///
+    /// A query is a sequence of segments, each selecting values relative to
+    /// the values selected by the previous segment, starting with `self`:
+    ///
+    /// | Segment       | Selects                                                      |
+    /// |---------------|--------------------------------------------------------------|
+    /// | `.name`       | The value of the map entry or field whose key is `name`      |
+    /// | `["a b"]`     | The value of the map entry whose key is the string `a b`     |
+    /// | `[0]` or `.0` | An element of a list or tuple, or the entry whose key is `0` |
+    /// | `[*]` or `.*` | Every element of a list or tuple, and every value of a map   |
+    /// | `::Name`      | The value itself, if it is a named value called `Name`       |
+    /// | `..`          | The value and every value nested within it, at any depth     |
+    ///
+    /// The `.` may be omitted from the first segment, and a member can follow
+    /// `..` directly, as in `..port`. An empty query selects `self`.
+    ///
+    /// ```rust
+    /// use rsn::parser::Config;
+    /// use rsn::value::Value;
+    ///
+    /// let value = Value::from_str(
+    ///     r#"{
+    ///         plugins: [
+    ///             Http { name: "web", port: 80 },
+    ///             Grpc { name: "api", port: 9000 },
+    ///         ],
+    ///         admin: Http { name: "admin", port: 8080 },
+    ///     }"#,
+    ///     Config::default(),
+    /// )
+    /// .unwrap();
+    /// let names = |query| {
+    ///     value
+    ///         .query(query)
+    ///         .unwrap()
+    ///         .into_iter()
+    ///         .map(ToString::to_string)
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(names("plugins[*].name"), [r#""web""#, r#""api""#]);
+    /// assert_eq!(names("..port"), ["80", "9000", "8080"]);
+    /// assert_eq!(names("..::Http.name"), [r#""web""#, r#""admin""#]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `query` is not a valid query.
+    pub fn query(&self, query: &str) -> Result<Vec<&Self>, QueryError> {
+        let query = query::Query::parse(query)?;
+        let mut matches = Vec::new();
+        query.visit(self, |value, _| {
+            matches.push(value);
+            true
+        });
+        Ok(matches)
+    }
+
+    /// Merges `overlay` into this value, as when applying a configuration file
+    /// on top of a set of defaults.
+    ///
+    /// - Maps are merged recursively: entries from `overlay` are merged into
+    ///   the entries with the same key, and other entries are added to the end
+    ///   of the map. Entries whose value is
+    ///   [`MergeStrategy::delete_marker`] are removed instead.
+    /// - Named values are merged the same way if they have the same name and
+    ///   both contain a map or both contain a tuple.
+    /// - Lists and tuples are merged using [`MergeStrategy::lists`].
+    /// - Otherwise, `overlay` replaces this value.
+    ///
+    /// ```rust
+    /// use rsn::parser::Config;
+    /// use rsn::value::{ListMerge, MergeStrategy, Value};
+    ///
+    /// let mut config = Value::from_str(
+    ///     r#"{ host: "localhost", port: 80, tags: [web] }"#,
+    ///     Config::default(),
+    /// )
+    /// .unwrap();
+    /// let site = Value::from_str("{ port: Unset, tags: [api] }", Config::default()).unwrap();
+    /// config.merge(
+    ///     site,
+    ///     MergeStrategy::default()
+    ///         .lists(ListMerge::Append)
+    ///         .delete_marker(Some("Unset")),
+    /// );
+    /// assert_eq!(config.to_string(), r#"{host:"localhost",tags:[web,api]}"#);
+    /// ```
+    pub fn merge(&mut self, overlay: Value<'a>, strategy: MergeStrategy) {
+        merge::merge(self, overlay, strategy);
+    }
+
     /// Returns a value representing the unit type.
     #[must_use]
     pub const fn unit() -> Self {
@@ -104,45 +300,61 @@ impl<'a> Value<'a> {
                 ErrorKind::UnexpectedEof,
             )
         })?;
-        Self::from_parser_event(event, parser)
+        let config = *parser.config();
+        Self::from_parser_event(event, parser, config)
     }
 
-    fn from_parser_event(event: Event<'a>, parser: &mut Parser<'a>) -> Result<Self, Error> {
+    /// Returns the value that begins with `event`, reading any remaining
+    /// events of a nested value from `events`.
+    pub(crate) fn from_parser_event<Events>(
+        event: Event<'a>,
+        events: &mut Events,
+        config: Config,
+    ) -> Result<Self, Error>
+    where
+        Events: Iterator<Item = Result<Event<'a>, Error>>,
+    {
         match event.kind {
             EventKind::BeginNested {
                 name,
                 kind: kind @ (Nested::Tuple | Nested::List),
-            } => Self::parse_sequence(name, parser, kind),
+            } => Self::parse_sequence(name, events, kind, config),
             EventKind::BeginNested {
                 name,
                 kind: Nested::Map,
-            } => Self::parse_map(name, parser),
+            } => Self::parse_map(name, events, config),
             EventKind::Primitive(primitive) => match primitive {
                 Primitive::Bool(value) => Ok(Value::Bool(value)),
                 Primitive::Integer(value) => Ok(Value::Integer(value)),
                 Primitive::Float(value) => Ok(Value::Float(value)),
                 Primitive::Char(value) => Ok(Value::Char(value)),
                 Primitive::String(value) => Ok(Value::String(value)),
-                Primitive::Identifier(value) => Ok(Value::Identifier(Cow::Borrowed(value))),
+                // The only identifier the JSON dialect produces is `null`.
+                Primitive::Identifier(_) if config.dialect == Dialect::Json => Ok(Value::unit()),
+                Primitive::Identifier(value) => Ok(Value::Identifier(value)),
                 Primitive::Bytes(value) => Ok(Value::Bytes(value)),
             },
-            EventKind::Comment(_) => unreachable!("disabled in parser"),
+            EventKind::Comment(_) | EventKind::Placeholder => unreachable!("disabled in parser"),
             EventKind::EndNested => unreachable!("Parser would error"),
         }
     }
 
-    fn parse_sequence(
+    fn parse_sequence<Events>(
         name: Option<Name<'a>>,
-        parser: &mut Parser<'a>,
+        events: &mut Events,
         kind: Nested,
-    ) -> Result<Self, Error> {
+        config: Config,
+    ) -> Result<Self, Error>
+    where
+        Events: Iterator<Item = Result<Event<'a>, Error>>,
+    {
         let mut list = List::default();
         loop {
-            let event = parser.next().expect("will error or have another event")?;
+            let event = events.next().expect("will error or have another event")?;
             if matches!(event.kind, EventKind::EndNested) {
                 if let Some(name) = name {
                     return Ok(Self::Named(Named {
-                        name: Cow::Borrowed(name.name),
+                        name: name.name,
                         contents: StructContents::Tuple(list),
                     }));
                 }
@@ -153,19 +365,26 @@ impl<'a> Value<'a> {
                     Nested::Map => unreachable!("parse_sequence isn't called on maps"),
                 }
             } else {
-                list.0.push(Self::from_parser_event(event, parser)?);
+                list.0.push(Self::from_parser_event(event, events, config)?);
             }
         }
     }
 
-    fn parse_map(name: Option<Name<'a>>, parser: &mut Parser<'a>) -> Result<Self, Error> {
+    fn parse_map<Events>(
+        name: Option<Name<'a>>,
+        events: &mut Events,
+        config: Config,
+    ) -> Result<Self, Error>
+    where
+        Events: Iterator<Item = Result<Event<'a>, Error>>,
+    {
         let mut map = Map::default();
         loop {
-            let event = parser.next().expect("will error or have another event")?;
+            let event = events.next().expect("will error or have another event")?;
             if matches!(event.kind, EventKind::EndNested) {
                 if let Some(name) = name {
                     return Ok(Self::Named(Named {
-                        name: Cow::Borrowed(name.name),
+                        name: name.name,
                         contents: StructContents::Map(map),
                     }));
                 }
@@ -173,13 +392,28 @@ impl<'a> Value<'a> {
                 return Ok(Self::Map(map));
             }
 
-            let key = Self::from_parser_event(event, parser)?;
+            let key_location = event.location.clone();
+            let key = Self::from_parser_event(event, events, config)?;
             let value = Self::from_parser_event(
-                parser.next().expect("will error or have another event")?,
-                parser,
+                events.next().expect("will error or have another event")?,
+                events,
+                config,
             )?;
 
-            map.0.push((key, value));
+            match map.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                Entry::Occupied(mut entry) => match config.duplicate_keys {
+                    DuplicateKeys::FirstWins => {}
+                    DuplicateKeys::LastWins => {
+                        entry.insert(value);
+                    }
+                    DuplicateKeys::Error => {
+                        return Err(Error::new(key_location, ErrorKind::DuplicateKey));
+                    }
+                },
+            }
         }
     }
 
@@ -190,7 +424,7 @@ impl<'a> Value<'a> {
     /// Returns an error if the `value` cannot be represented losslessly or if
     /// any errors occur from `Serializer` implementations.
     #[cfg(feature = "serde")]
-    pub fn from_serialize<S: ::serde::Serialize>(value: &S) -> Result<Self, ToValueError> {
+    pub fn from_serialize<S: ::serde::Serialize + ?Sized>(value: &S) -> Result<Self, ToValueError> {
         value.serialize(serde::ValueSerializer)
     }
 
@@ -262,6 +496,132 @@ impl<'a> Value<'a> {
             _ => None,
         }
     }
+
+    /// Returns the ordering between `self` and `other`, using a total order
+    /// across all values.
+    ///
+    /// Values of different kinds are ordered by kind, in the order the
+    /// variants of [`Value`] are declared. Values of the same kind are
+    /// ordered as follows:
+    ///
+    /// - Integers are ordered numerically, regardless of which [`Integer`]
+    ///   variant holds them.
+    /// - Floating point numbers are ordered using [`f64::total_cmp`].
+    /// - Identifiers, strings, and byte strings are ordered by their bytes.
+    /// - Named structures are ordered by name, then by their contents. Named
+    ///   maps are ordered before named tuples.
+    /// - Tuples, arrays, and maps are ordered lexicographically by their
+    ///   elements or entries.
+    ///
+    /// ```rust
+    /// use std::cmp::Ordering;
+    ///
+    /// use rsn::value::Value;
+    ///
+    /// let a = Value::from_str("[1, 2]", rsn::parser::Config::default()).unwrap();
+    /// let b = Value::from_str("[1, 2.5]", rsn::parser::Config::default()).unwrap();
+    /// assert_eq!(a.total_cmp(&b), Ordering::Less);
+    /// ```
+    #[must_use]
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => cmp_integers(*a, *b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Char(a), Value::Char(b)) => a.cmp(b),
+            (Value::Byte(a), Value::Byte(b)) => a.cmp(b),
+            (Value::Identifier(a), Value::Identifier(b)) | (Value::String(a), Value::String(b)) => {
+                a.cmp(b)
+            }
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Named(a), Value::Named(b)) => {
+                a.name
+                    .cmp(&b.name)
+                    .then_with(|| match (&a.contents, &b.contents) {
+                        (StructContents::Map(a), StructContents::Map(b)) => a.total_cmp(b),
+                        (StructContents::Tuple(a), StructContents::Tuple(b)) => a.total_cmp(b),
+                        (StructContents::Map(_), StructContents::Tuple(_)) => Ordering::Less,
+                        (StructContents::Tuple(_), StructContents::Map(_)) => Ordering::Greater,
+                    })
+            }
+            (Value::Tuple(a), Value::Tuple(b)) | (Value::Array(a), Value::Array(b)) => {
+                a.total_cmp(b)
+            }
+            (Value::Map(a), Value::Map(b)) => a.total_cmp(b),
+            _ => self.kind_order().cmp(&other.kind_order()),
+        }
+    }
+
+    const fn kind_order(&self) -> u8 {
+        match self {
+            Value::Integer(_) => 0,
+            Value::Float(_) => 1,
+            Value::Bool(_) => 2,
+            Value::Char(_) => 3,
+            Value::Byte(_) => 4,
+            Value::Identifier(_) => 5,
+            Value::String(_) => 6,
+            Value::Bytes(_) => 7,
+            Value::Named(_) => 8,
+            Value::Tuple(_) => 9,
+            Value::Array(_) => 10,
+            Value::Map(_) => 11,
+        }
+    }
+
+    /// Converts this value into its canonical form, so that values that are
+    /// semantically equal become identical. Writing a canonical value using
+    /// [`writer::Config::Canonical`] produces a deterministic representation
+    /// suitable for hashing or signing.
+    ///
+    /// Canonicalizing a value:
+    ///
+    /// - Stores integers in the smallest [`Integer`] variant that can hold
+    ///   them, preferring unsigned variants.
+    /// - Converts bytes into integers, as they are written the same way.
+    /// - Replaces negative zero with zero, and all NaNs with [`f64::NAN`].
+    /// - Sorts the entries of maps and named maps by key, using
+    ///   [`Value::total_cmp`]. If canonicalizing makes keys equal, such as
+    ///   `b'a'` and `97`, the value of the last entry is kept.
+    ///
+    /// ```rust
+    /// use rsn::value::Value;
+    /// use rsn::writer::{Config, Writer};
+    ///
+    /// let mut a = Value::from_str("{b: 2, a: [1, -0.0]}", rsn::parser::Config::default()).unwrap();
+    /// let mut b = Value::from_str("{ a: [1, 0.0], b: 2 }", rsn::parser::Config::default()).unwrap();
+    /// a.canonicalize();
+    /// b.canonicalize();
+    /// assert_eq!(a, b);
+    ///
+    /// let mut writer = Writer::new(String::new(), &Config::Canonical);
+    /// writer.write_value(&a).unwrap();
+    /// assert_eq!(writer.finish(), "{a:[1,0.0],b:2}");
+    /// ```
+    pub fn canonicalize(&mut self) {
+        match self {
+            Value::Integer(integer) => *integer = canonical_integer(*integer),
+            Value::Float(float) => {
+                if float.is_nan() {
+                    *float = f64::NAN;
+                } else if *float == 0.0 {
+                    *float = 0.0;
+                }
+            }
+            Value::Byte(byte) => *self = Value::Integer(Integer::from(*byte)),
+            Value::Bool(_)
+            | Value::Char(_)
+            | Value::Identifier(_)
+            | Value::String(_)
+            | Value::Bytes(_) => {}
+            Value::Named(named) => match &mut named.contents {
+                StructContents::Map(map) => map.canonicalize(),
+                StructContents::Tuple(list) => list.canonicalize(),
+            },
+            Value::Tuple(list) | Value::Array(list) => list.canonicalize(),
+            Value::Map(map) => map.canonicalize(),
+        }
+    }
 }
 
 impl FromStr for Value<'static> {
@@ -280,6 +640,8 @@ impl<'a> Display for Value<'a> {
                 &writer::Config::Pretty {
                     indentation: Cow::Borrowed("  "),
                     newline: Cow::Borrowed("\n"),
+                    max_width: None,
+                    align_map_values: false,
                 },
             )
         } else {
@@ -332,22 +694,6 @@ impl<'a> StructContents<'a> {
     }
 }
 
-/// A list of key-value pairs.
-#[derive(Default, Debug, Clone, PartialEq)]
-pub struct Map<'a>(pub Vec<(Value<'a>, Value<'a>)>);
-
-impl<'a> Map<'a> {
-    /// Returns an owned representation, copying to the heap if needed.
-    #[must_use]
-    pub fn into_owned(self) -> Map<'static> {
-        Map(self
-            .0
-            .into_iter()
-            .map(|(key, value)| (key.into_owned(), value.into_owned()))
-            .collect())
-    }
-}
-
 /// A list of values.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct List<'a>(pub Vec<Value<'a>>);
@@ -364,18 +710,58 @@ impl<'a> List<'a> {
     pub fn into_owned(self) -> List<'static> {
         List(self.0.into_iter().map(Value::into_owned).collect())
     }
+
+    fn canonicalize(&mut self) {
+        for value in &mut self.0 {
+            value.canonicalize();
+        }
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.0.iter().zip(&other.0) {
+            let ordering = a.total_cmp(b);
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+        self.0.len().cmp(&other.0.len())
+    }
+}
+
+/// Compares two integers numerically.
+fn cmp_integers(a: Integer, b: Integer) -> Ordering {
+    match (a.as_i128(), b.as_i128()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        // Only unsigned integers can be too large for an i128.
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (None, None) => a.as_u128().cmp(&b.as_u128()),
+    }
+}
+
+/// Returns `integer` stored in the smallest variant that can hold it.
+fn canonical_integer(integer: Integer) -> Integer {
+    if let Some(unsigned) = integer.as_u128() {
+        Integer::try_from(unsigned).unwrap_or(integer)
+    } else if let Some(signed) = integer.as_i128() {
+        Integer::try_from(signed).unwrap_or(integer)
+    } else {
+        integer
+    }
 }
 
 #[cfg(feature = "serde")]
 mod serde {
     use alloc::borrow::Cow;
     use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
     use alloc::{slice, vec};
     use core::fmt::Display;
     use core::num::TryFromIntError;
     use core::str::{self, Utf8Error};
 
-    use serde::de::{EnumAccess, MapAccess, SeqAccess, VariantAccess};
+    use serde::de::value::StrDeserializer;
+    use serde::de::{EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
     use serde::ser::{
         SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
         SerializeTupleStruct, SerializeTupleVariant,
@@ -383,10 +769,463 @@ mod serde {
     use serde::{Deserializer, Serializer};
 
     use super::{List, StructContents};
-    use crate::parser::Nested;
+    use crate::de::raw;
+    use crate::parser::{Config, Nested};
     use crate::tokenizer::Integer;
     use crate::value::{Map, Named, OwnedValue, Value};
 
+    /// The name of the newtype struct that [`Value`] serializes and
+    /// deserializes as.
+    pub(crate) const NAME: &str = "$__rsn_private_Value";
+    /// The name of the newtype struct that identifiers are serialized as.
+    const IDENTIFIER: &str = "$__rsn_private_Identifier";
+    /// The name of the newtype struct that named values are serialized as.
+    /// The named value is serialized as a map containing one entry from its
+    /// name to its contents.
+    const NAMED: &str = "$__rsn_private_Named";
+
+    impl serde::Serialize for Value<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct(NAME, &Contents(self))
+        }
+    }
+
+    /// Serializes the contents of a [`Value`].
+    ///
+    /// `None`, `Some(_)`, and `()` are serialized as Serde's optional and unit
+    /// types. Other identifiers and named values are wrapped in newtype
+    /// structs that [`ValueSerializer`] uses to restore them, while other
+    /// formats see a string and a single-entry map.
+    struct Contents<'a, 'b>(&'a Value<'b>);
+
+    impl serde::Serialize for Contents<'_, '_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self.0 {
+                Value::Integer(value) => match *value {
+                    Integer::Usize(value) => value.serialize(serializer),
+                    Integer::Isize(value) => value.serialize(serializer),
+                    Integer::UnsignedLarge(value) => value.serialize(serializer),
+                    Integer::SignedLarge(value) => value.serialize(serializer),
+                },
+                Value::Float(value) => serializer.serialize_f64(*value),
+                Value::Bool(value) => serializer.serialize_bool(*value),
+                Value::Char(value) => serializer.serialize_char(*value),
+                Value::Byte(value) => serializer.serialize_u8(*value),
+                Value::Identifier(value) if value == "None" => serializer.serialize_none(),
+                Value::Identifier(value) => serializer.serialize_newtype_struct(IDENTIFIER, value),
+                Value::String(value) => serializer.serialize_str(value),
+                Value::Bytes(value) => serializer.serialize_bytes(value),
+                Value::Named(Named {
+                    name,
+                    contents: StructContents::Tuple(list),
+                }) if name == "Some" && list.0.len() == 1 => {
+                    serializer.serialize_some(&Contents(&list.0[0]))
+                }
+                Value::Named(named) => {
+                    serializer.serialize_newtype_struct(NAMED, &NamedEntry(named))
+                }
+                Value::Tuple(list) if list.0.is_empty() => serializer.serialize_unit(),
+                Value::Tuple(list) => {
+                    let mut tuple = serializer.serialize_tuple(list.0.len())?;
+                    for value in &list.0 {
+                        tuple.serialize_element(&Contents(value))?;
+                    }
+                    tuple.end()
+                }
+                Value::Array(list) => {
+                    let mut seq = serializer.serialize_seq(Some(list.0.len()))?;
+                    for value in &list.0 {
+                        seq.serialize_element(&Contents(value))?;
+                    }
+                    seq.end()
+                }
+                Value::Map(map) => serialize_map(map, serializer),
+            }
+        }
+    }
+
+    fn serialize_map<S>(map: &Map<'_>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut serialize = serializer.serialize_map(Some(map.len()))?;
+        for (key, value) in map {
+            serialize.serialize_entry(&Contents(key), &Contents(value))?;
+        }
+        serialize.end()
+    }
+
+    /// Serializes a [`Named`] as a map containing one entry from its name to
+    /// its contents.
+    struct NamedEntry<'a, 'b>(&'a Named<'b>);
+
+    impl serde::Serialize for NamedEntry<'_, '_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(&*self.0.name, &NamedContents(&self.0.contents))?;
+            map.end()
+        }
+    }
+
+    struct NamedContents<'a, 'b>(&'a StructContents<'b>);
+
+    impl serde::Serialize for NamedContents<'_, '_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self.0 {
+                StructContents::Map(map) => serialize_map(map, serializer),
+                StructContents::Tuple(list) => {
+                    let mut tuple = serializer.serialize_tuple(list.0.len())?;
+                    for value in &list.0 {
+                        tuple.serialize_element(&Contents(value))?;
+                    }
+                    tuple.end()
+                }
+            }
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Value<'_> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_newtype_struct(NAME, MarkerVisitor)
+        }
+    }
+
+    /// Visits the newtype struct a [`Value`] is deserialized as.
+    ///
+    /// Deserializers that understand Rsn hand over the parsed value using
+    /// [`visit_value`], which preserves every kind of value. Other
+    /// deserializers visit the newtype struct, and its contents are
+    /// deserialized using [`ValueVisitor`].
+    struct MarkerVisitor;
+
+    impl<'de> Visitor<'de> for MarkerVisitor {
+        type Value = OwnedValue;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a value")
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(ValueVisitor)
+        }
+
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where
+            A: EnumAccess<'de>,
+        {
+            let (name, contents) = data.variant::<String>()?;
+            contents.tuple_variant(0, HandoffVisitor(name))
+        }
+    }
+
+    /// Visits `value` with the visitor a [`Value`] is deserialized with.
+    ///
+    /// Identifiers, bytes, named values, and tuples can't be told apart from
+    /// other values by [`ValueVisitor`], so they are handed over as an enum
+    /// using [`Handoff`]. Other values are visited as a newtype struct whose
+    /// nested values are handed over the same way.
+    pub(crate) fn visit_value<'de, V>(
+        value: &Value<'de>,
+        visitor: V,
+    ) -> Result<V::Value, FromValueError>
+    where
+        V: Visitor<'de>,
+    {
+        match value {
+            Value::Identifier(_) | Value::Byte(_) | Value::Named(_) | Value::Tuple(_) => {
+                visitor.visit_enum(Handoff(value))
+            }
+            _ => visitor.visit_newtype_struct(ValueDeserializer(value)),
+        }
+    }
+
+    /// Hands an identifier, byte, named value, or tuple to [`MarkerVisitor`].
+    ///
+    /// The variant is the identifier or name, or empty for bytes and tuples.
+    /// Its contents are visited as a unit, a `u8`, a sequence, or a map by
+    /// [`VariantAccess::tuple_variant`].
+    struct Handoff<'a, 'de>(&'a Value<'de>);
+
+    impl<'de> EnumAccess<'de> for Handoff<'_, 'de> {
+        type Error = FromValueError;
+        type Variant = Self;
+
+        fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where
+            V: serde::de::DeserializeSeed<'de>,
+        {
+            let name = match self.0 {
+                Value::Identifier(name) | Value::Named(Named { name, .. }) => &**name,
+                _ => "",
+            };
+            Ok((
+                seed.deserialize(StrDeserializer::<Self::Error>::new(name))?,
+                self,
+            ))
+        }
+    }
+
+    impl<'de> VariantAccess<'de> for Handoff<'_, 'de> {
+        type Error = FromValueError;
+
+        fn unit_variant(self) -> Result<(), Self::Error> {
+            unreachable!("values are handed over as tuple variants")
+        }
+
+        fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Self::Error>
+        where
+            T: serde::de::DeserializeSeed<'de>,
+        {
+            unreachable!("values are handed over as tuple variants")
+        }
+
+        fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            match self.0 {
+                Value::Byte(byte) => visitor.visit_u8(*byte),
+                Value::Named(Named {
+                    contents: StructContents::Map(map),
+                    ..
+                }) => visitor.visit_map(MapDeserializer::new(map)),
+                Value::Named(Named {
+                    contents: StructContents::Tuple(list),
+                    ..
+                })
+                | Value::Tuple(list) => visitor.visit_seq(SequenceDeserializer(list.0.iter())),
+                _ => visitor.visit_unit(),
+            }
+        }
+
+        fn struct_variant<V>(
+            self,
+            _fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            unreachable!("values are handed over as tuple variants")
+        }
+    }
+
+    /// Restores a value handed over by [`Handoff`] from its variant's name.
+    struct HandoffVisitor(String);
+
+    impl<'de> Visitor<'de> for HandoffVisitor {
+        type Value = OwnedValue;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("the contents of a value")
+        }
+
+        fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Byte(v))
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Identifier(Cow::Owned(self.0)))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut list = List::default();
+            while let Some(value) = seq.next_element()? {
+                list.0.push(value);
+            }
+            if self.0.is_empty() {
+                Ok(Value::Tuple(list))
+            } else {
+                Ok(Value::Named(Named {
+                    name: Cow::Owned(self.0),
+                    contents: StructContents::Tuple(list),
+                }))
+            }
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut contents = Map::default();
+            while let Some((key, value)) = map.next_entry()? {
+                contents.insert(key, value);
+            }
+            Ok(Value::Named(Named {
+                name: Cow::Owned(self.0),
+                contents: StructContents::Map(contents),
+            }))
+        }
+    }
+
+    /// Deserializes a [`Value`] from a self-describing format.
+    struct ValueVisitor;
+
+    impl<'de> Visitor<'de> for ValueVisitor {
+        type Value = OwnedValue;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a value")
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Bool(v))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Integer(Integer::from(v)))
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Integer::try_from(v).map(Value::Integer).map_err(E::custom)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Integer(Integer::from(v)))
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Integer::try_from(v).map(Value::Integer).map_err(E::custom)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Float(v))
+        }
+
+        fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Char(v))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::String(Cow::Owned(v.to_string())))
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::String(Cow::Owned(v)))
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Bytes(Cow::Owned(v.to_vec())))
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Bytes(Cow::Owned(v)))
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::Identifier(Cow::Borrowed("None")))
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(Value::Named(Named {
+                name: Cow::Borrowed("Some"),
+                contents: StructContents::Tuple(List(vec![deserializer.deserialize_any(self)?])),
+            }))
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Value::unit())
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut list = List::default();
+            while let Some(value) = seq.next_element()? {
+                list.0.push(value);
+            }
+            Ok(Value::Array(list))
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut contents = Map::default();
+            while let Some((key, value)) = map.next_entry()? {
+                contents.insert(key, value);
+            }
+            Ok(Value::Map(contents))
+        }
+    }
+
     pub struct ValueSerializer;
 
     impl Serializer for ValueSerializer {
@@ -495,9 +1334,42 @@ mod serde {
         where
             T: serde::Serialize + ?Sized,
         {
+            let value = value.serialize(ValueSerializer)?;
+            match name {
+                raw::NAME => {
+                    let Value::String(source) = value else {
+                        unreachable!("raw values serialize a string")
+                    };
+                    return Value::from_str(&source, Config::default())
+                        .map(Value::into_owned)
+                        .map_err(|err| ToValueError::Message(err.to_string()));
+                }
+                NAME => return Ok(value),
+                IDENTIFIER => {
+                    let Value::String(identifier) = value else {
+                        unreachable!("identifiers serialize a string")
+                    };
+                    return Ok(Value::Identifier(identifier));
+                }
+                NAMED => {
+                    let Value::Map(map) = value else {
+                        unreachable!("named values serialize a map")
+                    };
+                    let Some((Value::String(name), contents)) = map.into_iter().next() else {
+                        unreachable!("named values serialize one entry")
+                    };
+                    let contents = match contents {
+                        Value::Map(map) => StructContents::Map(map),
+                        Value::Tuple(list) | Value::Array(list) => StructContents::Tuple(list),
+                        _ => unreachable!("named values serialize a map or tuple"),
+                    };
+                    return Ok(Value::Named(Named { name, contents }));
+                }
+                _ => {}
+            }
             Ok(Value::Named(Named {
                 name: Cow::Owned(name.to_string()),
-                contents: StructContents::Tuple(List(vec![value.serialize(ValueSerializer)?])),
+                contents: StructContents::Tuple(List(vec![value])),
             }))
         }
 
@@ -553,6 +1425,7 @@ mod serde {
             Ok(MapSerializer {
                 name: None,
                 contents: Map::default(),
+                key: None,
             })
         }
 
@@ -564,6 +1437,7 @@ mod serde {
             Ok(MapSerializer {
                 name: Some(name.to_string()),
                 contents: Map::default(),
+                key: None,
             })
         }
 
@@ -577,6 +1451,7 @@ mod serde {
             Ok(MapSerializer {
                 name: Some(variant.to_string()),
                 contents: Map::default(),
+                key: None,
             })
         }
 
@@ -682,6 +1557,7 @@ mod serde {
     pub struct MapSerializer {
         name: Option<String>,
         contents: Map<'static>,
+        key: Option<OwnedValue>,
     }
 
     impl SerializeMap for MapSerializer {
@@ -692,10 +1568,7 @@ mod serde {
         where
             T: serde::Serialize + ?Sized,
         {
-            self.contents.0.push((
-                key.serialize(ValueSerializer)?,
-                Value::Tuple(List::default()),
-            ));
+            self.key = Some(key.serialize(ValueSerializer)?);
             Ok(())
         }
 
@@ -703,11 +1576,8 @@ mod serde {
         where
             T: serde::Serialize + ?Sized,
         {
-            self.contents
-                .0
-                .last_mut()
-                .expect("serialize_key not called")
-                .1 = value.serialize(ValueSerializer)?;
+            let key = self.key.take().expect("serialize_key not called");
+            self.contents.insert(key, value.serialize(ValueSerializer)?);
             Ok(())
         }
 
@@ -731,8 +1601,11 @@ mod serde {
         where
             T: serde::Serialize + ?Sized,
         {
-            SerializeMap::serialize_key(self, key)?;
-            SerializeMap::serialize_value(self, value)
+            self.contents.insert(
+                Value::Identifier(Cow::Borrowed(key)),
+                value.serialize(ValueSerializer)?,
+            );
+            Ok(())
         }
 
         fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -748,8 +1621,11 @@ mod serde {
         where
             T: serde::Serialize + ?Sized,
         {
-            SerializeMap::serialize_key(self, key)?;
-            SerializeMap::serialize_value(self, value)
+            self.contents.insert(
+                Value::Identifier(Cow::Borrowed(key)),
+                value.serialize(ValueSerializer)?,
+            );
+            Ok(())
         }
 
         fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -1002,6 +1878,9 @@ mod serde {
         where
             V: serde::de::Visitor<'de>,
         {
+            if name == NAME {
+                return visit_value(self.0, visitor);
+            }
             match &self.0 {
                 Value::Named(named) if named.name == name => match &named.contents {
                     StructContents::Tuple(contents) => {
@@ -1012,7 +1891,7 @@ mod serde {
                         }
                     }
                     StructContents::Map(map) => {
-                        if let Some((_, first)) = map.0.first() {
+                        if let Some((_, first)) = map.get_index(0) {
                             visitor.visit_newtype_struct(ValueDeserializer(first))
                         } else {
                             visitor.visit_newtype_struct(ValueDeserializer(&Value::unit()))
@@ -1218,9 +2097,8 @@ mod serde {
             K: serde::de::DeserializeSeed<'de>,
         {
             self.map
-                .0
-                .get(self.index)
-                .map(|item| seed.deserialize(ValueDeserializer(&item.0)))
+                .get_index(self.index)
+                .map(|(key, _)| seed.deserialize(ValueDeserializer(key)))
                 .transpose()
         }
 
@@ -1228,7 +2106,7 @@ mod serde {
         where
             V: serde::de::DeserializeSeed<'de>,
         {
-            let value = seed.deserialize(ValueDeserializer(&self.map.0[self.index].1))?;
+            let value = seed.deserialize(ValueDeserializer(&self.map.as_slice()[self.index].1))?;
             self.index += 1;
             Ok(value)
         }
@@ -1405,5 +2283,121 @@ fn display() {
     );
 }
 
+#[test]
+fn canonical() {
+    fn canonical(source: &str) -> String {
+        let mut value = Value::from_str(source, Config::default()).unwrap();
+        value.canonicalize();
+        let mut writer = Writer::new(String::new(), &writer::Config::Canonical);
+        writer.write_value(&value).unwrap();
+        writer.finish()
+    }
+
+    let expected = r#"Doc{a:[1,-2,0.0,1.5,100.0,NaN,-inf],b:{"x":Some(98),"y":(1,"\n")},c:Unit}"#;
+    assert_eq!(
+        canonical(
+            r#"Doc {
+                // Comments, whitespace, and trailing commas are removed.
+                c: Unit,
+                b: { "y": (0x1, "\n"), "x": Some(b'b'), },
+                a: [1, -2, -0.0, 1.50, 1e2, NaN, -inf],
+            }"#
+        ),
+        expected
+    );
+    assert_eq!(canonical(expected), expected);
+
+    assert_eq!(
+        Value::Integer(Integer::Isize(5)).total_cmp(&Value::Integer(Integer::Usize(5))),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Value::Integer(Integer::Isize(-1)).total_cmp(&Value::Integer(Integer::Usize(0))),
+        Ordering::Less
+    );
+    assert_eq!(
+        Value::Integer(Integer::Usize(10)).total_cmp(&Value::Float(0.)),
+        Ordering::Less
+    );
+    assert_eq!(
+        Value::Map(Map::default()).total_cmp(&Value::Array(List::new())),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn duplicate_keys() {
+    use alloc::string::ToString;
+
+    use crate::parser::DuplicateKeys;
+
+    let source = "{a: 1, b: 2, a: 3}";
+    let parse = |duplicate_keys| {
+        Value::from_str(source, Config::default().duplicate_keys(duplicate_keys))
+            .map(|value| value.to_string())
+    };
+    assert_eq!(parse(DuplicateKeys::LastWins).unwrap(), "{a:3,b:2}");
+    assert_eq!(parse(DuplicateKeys::FirstWins).unwrap(), "{a:1,b:2}");
+    let err = parse(DuplicateKeys::Error).unwrap_err();
+    assert_eq!(err.location, 13..14);
+    assert_eq!(err.kind, ErrorKind::DuplicateKey);
+
+    let mut value = Value::from_str("{b'a': 1, 97: 2}", Config::default()).unwrap();
+    value.canonicalize();
+    assert_eq!(value.to_string(), "{97:2}");
+}
+
+#[cfg(feature = "serde")]
+pub(crate) use self::serde::{visit_value, NAME};
 #[cfg(feature = "serde")]
 pub use self::serde::{FromValueError, ToValueError};
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde() {
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Plugin {
+        name: String,
+        settings: OwnedValue,
+    }
+
+    let settings = r#"{mode:Fast,limits:Limits(1,[2,3.5]),window:Window{x:1},key:b"k",pair:(1,"a"),unit:(),some:Some(1),none:None}"#;
+    let source = alloc::format!(r#"Plugin{{name:"a",settings:{settings}}}"#);
+    let plugin: Plugin = crate::from_str(&source).unwrap();
+    let expected = Value::from_str(settings, Config::default()).unwrap();
+    assert_eq!(plugin.settings, expected);
+    assert_eq!(crate::to_string(&plugin).unwrap(), source);
+
+    assert_eq!(Value::from_serialize(&expected).unwrap(), expected);
+    assert_eq!(expected.to_deserialize::<OwnedValue>().unwrap(), expected);
+
+    // Values are handed over without being written as text, which would
+    // lose the sign of zero and the payload of NaN.
+    let settings = r#"[-0.0,(1,"\n"),Unit]"#;
+    let source = alloc::format!(r#"Plugin{{name:"a",settings:{settings}}}"#);
+    let plugin: Plugin = crate::from_str(&source).unwrap();
+    assert_eq!(
+        plugin.settings,
+        Value::from_str(settings, Config::default()).unwrap()
+    );
+    let Value::Array(list) = &plugin.settings else {
+        unreachable!("settings are a list")
+    };
+    assert!(matches!(list.0[0], Value::Float(zero) if zero.is_sign_negative()));
+    let nan = f64::from_bits(0x7ff8_0000_0000_0001);
+    let values = Value::Array(List(alloc::vec![Value::Float(nan), Value::Byte(b'a')]));
+    let Value::Array(list) = values.to_deserialize::<OwnedValue>().unwrap() else {
+        unreachable!("values are a list")
+    };
+    assert!(matches!(list.0[0], Value::Float(float) if float.to_bits() == nan.to_bits()));
+    assert_eq!(list.0[1], Value::Byte(b'a'));
+    assert_eq!(
+        crate::ser::Config::pretty()
+            .implicit_map_at_root(true)
+            .serialize(&Value::from_str("{a:1,b:X(2)}", Config::default()).unwrap())
+            .unwrap(),
+        "a: 1\nb: X(\n  2\n)\n"
+    );
+}
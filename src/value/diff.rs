@@ -0,0 +1,688 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use super::{List, Map, Named, OwnedValue, StructContents, Value};
+
+/// Returns the operations that change `from` into `to`.
+///
+/// Values that [`Value::total_cmp`] considers equal are left unchanged.
+/// Maps are compared by key, ignoring the order of their entries. Lists and
+/// tuples are compared using their longest common subsequence, so adding or
+/// removing a value produces a single operation. Named values with different
+/// names but the same kind of contents are renamed, and their contents are
+/// compared.
+///
+/// Because added map entries are inserted at the end of their map, applying
+/// the patch to `from` can produce maps whose entries are in a different order
+/// than in `to`. The patched value is equal to `to` once both are
+/// canonicalized using [`Value::canonicalize`].
+///
+/// ```rust
+/// use rsn::parser::Config;
+/// use rsn::value::{diff, Value};
+///
+/// let from =
+///     Value::from_str("Server { ports: [80, 443], tls: true }", Config::default()).unwrap();
+/// let to = Value::from_str("Proxy { ports: [80, 8080, 443] }", Config::default()).unwrap();
+/// let patch = diff(&from, &to);
+/// assert_eq!(
+///     patch.to_string(),
+///     "[Rename{path:[],name:Proxy},Add{path:[ports,1],value:8080},Remove{path:[tls]}]"
+/// );
+///
+/// let mut patched = from.clone();
+/// patch.apply(&mut patched).unwrap();
+/// assert_eq!(patched, to);
+/// ```
+#[must_use]
+pub fn diff(from: &Value<'_>, to: &Value<'_>) -> Patch {
+    let mut patch = Patch::default();
+    diff_values(from, to, &mut Vec::new(), &mut patch.0);
+    patch
+}
+
+/// A list of operations that change a [`Value`], produced by [`diff`].
+///
+/// Each operation addresses the value it changes using a path of map keys and
+/// list or tuple indices, starting from the root value. Operations are applied
+/// in order, so indices refer to the value as changed by the previous
+/// operations.
+///
+/// A patch is written as Rsn using [`Patch::to_value`] or its [`Display`]
+/// implementation, and read using [`Patch::from_value`]. When the `serde`
+/// feature is enabled, `Patch` also implements `Serialize` and `Deserialize`
+/// using the same representation:
+///
+/// ```rsn
+/// [
+///     Rename { path: [], name: Proxy },
+///     Add { path: [ports, 1], value: 8080 },
+///     Remove { path: [tls] },
+/// ]
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Patch(pub Vec<Operation>);
+
+impl Patch {
+    /// Applies the operations in this patch to `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an operation's path does not address a value, or if
+    /// a renamed value is not a named value. The operations before the failed
+    /// operation remain applied.
+    pub fn apply(&self, value: &mut Value<'_>) -> Result<(), PatchError> {
+        for (index, operation) in self.0.iter().enumerate() {
+            operation
+                .apply(value)
+                .map_err(|kind| PatchError::new(index, kind))?;
+        }
+        Ok(())
+    }
+
+    /// Returns this patch as a list of named values.
+    #[must_use]
+    pub fn to_value(&self) -> OwnedValue {
+        Value::Array(List(self.0.iter().map(Operation::to_value).collect()))
+    }
+
+    /// Reads a patch from the representation returned by
+    /// [`Patch::to_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` is not a list of operations.
+    pub fn from_value(value: &Value<'_>) -> Result<Self, PatchError> {
+        let (Value::Array(list) | Value::Tuple(list)) = value else {
+            return Err(PatchError::new(0, PatchErrorKind::ExpectedList));
+        };
+        list.0
+            .iter()
+            .enumerate()
+            .map(|(index, operation)| {
+                Operation::from_value(operation)
+                    .ok_or_else(|| PatchError::new(index, PatchErrorKind::InvalidOperation))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Returns true if this patch contains no operations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Patch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.to_value(), f)
+    }
+}
+
+/// An operation in a [`Patch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Adds `value` at `path`.
+    ///
+    /// If the last segment of `path` addresses a list or tuple, the value is
+    /// inserted at that index. Otherwise, the value is inserted into the map,
+    /// replacing any existing entry with the same key. New entries are added
+    /// to the end of the map.
+    Add {
+        /// The location to add the value at.
+        path: Vec<OwnedValue>,
+        /// The value to add.
+        value: OwnedValue,
+    },
+    /// Removes the value at `path`.
+    Remove {
+        /// The location of the value to remove.
+        path: Vec<OwnedValue>,
+    },
+    /// Replaces the value at `path` with `value`.
+    Replace {
+        /// The location of the value to replace.
+        path: Vec<OwnedValue>,
+        /// The new value.
+        value: OwnedValue,
+    },
+    /// Changes the name of the named value at `path` to `name`.
+    Rename {
+        /// The location of the named value.
+        path: Vec<OwnedValue>,
+        /// The new name.
+        name: String,
+    },
+}
+
+impl Operation {
+    /// Returns the path of the value this operation changes.
+    #[must_use]
+    pub fn path(&self) -> &[OwnedValue] {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Rename { path, .. } => path,
+        }
+    }
+
+    fn apply(&self, target: &mut Value<'_>) -> Result<(), PatchErrorKind> {
+        match self {
+            Operation::Add { path, value } => {
+                let Some((segment, parent)) = path.split_last() else {
+                    *target = value.clone();
+                    return Ok(());
+                };
+                match nested(lookup(target, parent)?) {
+                    Nested::List(list) => {
+                        let index = segment
+                            .as_usize()
+                            .filter(|index| *index <= list.0.len())
+                            .ok_or(PatchErrorKind::PathNotFound)?;
+                        list.0.insert(index, value.clone());
+                    }
+                    Nested::Map(map) => {
+                        map.insert(segment.clone(), value.clone());
+                    }
+                    Nested::None => return Err(PatchErrorKind::PathNotFound),
+                }
+            }
+            Operation::Remove { path } => {
+                let (segment, parent) = path.split_last().ok_or(PatchErrorKind::EmptyPath)?;
+                let removed = match nested(lookup(target, parent)?) {
+                    Nested::List(list) => segment
+                        .as_usize()
+                        .filter(|index| *index < list.0.len())
+                        .map(|index| list.0.remove(index)),
+                    Nested::Map(map) => map.remove(segment),
+                    Nested::None => None,
+                };
+                removed.ok_or(PatchErrorKind::PathNotFound)?;
+            }
+            Operation::Replace { path, value } => *lookup(target, path)? = value.clone(),
+            Operation::Rename { path, name } => match lookup(target, path)? {
+                Value::Named(named) => named.name = Cow::Owned(name.clone()),
+                _ => return Err(PatchErrorKind::NotNamed),
+            },
+        }
+        Ok(())
+    }
+
+    fn to_value(&self) -> OwnedValue {
+        let path = ("path", Value::Array(List(self.path().to_vec())));
+        let (name, fields) = match self {
+            Operation::Add { value, .. } => ("Add", vec![path, ("value", value.clone())]),
+            Operation::Remove { .. } => ("Remove", vec![path]),
+            Operation::Replace { value, .. } => ("Replace", vec![path, ("value", value.clone())]),
+            Operation::Rename { name, .. } => (
+                "Rename",
+                vec![path, ("name", Value::Identifier(Cow::Owned(name.clone())))],
+            ),
+        };
+        Value::Named(Named {
+            name: Cow::Borrowed(name),
+            contents: StructContents::Map(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (Value::Identifier(Cow::Borrowed(key)), value))
+                    .collect(),
+            ),
+        })
+    }
+
+    fn from_value(value: &Value<'_>) -> Option<Self> {
+        let Value::Named(Named {
+            name,
+            contents: StructContents::Map(fields),
+        }) = value
+        else {
+            return None;
+        };
+        let path = match fields.get_str("path")? {
+            Value::Array(path) | Value::Tuple(path) => path
+                .0
+                .iter()
+                .map(|segment| segment.clone().into_owned())
+                .collect(),
+            _ => return None,
+        };
+        let value = || {
+            fields
+                .get_str("value")
+                .map(|value| value.clone().into_owned())
+        };
+        match &**name {
+            "Add" => Some(Operation::Add {
+                path,
+                value: value()?,
+            }),
+            "Remove" => Some(Operation::Remove { path }),
+            "Replace" => Some(Operation::Replace {
+                path,
+                value: value()?,
+            }),
+            "Rename" => match fields.get_str("name")? {
+                Value::Identifier(name) | Value::String(name) => Some(Operation::Rename {
+                    path,
+                    name: name.to_string(),
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Patch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_value().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Patch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = OwnedValue::deserialize(deserializer)?;
+        Self::from_value(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// An error that arose while applying or reading a [`Patch`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PatchError {
+    /// The index of the operation that caused this error.
+    pub operation: usize,
+    /// The kind of error that occurred.
+    pub kind: PatchErrorKind,
+}
+
+impl PatchError {
+    const fn new(operation: usize, kind: PatchErrorKind) -> Self {
+        Self { operation, kind }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatchError {}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "operation {}: {}", self.operation, self.kind)
+    }
+}
+
+/// A kind of error that arose while applying or reading a [`Patch`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PatchErrorKind {
+    /// The operation's path did not address a value.
+    PathNotFound,
+    /// A remove operation had an empty path.
+    EmptyPath,
+    /// A rename operation addressed a value that was not a named value.
+    NotNamed,
+    /// The patch being read was not a list. The error's operation is 0.
+    ExpectedList,
+    /// The operation being read was not a valid operation.
+    InvalidOperation,
+}
+
+impl Display for PatchErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            PatchErrorKind::PathNotFound => "path not found",
+            PatchErrorKind::EmptyPath => "the root value cannot be removed",
+            PatchErrorKind::NotNamed => "expected a named value",
+            PatchErrorKind::ExpectedList => "expected a list of operations",
+            PatchErrorKind::InvalidOperation => "invalid operation",
+        })
+    }
+}
+
+/// The values nested directly within a value.
+enum Nested<'v, 'a> {
+    List(&'v mut List<'a>),
+    Map(&'v mut Map<'a>),
+    None,
+}
+
+fn nested<'v, 'a>(value: &'v mut Value<'a>) -> Nested<'v, 'a> {
+    match value {
+        Value::Named(Named {
+            contents: StructContents::Tuple(list),
+            ..
+        })
+        | Value::Tuple(list)
+        | Value::Array(list) => Nested::List(list),
+        Value::Named(Named {
+            contents: StructContents::Map(map),
+            ..
+        })
+        | Value::Map(map) => Nested::Map(map),
+        _ => Nested::None,
+    }
+}
+
+fn lookup<'v, 'a>(
+    value: &'v mut Value<'a>,
+    path: &[OwnedValue],
+) -> Result<&'v mut Value<'a>, PatchErrorKind> {
+    path.iter().try_fold(value, |value, segment| {
+        match nested(value) {
+            Nested::List(list) => segment.as_usize().and_then(|index| list.0.get_mut(index)),
+            Nested::Map(map) => map.get_mut(segment),
+            Nested::None => None,
+        }
+        .ok_or(PatchErrorKind::PathNotFound)
+    })
+}
+
+fn is_same(a: &Value<'_>, b: &Value<'_>) -> bool {
+    a.total_cmp(b).is_eq()
+}
+
+fn diff_values(
+    from: &Value<'_>,
+    to: &Value<'_>,
+    path: &mut Vec<OwnedValue>,
+    operations: &mut Vec<Operation>,
+) {
+    match (from, to) {
+        (Value::Map(from), Value::Map(to)) => diff_maps(from, to, path, operations),
+        (Value::Tuple(from), Value::Tuple(to)) | (Value::Array(from), Value::Array(to)) => {
+            diff_lists(&from.0, &to.0, path, operations);
+        }
+        (Value::Named(from_named), Value::Named(to_named)) => {
+            match (&from_named.contents, &to_named.contents) {
+                (StructContents::Map(from), StructContents::Map(to)) => {
+                    rename(from_named, to_named, path, operations);
+                    diff_maps(from, to, path, operations);
+                }
+                (StructContents::Tuple(from), StructContents::Tuple(to)) => {
+                    rename(from_named, to_named, path, operations);
+                    diff_lists(&from.0, &to.0, path, operations);
+                }
+                _ => operations.push(Operation::Replace {
+                    path: path.clone(),
+                    value: to.clone().into_owned(),
+                }),
+            }
+        }
+        _ if is_same(from, to) => {}
+        _ => operations.push(Operation::Replace {
+            path: path.clone(),
+            value: to.clone().into_owned(),
+        }),
+    }
+}
+
+fn rename(from: &Named<'_>, to: &Named<'_>, path: &[OwnedValue], operations: &mut Vec<Operation>) {
+    if from.name != to.name {
+        operations.push(Operation::Rename {
+            path: path.to_vec(),
+            name: to.name.to_string(),
+        });
+    }
+}
+
+fn diff_maps(
+    from: &Map<'_>,
+    to: &Map<'_>,
+    path: &mut Vec<OwnedValue>,
+    operations: &mut Vec<Operation>,
+) {
+    for (key, from) in from {
+        path.push(key.clone().into_owned());
+        match to.get(key) {
+            Some(to) => diff_values(from, to, path, operations),
+            None => operations.push(Operation::Remove { path: path.clone() }),
+        }
+        path.pop();
+    }
+    for (key, value) in to {
+        if !from.contains_key(key) {
+            let mut path = path.clone();
+            path.push(key.clone().into_owned());
+            operations.push(Operation::Add {
+                path,
+                value: value.clone().into_owned(),
+            });
+        }
+    }
+}
+
+/// Compares two lists using their longest common subsequence. Values between
+/// the common values are compared pairwise, with any remaining values added or
+/// removed.
+fn diff_lists(
+    from: &[Value<'_>],
+    to: &[Value<'_>],
+    path: &mut Vec<OwnedValue>,
+    operations: &mut Vec<Operation>,
+) {
+    let prefix = from
+        .iter()
+        .zip(to)
+        .take_while(|(from, to)| is_same(from, to))
+        .count();
+    let suffix = from[prefix..]
+        .iter()
+        .rev()
+        .zip(to[prefix..].iter().rev())
+        .take_while(|(from, to)| is_same(from, to))
+        .count();
+    let from = &from[prefix..from.len() - suffix];
+    let to = &to[prefix..to.len() - suffix];
+
+    let mut matches = Vec::new();
+    common_subsequence(from, to, (0, 0), &mut matches);
+
+    let mut index = prefix;
+    let (mut i, mut j) = (0, 0);
+    for (match_i, match_j) in matches
+        .into_iter()
+        .chain(core::iter::once((from.len(), to.len())))
+    {
+        diff_gap(
+            &from[i..match_i],
+            &to[j..match_j],
+            &mut index,
+            path,
+            operations,
+        );
+        i = match_i + 1;
+        j = match_j + 1;
+        index += 1;
+    }
+}
+
+/// Pushes the index pairs of a longest common subsequence of `from` and `to`
+/// onto `matches`, in order, offset by `offset`.
+///
+/// This uses Hirschberg's algorithm, which needs memory proportional to the
+/// length of `to` rather than to the product of both lengths.
+fn common_subsequence(
+    from: &[Value<'_>],
+    to: &[Value<'_>],
+    offset: (usize, usize),
+    matches: &mut Vec<(usize, usize)>,
+) {
+    if from.is_empty() || to.is_empty() {
+        return;
+    }
+    if from.len() == 1 {
+        if let Some(j) = to.iter().position(|to| is_same(&from[0], to)) {
+            matches.push((offset.0, offset.1 + j));
+        }
+        return;
+    }
+
+    // Splits `to` where the common subsequence of the first half of `from`
+    // ends and the common subsequence of the second half begins.
+    let middle = from.len() / 2;
+    let forward = subsequence_lengths(from[..middle].iter(), &to.iter());
+    let backward = subsequence_lengths(from[middle..].iter().rev(), &to.iter().rev());
+    let split = (0..=to.len())
+        .max_by_key(|&j| (forward[j] + backward[to.len() - j], core::cmp::Reverse(j)))
+        .unwrap_or_default();
+
+    common_subsequence(&from[..middle], &to[..split], offset, matches);
+    common_subsequence(
+        &from[middle..],
+        &to[split..],
+        (offset.0 + middle, offset.1 + split),
+        matches,
+    );
+}
+
+/// Returns the lengths of the longest common subsequences of `from` and each
+/// prefix of `to`, indexed by the length of the prefix.
+fn subsequence_lengths<'v, 'a: 'v>(
+    from: impl Iterator<Item = &'v Value<'a>>,
+    to: &(impl Iterator<Item = &'v Value<'a>> + Clone),
+) -> Vec<usize> {
+    let mut lengths = vec![0; to.clone().count() + 1];
+    for from in from {
+        let mut diagonal = 0;
+        for (j, to) in to.clone().enumerate() {
+            let above = lengths[j + 1];
+            lengths[j + 1] = if is_same(from, to) {
+                diagonal + 1
+            } else {
+                above.max(lengths[j])
+            };
+            diagonal = above;
+        }
+    }
+    lengths
+}
+
+/// Changes the values in `from` into the values in `to`, starting at `index`
+/// in the list at `path`.
+fn diff_gap(
+    from: &[Value<'_>],
+    to: &[Value<'_>],
+    index: &mut usize,
+    path: &mut Vec<OwnedValue>,
+    operations: &mut Vec<Operation>,
+) {
+    let paired = from.len().min(to.len());
+    for (from, to) in from.iter().zip(to) {
+        path.push(Value::Integer((*index).into()));
+        diff_values(from, to, path, operations);
+        path.pop();
+        *index += 1;
+    }
+    for _ in &from[paired..] {
+        let mut path = path.clone();
+        path.push(Value::Integer((*index).into()));
+        operations.push(Operation::Remove { path });
+    }
+    for value in &to[paired..] {
+        let mut path = path.clone();
+        path.push(Value::Integer((*index).into()));
+        operations.push(Operation::Add {
+            path,
+            value: value.clone().into_owned(),
+        });
+        *index += 1;
+    }
+}
+
+#[test]
+fn round_trip() {
+    use crate::parser::Config;
+
+    let parse = |source| Value::from_str(source, Config::default()).unwrap();
+    let cases = [
+        ("[1, 2, 3]", "[0, 1, 2, 3]"),
+        ("[1, 2, 3, 4, 5]", "[1, 3, 5, 6]"),
+        ("[a, b, c]", "[x, y]"),
+        ("[[1, 2], [3]]", "[[1, 2, 3], [3], []]"),
+        ("(1, b'a', 2.5)", "(1, 97, -0.5)"),
+        (
+            "Config { name: a, tags: [x], nested: { a: 1, b: 2 } }",
+            "Config { tags: [w, x], nested: { b: 3, c: Some(4) }, extra: () }",
+        ),
+        ("A(1, 2)", "B(1, 3)"),
+        ("A(1, 2)", "A { a: 1 }"),
+        ("{1: a, 2: b}", "{1: c}"),
+        ("true", "[true]"),
+    ];
+    for (from, to) in cases {
+        let (from, to) = (parse(from), parse(to));
+        let patch = diff(&from, &to);
+        let mut patched = from.clone();
+        patch.apply(&mut patched).unwrap();
+        assert!(patched.total_cmp(&to).is_eq(), "{from} -> {to}: {patch}");
+
+        let source = patch.to_string();
+        let value = Value::from_str(&source, Config::default()).unwrap();
+        assert_eq!(Patch::from_value(&value).unwrap(), patch);
+        #[cfg(feature = "serde")]
+        assert_eq!(crate::from_str::<Patch>(&source).unwrap(), patch);
+    }
+
+    assert!(diff(&parse("[1, 2]"), &parse("[1, 2]")).is_empty());
+    assert_eq!(
+        diff(&parse("[1, 2, 3]"), &parse("[1, 9, 2, 3]")).to_string(),
+        "[Add{path:[1],value:9}]"
+    );
+    assert_eq!(
+        diff(&parse("[1, 2, 3]"), &parse("[1, 3]")).to_string(),
+        "[Remove{path:[1]}]"
+    );
+
+    let list = |values: &mut dyn Iterator<Item = usize>| {
+        Value::Array(List(
+            values.map(|value| Value::Integer(value.into())).collect(),
+        ))
+    };
+    let from = list(&mut (0..300));
+    let to = list(&mut (0..300).filter(|value| value % 3 != 0).rev());
+    let patch = diff(&from, &to);
+    let mut patched = from.clone();
+    patch.apply(&mut patched).unwrap();
+    assert_eq!(patched, to);
+    let from = list(&mut (0..300));
+    let to = list(&mut (0..300).filter(|value| value % 3 != 0).chain(1000..1010));
+    assert_eq!(diff(&from, &to).0.len(), 110);
+
+    // Added entries are inserted at the end of their map.
+    let (from, mut to) = (parse("{a: 1, c: 3}"), parse("{a: 1, b: 2, c: 3}"));
+    let mut patched = from.clone();
+    diff(&from, &to).apply(&mut patched).unwrap();
+    assert_ne!(patched, to);
+    patched.canonicalize();
+    to.canonicalize();
+    assert_eq!(patched, to);
+
+    let err = Patch(vec![
+        Operation::Remove {
+            path: vec![Value::Integer(0_usize.into())],
+        },
+        Operation::Rename {
+            path: Vec::new(),
+            name: "A".into(),
+        },
+    ])
+    .apply(&mut parse("[1]"))
+    .unwrap_err();
+    assert_eq!(err, PatchError::new(1, PatchErrorKind::NotNamed));
+    assert_eq!(
+        Patch::from_value(&parse("[Move { path: [] }]")).unwrap_err(),
+        PatchError::new(0, PatchErrorKind::InvalidOperation)
+    );
+}
@@ -0,0 +1,776 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+use core::ops::Range;
+
+use super::{List, Map, Named, StructContents, Value};
+use crate::parser::{self, Event, EventKind, Nested, Parser, Primitive};
+use crate::tokenizer::{Dialect, Integer, Token, TokenKind, Tokenizer};
+
+/// The configuration used when converting between [`Value`] and JSON.
+///
+/// Values that JSON supports directly are always converted to their JSON
+/// equivalent: integers and finite floats become numbers, booleans become
+/// booleans, strings become strings, arrays become arrays, and maps whose keys
+/// are all strings become objects. JSON's `null` is converted to the unit
+/// value, `()`.
+///
+/// The remaining values depend on [`JsonConfig::annotated`]:
+///
+/// | Rsn                  | Lossy                         | Annotated                                    |
+/// |----------------------|-------------------------------|----------------------------------------------|
+/// | `inf`, `-inf`, `NaN` | `null`                        | `{"$float": "inf"}`                          |
+/// | `'a'`                | `"a"`                         | `{"$char": "a"}`                             |
+/// | `b'a'`               | `97`                          | `{"$byte": 97}`                              |
+/// | `Name`               | `"Name"`                      | `{"$identifier": "Name"}`                    |
+/// | `b"ab"`              | `[97, 98]`                    | `{"$bytes": [97, 98]}`                       |
+/// | `()`                 | `null`                        | `{"$tuple": []}`                             |
+/// | `(1, 2)`             | `[1, 2]`                      | `{"$tuple": [1, 2]}`                         |
+/// | `{a: 1}`             | `{"a": 1}`                    | `{"$fields": {"a": 1}}`                      |
+/// | `{1: 2}`             | `{"1": 2}`                    | `{"$map": [[1, 2]]}`                         |
+/// | `Name {a: 1}`        | `{"a": 1}`                    | `{"$named": "Name", "$fields": {"a": 1}}`    |
+/// | `Name(1)`            | `[1]`                         | `{"$named": "Name", "$tuple": [1]}`          |
+///
+/// The lossy mapping writes map keys that are not strings or identifiers
+/// using their compact Rsn representation.
+///
+/// The annotated mapping converts back to an identical [`Value`]. `$fields`
+/// is used for maps whose keys are all identifiers, and `$map` is used for
+/// maps containing other keys or string keys that begin with `$`. Floats are
+/// always written with a decimal point or exponent so that they remain floats.
+///
+/// ```rust
+/// use rsn::value::{JsonConfig, Value};
+///
+/// let value = Value::from_str("Point { x: 1.0, y: inf }", Default::default()).unwrap();
+/// assert_eq!(
+///     value.to_json_string(JsonConfig::default()),
+///     r#"{"x":1.0,"y":null}"#
+/// );
+///
+/// let annotated = JsonConfig::default().annotated(true);
+/// let json = value.to_json_string(annotated);
+/// assert_eq!(
+///     json,
+///     r#"{"$named":"Point","$fields":{"x":1.0,"y":{"$float":"inf"}}}"#
+/// );
+/// assert_eq!(Value::from_json(&json, annotated).unwrap(), value);
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct JsonConfig {
+    /// When true, values without a JSON equivalent are written as objects
+    /// describing their Rsn type, and these objects are converted back into
+    /// the original values when parsing.
+    pub annotated: bool,
+    /// When true, JSON is written with each element on its own line, indented
+    /// using two spaces.
+    pub pretty: bool,
+}
+
+impl JsonConfig {
+    /// Sets [`JsonConfig::annotated`] to `annotated` and returns self.
+    #[must_use]
+    pub const fn annotated(mut self, annotated: bool) -> Self {
+        self.annotated = annotated;
+        self
+    }
+
+    /// Sets [`JsonConfig::pretty`] to `pretty` and returns self.
+    #[must_use]
+    pub const fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+}
+
+pub(super) fn to_json_string(value: &Value<'_>, config: JsonConfig) -> String {
+    let mut writer = JsonWriter {
+        output: String::new(),
+        annotated: config.annotated,
+        depth: config.pretty.then_some(0),
+    };
+    writer.value(value);
+    writer.output
+}
+
+struct JsonWriter {
+    output: String,
+    annotated: bool,
+    /// The current indentation level, or `None` when writing compact JSON.
+    depth: Option<usize>,
+}
+
+impl JsonWriter {
+    fn value(&mut self, value: &Value<'_>) {
+        match value {
+            Value::Integer(integer) => match integer {
+                Integer::Usize(value) => self.display(value),
+                Integer::Isize(value) => self.display(value),
+                Integer::UnsignedLarge(value) => self.display(value),
+                Integer::SignedLarge(value) => self.display(value),
+            },
+            Value::Float(value) if value.is_finite() => {
+                // The debug representation always includes a decimal point or
+                // an exponent.
+                write!(self.output, "{value:?}").expect("writing to a string");
+            }
+            Value::Float(value) if self.annotated => {
+                let name = if value.is_nan() {
+                    "NaN"
+                } else if value.is_sign_positive() {
+                    "inf"
+                } else {
+                    "-inf"
+                };
+                self.annotation("$float", |writer| writer.string(name));
+            }
+            Value::Float(_) => self.output.push_str("null"),
+            Value::Bool(value) => self.display(value),
+            Value::Char(value) if self.annotated => {
+                self.annotation("$char", |writer| {
+                    writer.string(value.encode_utf8(&mut [0; 4]));
+                });
+            }
+            Value::Char(value) => self.string(value.encode_utf8(&mut [0; 4])),
+            Value::Byte(value) if self.annotated => {
+                self.annotation("$byte", |writer| writer.display(value));
+            }
+            Value::Byte(value) => self.display(value),
+            Value::Identifier(value) if self.annotated => {
+                self.annotation("$identifier", |writer| writer.string(value));
+            }
+            Value::Identifier(value) | Value::String(value) => self.string(value),
+            Value::Bytes(bytes) if self.annotated => {
+                self.annotation("$bytes", |writer| writer.bytes(bytes));
+            }
+            Value::Bytes(bytes) => self.bytes(bytes),
+            Value::Named(named) if self.annotated => {
+                self.begin('{');
+                self.key("$named");
+                self.string(&named.name);
+                self.comma();
+                match &named.contents {
+                    StructContents::Map(map) => self.annotated_map_contents(map),
+                    StructContents::Tuple(list) => {
+                        self.key("$tuple");
+                        self.list(list);
+                    }
+                }
+                self.end('}');
+            }
+            Value::Named(named) => match &named.contents {
+                StructContents::Map(map) => self.map(map),
+                StructContents::Tuple(list) => self.list(list),
+            },
+            Value::Tuple(list) if self.annotated => {
+                self.annotation("$tuple", |writer| writer.list(list));
+            }
+            Value::Tuple(list) if list.0.is_empty() => self.output.push_str("null"),
+            Value::Tuple(list) | Value::Array(list) => self.list(list),
+            Value::Map(map)
+                if self.annotated
+                    && !map
+                        .keys()
+                        .all(|key| matches!(key, Value::String(key) if !key.starts_with('$'))) =>
+            {
+                self.begin('{');
+                self.annotated_map_contents(map);
+                self.end('}');
+            }
+            Value::Map(map) => self.map(map),
+        }
+    }
+
+    fn display(&mut self, value: impl Display) {
+        write!(self.output, "{value}").expect("writing to a string");
+    }
+
+    /// Begins a nested value, increasing the indentation.
+    fn begin(&mut self, open: char) {
+        self.output.push(open);
+        if let Some(depth) = &mut self.depth {
+            *depth += 1;
+        }
+        self.newline();
+    }
+
+    /// Ends a nested value, decreasing the indentation.
+    fn end(&mut self, close: char) {
+        if let Some(depth) = &mut self.depth {
+            *depth -= 1;
+        }
+        self.newline();
+        self.output.push(close);
+    }
+
+    fn newline(&mut self) {
+        if let Some(depth) = self.depth {
+            self.output.push('\n');
+            for _ in 0..depth {
+                self.output.push_str("  ");
+            }
+        }
+    }
+
+    fn comma(&mut self) {
+        self.output.push(',');
+        self.newline();
+    }
+
+    fn key(&mut self, key: &str) {
+        self.string(key);
+        self.output.push(':');
+        if self.depth.is_some() {
+            self.output.push(' ');
+        }
+    }
+
+    /// Writes an object containing a single annotation.
+    fn annotation(&mut self, name: &str, value: impl FnOnce(&mut Self)) {
+        self.begin('{');
+        self.key(name);
+        value(self);
+        self.end('}');
+    }
+
+    fn sequence<T>(&mut self, elements: &[T], mut element: impl FnMut(&mut Self, &T)) {
+        if elements.is_empty() {
+            self.output.push_str("[]");
+            return;
+        }
+        self.begin('[');
+        for (index, value) in elements.iter().enumerate() {
+            if index > 0 {
+                self.comma();
+            }
+            element(self, value);
+        }
+        self.end(']');
+    }
+
+    fn list(&mut self, list: &List<'_>) {
+        self.sequence(&list.0, Self::value);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.sequence(bytes, |writer, byte| writer.display(byte));
+    }
+
+    fn map(&mut self, map: &Map<'_>) {
+        if map.is_empty() {
+            self.output.push_str("{}");
+            return;
+        }
+        self.begin('{');
+        for (index, (key, value)) in map.iter().enumerate() {
+            if index > 0 {
+                self.comma();
+            }
+            match key {
+                Value::Identifier(key) | Value::String(key) => self.key(key),
+                other => self.key(&other.to_string()),
+            }
+            self.value(value);
+        }
+        self.end('}');
+    }
+
+    /// Writes the `$fields` or `$map` annotation for `map`.
+    fn annotated_map_contents(&mut self, map: &Map<'_>) {
+        if map.keys().all(|key| matches!(key, Value::Identifier(_))) {
+            self.key("$fields");
+            if map.is_empty() {
+                self.output.push_str("{}");
+            } else {
+                self.begin('{');
+                for (index, (key, value)) in map.iter().enumerate() {
+                    if index > 0 {
+                        self.comma();
+                    }
+                    if let Value::Identifier(key) = key {
+                        self.key(key);
+                    }
+                    self.value(value);
+                }
+                self.end('}');
+            }
+        } else {
+            self.key("$map");
+            self.sequence(map.as_slice(), |writer, (key, value)| {
+                writer.begin('[');
+                writer.value(key);
+                writer.comma();
+                writer.value(value);
+                writer.end(']');
+            });
+        }
+    }
+
+    fn string(&mut self, value: &str) {
+        self.output.push('"');
+        for ch in value.chars() {
+            match ch {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                ch if ch.is_control() => {
+                    write!(self.output, "\\u{:04x}", u32::from(ch)).expect("writing to a string");
+                }
+                ch => self.output.push(ch),
+            }
+        }
+        self.output.push('"');
+    }
+}
+
+pub(super) fn from_json(json: &str, config: JsonConfig) -> Result<Value<'_>, JsonError> {
+    let mut builder = JsonBuilder {
+        parser: Parser::new(json, parser::Config::default().dialect(Dialect::Json)),
+        annotated: config.annotated,
+    };
+    let event = builder.parser.next().transpose()?.ok_or_else(|| {
+        JsonError::new(
+            json.len()..json.len(),
+            JsonErrorKind::Parser(parser::ErrorKind::UnexpectedEof),
+        )
+    })?;
+    let value = builder.value(event)?;
+    // The parser returns an error if anything follows the value.
+    builder.parser.next().transpose()?;
+    Ok(value)
+}
+
+/// Builds a [`Value`] from the events of a JSON [`Parser`].
+struct JsonBuilder<'a> {
+    parser: Parser<'a>,
+    annotated: bool,
+}
+
+impl<'a> JsonBuilder<'a> {
+    fn next_event(&mut self) -> Result<Event<'a>, JsonError> {
+        Ok(self
+            .parser
+            .next()
+            .expect("will error or have another event")?)
+    }
+
+    fn value(&mut self, event: Event<'a>) -> Result<Value<'a>, JsonError> {
+        match event.kind {
+            EventKind::BeginNested {
+                kind: Nested::Map, ..
+            } => {
+                let mut entries = Vec::new();
+                let end = loop {
+                    let key = self.next_event()?;
+                    let key = match key.kind {
+                        EventKind::EndNested => break key.location.end,
+                        EventKind::Primitive(Primitive::String(key)) => key,
+                        _ => unreachable!("the JSON dialect only allows string keys"),
+                    };
+                    let value = self.next_event()?;
+                    entries.push((key, self.value(value)?));
+                };
+                if self.annotated
+                    && entries
+                        .first()
+                        .map_or(false, |(key, _)| key.starts_with('$'))
+                {
+                    decode_annotation(entries).ok_or_else(|| {
+                        JsonError::new(event.location.start..end, JsonErrorKind::InvalidAnnotation)
+                    })
+                } else {
+                    Ok(Value::Map(
+                        entries
+                            .into_iter()
+                            .map(|(key, value)| (Value::String(key), value))
+                            .collect(),
+                    ))
+                }
+            }
+            EventKind::BeginNested { .. } => {
+                let mut list = List::new();
+                loop {
+                    let event = self.next_event()?;
+                    if matches!(event.kind, EventKind::EndNested) {
+                        return Ok(Value::Array(list));
+                    }
+                    list.0.push(self.value(event)?);
+                }
+            }
+            EventKind::Primitive(primitive) => Ok(match primitive {
+                Primitive::Bool(value) => Value::Bool(value),
+                Primitive::Integer(value) => Value::Integer(value),
+                Primitive::Float(value) => Value::Float(value),
+                Primitive::String(value) => Value::String(value),
+                // The JSON dialect parses `null` as `None`.
+                Primitive::Identifier(_) => Value::unit(),
+                Primitive::Char(_) | Primitive::Bytes(_) => {
+                    unreachable!("not produced by the JSON dialect")
+                }
+            }),
+            EventKind::Comment(_) | EventKind::Placeholder | EventKind::EndNested => {
+                unreachable!("disabled in parser")
+            }
+        }
+    }
+}
+
+/// Converts the entries of an object whose first key begins with `$` into the
+/// value it describes.
+fn decode_annotation<'a>(mut entries: Vec<(Cow<'a, str>, Value<'a>)>) -> Option<Value<'a>> {
+    let name = if entries.len() == 2 && entries[0].0 == "$named" {
+        let (_, name) = entries.remove(0);
+        match name {
+            Value::String(name) if is_identifier(&name) => Some(name),
+            _ => return None,
+        }
+    } else {
+        None
+    };
+    let [(annotation, value)]: [_; 1] = entries.try_into().ok()?;
+
+    if let Some(name) = name {
+        let contents = match (&*annotation, value) {
+            ("$tuple", Value::Array(list)) => StructContents::Tuple(list),
+            (annotation, value) => match decode_map(annotation, value)? {
+                Value::Map(map) => StructContents::Map(map),
+                _ => return None,
+            },
+        };
+        return Some(Value::Named(Named { name, contents }));
+    }
+
+    match (&*annotation, value) {
+        ("$float", Value::String(name)) => match &*name {
+            "inf" => Some(Value::Float(f64::INFINITY)),
+            "-inf" => Some(Value::Float(f64::NEG_INFINITY)),
+            "NaN" => Some(Value::Float(f64::NAN)),
+            _ => None,
+        },
+        ("$char", Value::String(value)) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Some(Value::Char(ch)),
+                _ => None,
+            }
+        }
+        ("$byte", value) => value.as_u8().map(Value::Byte),
+        ("$identifier", Value::String(name)) if is_identifier(&name) => {
+            Some(Value::Identifier(name))
+        }
+        ("$bytes", Value::Array(list)) => list
+            .0
+            .iter()
+            .map(Value::as_u8)
+            .collect::<Option<Vec<_>>>()
+            .map(|bytes| Value::Bytes(Cow::Owned(bytes))),
+        ("$tuple", Value::Array(list)) => Some(Value::Tuple(list)),
+        (annotation, value) => decode_map(annotation, value),
+    }
+}
+
+/// Decodes the `$fields` and `$map` annotations.
+fn decode_map<'a>(annotation: &str, value: Value<'a>) -> Option<Value<'a>> {
+    match (annotation, value) {
+        ("$fields", Value::Map(map)) => map
+            .into_iter()
+            .map(|(key, value)| match key {
+                Value::String(key) if is_identifier(&key) => Some((Value::Identifier(key), value)),
+                _ => None,
+            })
+            .collect::<Option<Map<'_>>>()
+            .map(Value::Map),
+        ("$map", Value::Array(entries)) => entries
+            .0
+            .into_iter()
+            .map(|entry| match entry {
+                Value::Array(List(entry)) => {
+                    let [key, value]: [_; 2] = entry.try_into().ok()?;
+                    Some((key, value))
+                }
+                _ => None,
+            })
+            .collect::<Option<Map<'_>>>()
+            .map(Value::Map),
+        _ => None,
+    }
+}
+
+/// Returns true if `name` is written as an identifier that parses as `name`.
+fn is_identifier(name: &str) -> bool {
+    let mut tokens = Tokenizer::minified(name);
+    matches!(
+        tokens.next(),
+        Some(Ok(Token {
+            kind: TokenKind::Identifier(parsed),
+            ..
+        })) if parsed == name
+    ) && tokens.next().is_none()
+}
+
+/// An error that arose while parsing JSON.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JsonError {
+    /// The byte range of this error.
+    pub location: Range<usize>,
+    /// The kind of error that occurred.
+    pub kind: JsonErrorKind,
+}
+
+impl JsonError {
+    #[must_use]
+    pub(crate) const fn new(location: Range<usize>, kind: JsonErrorKind) -> Self {
+        Self { location, kind }
+    }
+}
+
+impl From<parser::Error> for JsonError {
+    fn from(err: parser::Error) -> Self {
+        Self::new(err.location, err.kind.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JsonError {}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+/// A kind of error that arose while parsing JSON.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum JsonErrorKind {
+    /// The source was not valid JSON, or exceeded one of the limits of the
+    /// default [`parser::Config`].
+    Parser(parser::ErrorKind),
+    /// An object beginning with a key starting with `$` was not a valid
+    /// annotation.
+    ///
+    /// This error is only returned when [`JsonConfig::annotated`] is true.
+    InvalidAnnotation,
+}
+
+impl From<parser::ErrorKind> for JsonErrorKind {
+    fn from(kind: parser::ErrorKind) -> Self {
+        Self::Parser(kind)
+    }
+}
+
+impl Display for JsonErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            JsonErrorKind::Parser(parser) => Display::fmt(parser, f),
+            JsonErrorKind::InvalidAnnotation => f.write_str("invalid Rsn type annotation"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use alloc::string::ToString;
+    use core::ops::Range;
+
+    use super::{JsonConfig, JsonErrorKind};
+    use crate::parser::{ErrorKind, Nested};
+    use crate::tokenizer;
+    use crate::value::Value;
+
+    const ANNOTATED: JsonConfig = JsonConfig {
+        annotated: true,
+        pretty: false,
+    };
+
+    #[track_caller]
+    fn assert_lossy(rsn: &str, json: &str) {
+        let value = Value::from_str(rsn, crate::parser::Config::default()).unwrap();
+        assert_eq!(value.to_json_string(JsonConfig::default()), json);
+    }
+
+    #[track_caller]
+    fn assert_annotated(rsn: &str, json: &str) {
+        let value = Value::from_str(rsn, crate::parser::Config::default()).unwrap();
+        assert_eq!(value.to_json_string(ANNOTATED), json);
+        let parsed = Value::from_json(json, ANNOTATED).unwrap();
+        // NaN is never equal to itself.
+        assert_eq!(parsed.to_string(), value.to_string());
+    }
+
+    #[test]
+    fn lossy() {
+        assert_lossy(
+            "[1, -2, 1.0, 1e100, inf, NaN]",
+            "[1,-2,1.0,1e100,null,null]",
+        );
+        assert_lossy("('a', b'a', Ident, b\"ab\")", r#"["a",97,"Ident",[97,98]]"#);
+        assert_lossy(
+            "[(), (1,), Named(1), Named { a: 1 }]",
+            r#"[null,[1],[1],{"a":1}]"#,
+        );
+        assert_lossy(
+            r#"{a: 1, "b\n": 2, 3: 3, [1]: 4, 'c': 5}"#,
+            r#"{"a":1,"b\n":2,"3":3,"[1]":4,"'c'":5}"#,
+        );
+        assert_lossy("[{}, []]", "[{},[]]");
+    }
+
+    #[test]
+    fn annotated() {
+        assert_annotated("[1, -2, 1.0, true, \"s\"]", r#"[1,-2,1.0,true,"s"]"#);
+        assert_annotated(
+            "[inf, -inf, NaN]",
+            r#"[{"$float":"inf"},{"$float":"-inf"},{"$float":"NaN"}]"#,
+        );
+        assert_annotated(
+            "('a', Ident, b\"ab\")",
+            r#"{"$tuple":[{"$char":"a"},{"$identifier":"Ident"},{"$bytes":[97,98]}]}"#,
+        );
+        assert_eq!(Value::Byte(1).to_json_string(ANNOTATED), r#"{"$byte":1}"#);
+        assert_eq!(
+            Value::from_json(r#"{"$byte":1}"#, ANNOTATED).unwrap(),
+            Value::Byte(1)
+        );
+        assert_annotated(
+            "[Named(1), Named { a: 1 }, Named { \"a\": 1 }, Named {}]",
+            r#"[{"$named":"Named","$tuple":[1]},{"$named":"Named","$fields":{"a":1}},{"$named":"Named","$map":[["a",1]]},{"$named":"Named","$fields":{}}]"#,
+        );
+        assert_annotated(
+            r#"[{"a": 1}, {a: 1}, {"$a": 1}, {1: 2}, {}]"#,
+            r#"[{"a":1},{"$fields":{"a":1}},{"$map":[["$a",1]]},{"$map":[[1,2]]},{}]"#,
+        );
+        assert_annotated("{a: Ident}", r#"{"$fields":{"a":{"$identifier":"Ident"}}}"#);
+    }
+
+    #[test]
+    fn pretty() {
+        let value = Value::from_str(
+            "{a: [1, 2], b: [], c: {}}",
+            crate::parser::Config::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_json_string(JsonConfig::default().pretty(true)),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": [],\n  \"c\": {}\n}"
+        );
+        assert_eq!(
+            Value::from_str("'a'", crate::parser::Config::default())
+                .unwrap()
+                .to_json_string(ANNOTATED.pretty(true)),
+            "{\n  \"$char\": \"a\"\n}"
+        );
+    }
+
+    #[test]
+    fn from_json() {
+        let value = Value::from_json(
+            r#" {"a": [1, -2, 3.5, true, null], "b": "\"\n\u00e9\ud83e\udd80", "c": "x"} "#,
+            JsonConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":[1,-2,3.5,true,()],"b":"\"\né🦀","c":"x"}"#
+        );
+        // Strings without escapes borrow from the source.
+        let Value::Map(map) = value else {
+            unreachable!()
+        };
+        assert!(matches!(
+            map.as_slice()[2].1,
+            Value::String(Cow::Borrowed("x"))
+        ));
+
+        // Annotations are only interpreted when enabled.
+        assert_eq!(
+            Value::from_json(r#"{"$char":"a"}"#, JsonConfig::default())
+                .unwrap()
+                .to_string(),
+            r#"{"$char":"a"}"#
+        );
+        assert_eq!(
+            Value::from_json("1e3", JsonConfig::default()).unwrap(),
+            Value::Float(1000.)
+        );
+        // Integers too large for `Integer` become floats.
+        assert_eq!(
+            Value::from_json(
+                "1000000000000000000000000000000000000000",
+                JsonConfig::default()
+            )
+            .unwrap(),
+            Value::Float(1e39)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        #[track_caller]
+        fn assert_error(json: &str, kind: impl Into<JsonErrorKind>, location: Range<usize>) {
+            let err = Value::from_json(json, ANNOTATED).unwrap_err();
+            assert_eq!((err.kind, err.location), (kind.into(), location));
+        }
+
+        assert_error("", ErrorKind::UnexpectedEof, 0..0);
+        assert_error("[1,]", ErrorKind::ExpectedValue, 3..4);
+        assert_error("[1 2]", ErrorKind::ExpectedCommaOrEnd(Nested::List), 3..4);
+        assert_error("{\"a\" 1}", ErrorKind::ExpectedColon, 5..6);
+        assert_error("{1: 1}", ErrorKind::ExpectedKey, 1..2);
+        assert_error("1 2", ErrorKind::TrailingData, 2..3);
+        assert_error(
+            "-1.",
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::ExpectedDigit),
+            3..3,
+        );
+        assert_error(
+            "\"\\ud800\"",
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::InvalidUnicode),
+            1..8,
+        );
+        assert_error(
+            "\"\n\"",
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::Unexpected('\n')),
+            1..2,
+        );
+        assert_error(
+            "NaN",
+            ErrorKind::Tokenizer(tokenizer::ErrorKind::Unexpected('N')),
+            0..3,
+        );
+        // Nesting is limited by the default `parser::Config::max_depth`.
+        assert_error(&"[".repeat(200_000), ErrorKind::TooDeeplyNested, 128..129);
+        let nested = "[".repeat(128) + &"]".repeat(128);
+        assert!(Value::from_json(&nested, ANNOTATED).is_ok());
+
+        assert_error(
+            "[{\"$char\": \"ab\"}]",
+            JsonErrorKind::InvalidAnnotation,
+            1..16,
+        );
+        // Identifiers must parse back as themselves.
+        for json in [
+            r#"{"$identifier": "not an ident"}"#,
+            r#"{"$identifier": "true"}"#,
+            r#"{"$identifier": "r#a"}"#,
+            r#"{"$fields": {"a b": 1}}"#,
+            r#"{"$named": "1", "$tuple": []}"#,
+        ] {
+            assert_error(json, JsonErrorKind::InvalidAnnotation, 0..json.len());
+        }
+    }
+
+    #[test]
+    fn unescaped_characters() {
+        // Only U+0000 through U+001F must be escaped.
+        assert_eq!(
+            Value::from_json("\"\u{7f}\u{85}\u{9f}\"", JsonConfig::default()).unwrap(),
+            Value::String(Cow::Borrowed("\u{7f}\u{85}\u{9f}"))
+        );
+    }
+}
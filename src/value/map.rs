@@ -0,0 +1,748 @@
+use alloc::vec::{self, Vec};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{BuildHasher, Hasher};
+use core::{mem, slice};
+
+use super::{List, Named, StructContents, Value};
+
+/// A marker in [`Map::table`] for a slot that doesn't contain an entry.
+const EMPTY: usize = usize::MAX;
+
+/// A collection of key-value pairs that preserves the order entries were
+/// inserted in.
+///
+/// Each key can only appear once in a map. Keys are equal when
+/// [`Value::total_cmp`] considers them equal, so `1` and `1.0` are different
+/// keys, as are the identifier `name` and the string `"name"`. Entries are
+/// located using a hash of their keys, and [`Map::get_str`] finds entries
+/// whose key is either an identifier or a string.
+///
+/// When the `std` feature is enabled, keys are hashed using a randomly seeded
+/// hasher, so keys chosen to collide cannot be used to slow down maps read from
+/// untrusted input. Without the `std` feature, keys are hashed using the
+/// unseeded FNV-1a hash function, and reading untrusted input can take time
+/// proportional to the square of the number of entries in a map.
+///
+/// ```rust
+/// use rsn::value::{Map, Value};
+///
+/// let mut map = Map::new();
+/// map.insert(
+///     Value::from_str("b", Default::default()).unwrap(),
+///     Value::Bool(true),
+/// );
+/// map.insert(Value::String("a".into()), Value::Bool(false));
+/// assert_eq!(map.get_str("b"), Some(&Value::Bool(true)));
+/// assert_eq!(Value::Map(map).to_string(), r#"{b:true,"a":false}"#);
+/// ```
+#[derive(Clone, Default)]
+pub struct Map<'a> {
+    entries: Vec<(Value<'a>, Value<'a>)>,
+    /// An open-addressed hash table containing the indices of `entries`. Its
+    /// length is either zero or a power of two greater than twice the number
+    /// of entries.
+    table: Vec<usize>,
+    /// The state used to hash keys, created along with the table.
+    hash_state: Option<HashState>,
+}
+
+/// Creates the hashers used for the keys of a [`Map`].
+#[cfg(feature = "std")]
+type HashState = std::collections::hash_map::RandomState;
+/// Creates the hashers used for the keys of a [`Map`].
+#[cfg(not(feature = "std"))]
+type HashState = core::hash::BuildHasherDefault<KeyHasher>;
+
+impl<'a> Map<'a> {
+    /// Returns an empty map.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            table: Vec::new(),
+            hash_state: None,
+        }
+    }
+
+    /// Returns the number of entries in this map.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this map contains no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of the entry with `key`, if present.
+    #[must_use]
+    pub fn get(&self, key: &Value<'_>) -> Option<&Value<'a>> {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the value of the entry with `key` mutably, if present.
+    pub fn get_mut(&mut self, key: &Value<'_>) -> Option<&mut Value<'a>> {
+        self.index_of(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Returns the value of the first entry whose key is the identifier or
+    /// string `key`, if present.
+    #[must_use]
+    pub fn get_str(&self, key: &str) -> Option<&Value<'a>> {
+        self.index_of_str(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the value of the first entry whose key is the identifier or
+    /// string `key` mutably, if present.
+    pub fn get_str_mut(&mut self, key: &str) -> Option<&mut Value<'a>> {
+        self.index_of_str(key)
+            .map(|index| &mut self.entries[index].1)
+    }
+
+    /// Returns true if this map contains an entry with `key`.
+    #[must_use]
+    pub fn contains_key(&self, key: &Value<'_>) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the position of the entry with `key`, if present.
+    #[must_use]
+    pub fn index_of(&self, key: &Value<'_>) -> Option<usize> {
+        self.find(self.hash(key), |candidate| is_same_key(candidate, key))
+            .ok()
+    }
+
+    /// Returns the position of the first entry whose key is the identifier or
+    /// string `key`, if present.
+    pub(super) fn index_of_str(&self, key: &str) -> Option<usize> {
+        self.find(self.hash(key), |candidate| {
+            matches!(candidate, Value::Identifier(candidate) | Value::String(candidate) if candidate == key)
+        })
+        .ok()
+    }
+
+    /// Returns the key and value of the entry at `index`, if present.
+    #[must_use]
+    pub fn get_index(&self, index: usize) -> Option<(&Value<'a>, &Value<'a>)> {
+        self.entries.get(index).map(|(key, value)| (key, value))
+    }
+
+    /// Returns the key and value of the entry at `index`, with the value
+    /// borrowed mutably, if present.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&Value<'a>, &mut Value<'a>)> {
+        self.entries
+            .get_mut(index)
+            .map(|(key, value)| (&*key, value))
+    }
+
+    /// Inserts an entry from `key` to `value`.
+    ///
+    /// If an entry with `key` already exists, its value is replaced and the
+    /// previous value is returned. The entry keeps its position in the map.
+    /// Otherwise, the entry is added to the end of the map.
+    pub fn insert(&mut self, key: Value<'a>, value: Value<'a>) -> Option<Value<'a>> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry with `key`, returning its value if present.
+    ///
+    /// The entries after the removed entry are shifted to keep their order,
+    /// which takes time proportional to the length of the map.
+    pub fn remove(&mut self, key: &Value<'_>) -> Option<Value<'a>> {
+        self.index_of(key).map(|index| self.remove_index(index).1)
+    }
+
+    /// Removes every entry for which `keep` returns false, preserving the order
+    /// of the remaining entries.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Value<'a>, &mut Value<'a>) -> bool,
+    {
+        let len = self.entries.len();
+        self.entries.retain_mut(|(key, value)| keep(key, value));
+        if self.entries.len() < len {
+            self.rebuild_table(self.table.len());
+        }
+    }
+
+    /// Removes all entries from this map.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.table.clear();
+    }
+
+    /// Returns the entry for `key`, which can be used to inspect, insert, or
+    /// modify the entry in place.
+    ///
+    /// ```rust
+    /// use rsn::value::{Map, Value};
+    ///
+    /// let mut counts = Map::new();
+    /// for word in ["a", "b", "a"] {
+    ///     let count = counts
+    ///         .entry(Value::String(word.into()))
+    ///         .or_insert(Value::Integer(0_usize.into()));
+    ///     *count = Value::Integer((count.as_usize().unwrap() + 1).into());
+    /// }
+    /// assert_eq!(Value::Map(counts).to_string(), r#"{"a":2,"b":1}"#);
+    /// ```
+    pub fn entry(&mut self, key: Value<'a>) -> Entry<'_, 'a> {
+        if (self.entries.len() + 1) * 2 > self.table.len() {
+            let capacity = ((self.entries.len() + 1) * 2).next_power_of_two().max(8);
+            self.rebuild_table(capacity);
+        }
+        match self.find(self.hash(&key), |candidate| is_same_key(candidate, &key)) {
+            Ok(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            Err(slot) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                slot,
+            }),
+        }
+    }
+
+    /// Returns an iterator over the entries of this map, in order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, 'a> {
+        Iter(self.entries.iter())
+    }
+
+    /// Returns an iterator over the entries of this map with mutable values,
+    /// in order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, 'a> {
+        IterMut(self.entries.iter_mut())
+    }
+
+    /// Returns an iterator over the keys of this map, in order.
+    #[must_use]
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &Value<'a>> + ExactSizeIterator {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values of this map, in order.
+    #[must_use]
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Value<'a>> + ExactSizeIterator {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the values of this map mutably, in order.
+    pub fn values_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut Value<'a>> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+
+    /// Returns the entries of this map, in order.
+    #[must_use]
+    pub fn as_slice(&self) -> &[(Value<'a>, Value<'a>)] {
+        &self.entries
+    }
+
+    /// Returns an owned representation, copying to the heap if needed.
+    #[must_use]
+    pub fn into_owned(self) -> Map<'static> {
+        Map {
+            entries: self
+                .entries
+                .into_iter()
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect(),
+            table: self.table,
+            hash_state: self.hash_state,
+        }
+    }
+
+    pub(super) fn canonicalize(&mut self) {
+        let mut entries = mem::take(&mut self.entries);
+        for (key, value) in &mut entries {
+            key.canonicalize();
+            value.canonicalize();
+        }
+        // Canonicalizing can make keys equal. The stable sort keeps them in
+        // their original order, allowing the last value to replace the others.
+        entries.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        *self = entries.into_iter().collect();
+    }
+
+    pub(super) fn total_cmp(&self, other: &Self) -> Ordering {
+        for ((a_key, a_value), (b_key, b_value)) in self.entries.iter().zip(&other.entries) {
+            let ordering = a_key
+                .total_cmp(b_key)
+                .then_with(|| a_value.total_cmp(b_value));
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+        self.entries.len().cmp(&other.entries.len())
+    }
+
+    fn remove_index(&mut self, index: usize) -> (Value<'a>, Value<'a>) {
+        let entry = self.entries.remove(index);
+        self.rebuild_table(self.table.len());
+        entry
+    }
+
+    /// Returns the hash of `key`, or 0 if this map has no table to look it up
+    /// in.
+    fn hash<K>(&self, key: &K) -> u64
+    where
+        K: HashKey + ?Sized,
+    {
+        self.hash_state.as_ref().map_or(0, |state| {
+            let mut hasher = state.build_hasher();
+            key.hash_key(&mut hasher);
+            hasher.finish()
+        })
+    }
+
+    /// Returns the index of the entry in the probe sequence of `hash` whose
+    /// key `is_key` returns true for, or the empty slot that ends the probe
+    /// sequence.
+    fn find<F>(&self, hash: u64, mut is_key: F) -> Result<usize, usize>
+    where
+        F: FnMut(&Value<'a>) -> bool,
+    {
+        if self.table.is_empty() {
+            return Err(EMPTY);
+        }
+        let mask = self.table.len() - 1;
+        let mut slot = slot_for(hash, mask);
+        loop {
+            match self.table[slot] {
+                EMPTY => return Err(slot),
+                index if is_key(&self.entries[index].0) => return Ok(index),
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+    }
+
+    fn rebuild_table(&mut self, capacity: usize) {
+        self.table.clear();
+        self.table.resize(capacity, EMPTY);
+        self.hash_state.get_or_insert_with(HashState::default);
+        let mask = capacity - 1;
+        for (index, (key, _)) in self.entries.iter().enumerate() {
+            let mut slot = slot_for(self.hash(key), mask);
+            while self.table[slot] != EMPTY {
+                slot = (slot + 1) & mask;
+            }
+            self.table[slot] = index;
+        }
+    }
+}
+
+impl Debug for Map<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for Map<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<'a> FromIterator<(Value<'a>, Value<'a>)> for Map<'a> {
+    fn from_iter<T: IntoIterator<Item = (Value<'a>, Value<'a>)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a> Extend<(Value<'a>, Value<'a>)> for Map<'a> {
+    fn extend<T: IntoIterator<Item = (Value<'a>, Value<'a>)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for Map<'a> {
+    type IntoIter = vec::IntoIter<(Value<'a>, Value<'a>)>;
+    type Item = (Value<'a>, Value<'a>);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'m, 'a> IntoIterator for &'m Map<'a> {
+    type IntoIter = Iter<'m, 'a>;
+    type Item = (&'m Value<'a>, &'m Value<'a>);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'m, 'a> IntoIterator for &'m mut Map<'a> {
+    type IntoIter = IterMut<'m, 'a>;
+    type Item = (&'m Value<'a>, &'m mut Value<'a>);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the entries of a [`Map`].
+#[derive(Debug, Clone)]
+pub struct Iter<'m, 'a>(slice::Iter<'m, (Value<'a>, Value<'a>)>);
+
+impl<'m, 'a> Iterator for Iter<'m, 'a> {
+    type Item = (&'m Value<'a>, &'m Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_, '_> {}
+
+/// An iterator over the entries of a [`Map`] with mutable values.
+#[derive(Debug)]
+pub struct IterMut<'m, 'a>(slice::IterMut<'m, (Value<'a>, Value<'a>)>);
+
+impl<'m, 'a> Iterator for IterMut<'m, 'a> {
+    type Item = (&'m Value<'a>, &'m mut Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (&*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterMut<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (&*key, value))
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_, '_> {}
+
+/// An entry in a [`Map`], returned from [`Map::entry`].
+#[derive(Debug)]
+pub enum Entry<'m, 'a> {
+    /// An entry that is present in the map.
+    Occupied(OccupiedEntry<'m, 'a>),
+    /// An entry that is not present in the map.
+    Vacant(VacantEntry<'m, 'a>),
+}
+
+impl<'m, 'a> Entry<'m, 'a> {
+    /// Returns the key of this entry.
+    #[must_use]
+    pub fn key(&self) -> &Value<'a> {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if this entry is vacant, and returns the entry's
+    /// value.
+    #[allow(clippy::must_use_candidate)]
+    pub fn or_insert(self, default: Value<'a>) -> &'m mut Value<'a> {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Inserts the result of `default` if this entry is vacant, and returns
+    /// the entry's value.
+    pub fn or_insert_with<F>(self, default: F) -> &'m mut Value<'a>
+    where
+        F: FnOnce() -> Value<'a>,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `modify` with the entry's value if this entry is occupied, and
+    /// returns self.
+    #[must_use]
+    pub fn and_modify<F>(mut self, modify: F) -> Self
+    where
+        F: FnOnce(&mut Value<'a>),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// An entry that is present in a [`Map`].
+#[derive(Debug)]
+pub struct OccupiedEntry<'m, 'a> {
+    map: &'m mut Map<'a>,
+    index: usize,
+}
+
+impl<'m, 'a> OccupiedEntry<'m, 'a> {
+    /// Returns the key of this entry.
+    #[must_use]
+    pub fn key(&self) -> &Value<'a> {
+        &self.map.entries[self.index].0
+    }
+
+    /// Returns the position of this entry in the map.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the value of this entry.
+    #[must_use]
+    pub fn get(&self) -> &Value<'a> {
+        &self.map.entries[self.index].1
+    }
+
+    /// Returns the value of this entry mutably.
+    pub fn get_mut(&mut self) -> &mut Value<'a> {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Returns the value of this entry mutably, for the lifetime of the map's
+    /// borrow.
+    #[must_use]
+    pub fn into_mut(self) -> &'m mut Value<'a> {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replaces the value of this entry, returning the previous value.
+    pub fn insert(&mut self, value: Value<'a>) -> Value<'a> {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes this entry from the map, returning its value. The entries after
+    /// this entry are shifted to keep their order.
+    #[allow(clippy::must_use_candidate)]
+    pub fn remove(self) -> Value<'a> {
+        self.map.remove_index(self.index).1
+    }
+}
+
+/// An entry that is not present in a [`Map`].
+#[derive(Debug)]
+pub struct VacantEntry<'m, 'a> {
+    map: &'m mut Map<'a>,
+    key: Value<'a>,
+    /// The empty slot in the map's table for this entry's key.
+    slot: usize,
+}
+
+impl<'m, 'a> VacantEntry<'m, 'a> {
+    /// Returns the key of this entry.
+    #[must_use]
+    pub const fn key(&self) -> &Value<'a> {
+        &self.key
+    }
+
+    /// Returns the key of this entry without inserting it.
+    #[must_use]
+    pub fn into_key(self) -> Value<'a> {
+        self.key
+    }
+
+    /// Adds this entry to the end of the map, returning its value.
+    #[allow(clippy::must_use_candidate)]
+    pub fn insert(self, value: Value<'a>) -> &'m mut Value<'a> {
+        let index = self.map.entries.len();
+        self.map.table[self.slot] = index;
+        self.map.entries.push((self.key, value));
+        &mut self.map.entries[index].1
+    }
+}
+
+fn is_same_key(a: &Value<'_>, b: &Value<'_>) -> bool {
+    a.total_cmp(b).is_eq()
+}
+
+#[allow(clippy::cast_possible_truncation)] // Only the low bits are needed.
+const fn slot_for(hash: u64, mask: usize) -> usize {
+    hash as usize & mask
+}
+
+/// A key that can be hashed to find entries in a [`Map`].
+trait HashKey {
+    fn hash_key<H: Hasher>(&self, hasher: &mut H);
+}
+
+impl HashKey for Value<'_> {
+    fn hash_key<H: Hasher>(&self, hasher: &mut H) {
+        hash_value(self, hasher);
+    }
+}
+
+impl HashKey for str {
+    fn hash_key<H: Hasher>(&self, hasher: &mut H) {
+        hash_str(self, hasher);
+    }
+}
+
+/// Hashes `value` such that values [`Value::total_cmp`] considers equal have
+/// the same hash. Identifiers and strings with the same text also have the
+/// same hash, allowing [`Map::get_str`] to find either.
+fn hash_value<H: Hasher>(value: &Value<'_>, hasher: &mut H) {
+    match value {
+        Value::Integer(integer) => {
+            hasher.write_u8(0);
+            if let Some(signed) = integer.as_i128() {
+                hasher.write_i128(signed);
+            } else if let Some(unsigned) = integer.as_u128() {
+                hasher.write_u128(unsigned);
+            }
+        }
+        Value::Float(float) => {
+            hasher.write_u8(1);
+            hasher.write_u64(float.to_bits());
+        }
+        Value::Bool(bool) => {
+            hasher.write_u8(2);
+            hasher.write_u8(u8::from(*bool));
+        }
+        Value::Char(char) => {
+            hasher.write_u8(3);
+            hasher.write_u32(u32::from(*char));
+        }
+        Value::Byte(byte) => {
+            hasher.write_u8(4);
+            hasher.write_u8(*byte);
+        }
+        Value::Identifier(text) | Value::String(text) => hash_str(text, hasher),
+        Value::Bytes(bytes) => {
+            hasher.write_u8(6);
+            hasher.write_usize(bytes.len());
+            hasher.write(bytes);
+        }
+        Value::Named(Named { name, contents }) => {
+            hash_str(name, hasher);
+            match contents {
+                StructContents::Map(map) => hash_map(map, hasher),
+                StructContents::Tuple(list) => hash_list(list, hasher),
+            }
+        }
+        Value::Tuple(list) | Value::Array(list) => hash_list(list, hasher),
+        Value::Map(map) => hash_map(map, hasher),
+    }
+}
+
+fn hash_str<H: Hasher>(text: &str, hasher: &mut H) {
+    hasher.write_u8(5);
+    hasher.write_usize(text.len());
+    hasher.write(text.as_bytes());
+}
+
+fn hash_list<H: Hasher>(list: &List<'_>, hasher: &mut H) {
+    hasher.write_u8(7);
+    hasher.write_usize(list.0.len());
+    for value in &list.0 {
+        hash_value(value, hasher);
+    }
+}
+
+fn hash_map<H: Hasher>(map: &Map<'_>, hasher: &mut H) {
+    hasher.write_u8(8);
+    hasher.write_usize(map.len());
+    for (key, value) in map {
+        hash_value(key, hasher);
+        hash_value(value, hasher);
+    }
+}
+
+/// The 64-bit FNV-1a hash function.
+#[cfg(not(feature = "std"))]
+struct KeyHasher(u64);
+
+#[cfg(not(feature = "std"))]
+impl Default for KeyHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn map() {
+    use alloc::string::ToString;
+
+    use crate::tokenizer::Integer;
+
+    let int = |value: usize| Value::Integer(Integer::from(value));
+    let mut map = Map::new();
+    for index in 0..100 {
+        assert_eq!(map.insert(int(index), int(index * 2)), None);
+    }
+    assert_eq!(map.insert(int(5), int(0)), Some(int(10)));
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.get(&Value::Integer(Integer::Isize(5))), Some(&int(0)));
+    assert_eq!(map.get(&Value::Float(5.)), None);
+    assert_eq!(map.remove(&int(0)), Some(int(0)));
+    assert_eq!(map.index_of(&int(99)), Some(98));
+    assert_eq!(
+        map.keys()
+            .take(3)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["1", "2", "3"]
+    );
+
+    map.clear();
+    map.insert(Value::String("a".into()), int(1));
+    map.insert(Value::Identifier("a".into()), int(2));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_str("a"), Some(&int(1)));
+    assert_eq!(map.get(&Value::Identifier("a".into())), Some(&int(2)));
+    match map.entry(Value::String("a".into())) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), int(1)),
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(map.get_str_mut("a"), Some(&mut int(2)));
+    map.entry(Value::Identifier("b".into()))
+        .and_modify(|_| unreachable!())
+        .or_insert_with(|| int(3));
+    let owned = map.clone().into_owned();
+    assert_eq!(owned.get_str("b"), Some(&int(3)));
+    assert_eq!(owned, map);
+    assert_eq!(Value::Map(map).to_string(), "{a:2,b:3}");
+}
@@ -0,0 +1,239 @@
+use super::{Entry, List, Map, Named, StructContents, Value};
+
+/// Controls how [`Value::merge`] combines two values.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct MergeStrategy {
+    /// How lists and tuples are merged.
+    ///
+    /// Defaults to [`ListMerge::Replace`].
+    pub lists: ListMerge,
+    /// An identifier that deletes a map entry when it is the entry's value in
+    /// the overlay.
+    ///
+    /// For example, with a marker of `Delete`, merging `{port: Delete}` into a
+    /// map removes its `port` entry. Markers that do not delete an existing
+    /// entry are removed from the merged value: markers within lists and
+    /// tuples are dropped, and a marker that is merged into another value,
+    /// such as the overlay itself or a list element merged by index, leaves
+    /// that value unchanged.
+    ///
+    /// Defaults to no marker.
+    pub delete_marker: Option<&'static str>,
+}
+
+impl MergeStrategy {
+    /// Sets [`MergeStrategy::lists`] to `lists` and returns self.
+    #[must_use]
+    pub const fn lists(mut self, lists: ListMerge) -> Self {
+        self.lists = lists;
+        self
+    }
+
+    /// Sets [`MergeStrategy::delete_marker`] to `marker` and returns self.
+    #[must_use]
+    pub const fn delete_marker(mut self, marker: Option<&'static str>) -> Self {
+        self.delete_marker = marker;
+        self
+    }
+
+    fn is_delete_marker(&self, value: &Value<'_>) -> bool {
+        matches!((value, self.delete_marker), (Value::Identifier(value), Some(marker)) if value == marker)
+    }
+
+    /// Returns `value` with any delete markers removed.
+    fn without_delete_markers<'a>(&self, mut value: Value<'a>) -> Value<'a> {
+        if self.delete_marker.is_some() {
+            self.remove_delete_markers(&mut value);
+        }
+        value
+    }
+
+    fn remove_delete_markers(&self, value: &mut Value<'_>) {
+        match value {
+            Value::Named(Named {
+                contents: StructContents::Map(map),
+                ..
+            })
+            | Value::Map(map) => map.retain(|_, value| {
+                if self.is_delete_marker(value) {
+                    false
+                } else {
+                    self.remove_delete_markers(value);
+                    true
+                }
+            }),
+            Value::Named(Named {
+                contents: StructContents::Tuple(list),
+                ..
+            })
+            | Value::Tuple(list)
+            | Value::Array(list) => list.0.retain_mut(|value| {
+                if self.is_delete_marker(value) {
+                    false
+                } else {
+                    self.remove_delete_markers(value);
+                    true
+                }
+            }),
+            _ => {}
+        }
+    }
+}
+
+/// How [`Value::merge`] combines two lists or two tuples.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ListMerge {
+    /// The overlay's list replaces the list.
+    #[default]
+    Replace,
+    /// The overlay's values are added to the end of the list.
+    Append,
+    /// Each of the overlay's values is merged into the value at the same
+    /// index. Values beyond the end of the list are added to it.
+    ByIndex,
+}
+
+pub(super) fn merge<'a>(base: &mut Value<'a>, overlay: Value<'a>, strategy: MergeStrategy) {
+    if strategy.is_delete_marker(&overlay) {
+        return;
+    }
+    match (base, overlay) {
+        (Value::Map(base), Value::Map(overlay)) => merge_maps(base, overlay, strategy),
+        (Value::Named(base), Value::Named(overlay))
+            if base.name == overlay.name
+                && matches!(
+                    (&base.contents, &overlay.contents),
+                    (StructContents::Map(_), StructContents::Map(_))
+                        | (StructContents::Tuple(_), StructContents::Tuple(_))
+                ) =>
+        {
+            match (&mut base.contents, overlay.contents) {
+                (StructContents::Map(base), StructContents::Map(overlay)) => {
+                    merge_maps(base, overlay, strategy);
+                }
+                (StructContents::Tuple(base), StructContents::Tuple(overlay)) => {
+                    merge_lists(base, overlay, strategy);
+                }
+                _ => unreachable!("contents are the same kind"),
+            }
+        }
+        (Value::Tuple(base), Value::Tuple(overlay))
+        | (Value::Array(base), Value::Array(overlay)) => {
+            merge_lists(base, overlay, strategy);
+        }
+        (base, overlay) => *base = strategy.without_delete_markers(overlay),
+    }
+}
+
+fn merge_maps<'a>(base: &mut Map<'a>, overlay: Map<'a>, strategy: MergeStrategy) {
+    for (key, value) in overlay {
+        if strategy.is_delete_marker(&value) {
+            base.remove(&key);
+            continue;
+        }
+        match base.entry(key) {
+            Entry::Occupied(entry) => merge(entry.into_mut(), value, strategy),
+            Entry::Vacant(entry) => {
+                entry.insert(strategy.without_delete_markers(value));
+            }
+        }
+    }
+}
+
+fn merge_lists<'a>(base: &mut List<'a>, overlay: List<'a>, strategy: MergeStrategy) {
+    let mut overlay = overlay.0.into_iter();
+    match strategy.lists {
+        ListMerge::Replace => base.0.clear(),
+        ListMerge::Append => {}
+        ListMerge::ByIndex => {
+            for (base, overlay) in base.0.iter_mut().zip(&mut overlay) {
+                merge(base, overlay, strategy);
+            }
+        }
+    }
+    base.0.extend(
+        overlay
+            .filter(|value| !strategy.is_delete_marker(value))
+            .map(|value| strategy.without_delete_markers(value)),
+    );
+}
+
+#[test]
+fn layers() {
+    use alloc::string::ToString;
+
+    use crate::parser::Config;
+
+    let parse = |source| Value::from_str(source, Config::default()).unwrap();
+    let merged = |strategy: MergeStrategy| {
+        let mut value = parse(
+            r#"Server {
+                name: "defaults",
+                tls: { cert: "a.pem", key: "a.key" },
+                ports: [80, 443],
+                limits: Limits(1, 2),
+                mode: Fast,
+            }"#,
+        );
+        value.merge(
+            parse(
+                r#"Server {
+                    tls: { key: Delete, ca: { path: "ca.pem", pin: Delete } },
+                    ports: [8080],
+                    limits: Limits(3),
+                    mode: Slow { level: 2 },
+                }"#,
+            ),
+            strategy,
+        );
+        value.to_string()
+    };
+
+    assert_eq!(
+        merged(MergeStrategy::default().delete_marker(Some("Delete"))),
+        r#"Server{name:"defaults",tls:{cert:"a.pem",ca:{path:"ca.pem"}},ports:[8080],limits:Limits(3),mode:Slow{level:2}}"#
+    );
+    assert_eq!(
+        merged(MergeStrategy::default().lists(ListMerge::Append)),
+        r#"Server{name:"defaults",tls:{cert:"a.pem",key:Delete,ca:{path:"ca.pem",pin:Delete}},ports:[80,443,8080],limits:Limits(1,2,3),mode:Slow{level:2}}"#
+    );
+    assert_eq!(
+        merged(MergeStrategy::default().lists(ListMerge::ByIndex)),
+        r#"Server{name:"defaults",tls:{cert:"a.pem",key:Delete,ca:{path:"ca.pem",pin:Delete}},ports:[8080,443],limits:Limits(3,2),mode:Slow{level:2}}"#
+    );
+
+    let mut value = parse("Server { port: 80 }");
+    value.merge(parse("Client { host: h }"), MergeStrategy::default());
+    assert_eq!(value.to_string(), "Client{host:h}");
+}
+
+#[test]
+fn unmatched_delete_markers() {
+    use alloc::string::ToString;
+
+    use crate::parser::Config;
+
+    let parse = |source| Value::from_str(source, Config::default()).unwrap();
+    let merged = |base, overlay, lists| {
+        let mut value = parse(base);
+        value.merge(
+            parse(overlay),
+            MergeStrategy::default()
+                .lists(lists)
+                .delete_marker(Some("Delete")),
+        );
+        value.to_string()
+    };
+
+    assert_eq!(
+        merged("[1, 2]", "[Delete, 3, Delete]", ListMerge::ByIndex),
+        "[1,3]"
+    );
+    assert_eq!(merged("[1]", "[Delete, 2]", ListMerge::Append), "[1,2]");
+    assert_eq!(
+        merged("[1]", "[Delete, (2, Delete)]", ListMerge::Replace),
+        "[(2)]"
+    );
+    assert_eq!(merged("{a: 1}", "Delete", ListMerge::Replace), "{a:1}");
+}
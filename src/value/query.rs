@@ -0,0 +1,438 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::ops::Range;
+
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use super::{Map, Named, StructContents, Value};
+use crate::tokenizer::{TokenKind, Tokenizer};
+
+/// A parsed query, such as `servers[*].tls.cert`.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Query<'q>(Vec<Segment<'q>>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment<'q> {
+    /// `.name` or `["name"]`: the value of the map entry whose key is the
+    /// identifier or string `name`.
+    Field(Cow<'q, str>),
+    /// `[0]` or `.0`: an element of a list or tuple, or the value of the map
+    /// entry whose key is the integer.
+    Index(usize),
+    /// `[*]` or `.*`: every element of a list or tuple, and every value of a
+    /// map.
+    Wildcard,
+    /// `::Name`: the current value, if it is a named value called `Name`.
+    Name(Cow<'q, str>),
+    /// `..`: the current value and every value nested within it.
+    Descendants,
+}
+
+impl<'q> Query<'q> {
+    pub fn parse(query: &'q str) -> Result<Self, QueryError> {
+        let mut segments = Vec::new();
+        let mut offset = 0;
+        while offset < query.len() {
+            let remaining = &query[offset..];
+            if let Some(after) = remaining.strip_prefix("..") {
+                segments.push(Segment::Descendants);
+                offset += 2;
+                // A member can directly follow `..`, as in `..port`.
+                if after.starts_with(|ch: char| !matches!(ch, '.' | '[' | ':')) {
+                    offset = parse_member(query, offset, &mut segments)?;
+                }
+            } else if remaining.starts_with('.') {
+                offset = parse_member(query, offset + 1, &mut segments)?;
+            } else if remaining.starts_with('[') {
+                offset = parse_bracketed(query, offset + 1, &mut segments)?;
+            } else if remaining.starts_with("::") {
+                let end = identifier_end(query, offset + 2)
+                    .ok_or_else(|| error_at(query, offset + 2, QueryErrorKind::ExpectedName))?;
+                segments.push(Segment::Name(Cow::Borrowed(&query[offset + 2..end])));
+                offset = end;
+            } else if offset == 0 {
+                offset = parse_member(query, offset, &mut segments)?;
+            } else {
+                return Err(error_at(query, offset, QueryErrorKind::ExpectedSegment));
+            }
+        }
+        Ok(Self(segments))
+    }
+
+    /// Calls `matched` with each value matching this query, in the order they
+    /// appear in `value`, until it returns false. The positions of the nested
+    /// values leading to each match are also provided.
+    pub fn visit<'v, 'a, F>(&self, value: &'v Value<'a>, mut matched: F)
+    where
+        F: FnMut(&'v Value<'a>, &[usize]) -> bool,
+    {
+        visit(&self.0, value, &mut Vec::new(), &mut matched);
+    }
+
+    /// Returns the positions of the nested values leading to the first value
+    /// matching this query, if any.
+    pub fn first_location(&self, value: &Value<'_>) -> Option<Vec<usize>> {
+        let mut first = None;
+        self.visit(value, |_, location| {
+            first = Some(location.to_vec());
+            false
+        });
+        first
+    }
+}
+
+/// Parses a field name, index, or `*` starting at `offset`, returning the
+/// offset after it.
+fn parse_member<'q>(
+    query: &'q str,
+    offset: usize,
+    segments: &mut Vec<Segment<'q>>,
+) -> Result<usize, QueryError> {
+    let remaining = &query[offset..];
+    if remaining.starts_with('*') {
+        segments.push(Segment::Wildcard);
+        Ok(offset + 1)
+    } else if remaining.starts_with(|ch: char| ch.is_ascii_digit()) {
+        let (index, end) = parse_index(query, offset)?;
+        segments.push(Segment::Index(index));
+        Ok(end)
+    } else {
+        let end = identifier_end(query, offset)
+            .ok_or_else(|| error_at(query, offset, QueryErrorKind::ExpectedMember))?;
+        segments.push(Segment::Field(Cow::Borrowed(&query[offset..end])));
+        Ok(end)
+    }
+}
+
+/// Parses the contents of `[]` starting at `offset`, returning the offset
+/// after the closing bracket.
+fn parse_bracketed<'q>(
+    query: &'q str,
+    offset: usize,
+    segments: &mut Vec<Segment<'q>>,
+) -> Result<usize, QueryError> {
+    let offset = skip_whitespace(query, offset);
+    let remaining = &query[offset..];
+    let end = if remaining.starts_with('*') {
+        segments.push(Segment::Wildcard);
+        offset + 1
+    } else if remaining.starts_with(|ch: char| ch.is_ascii_digit()) {
+        let (index, end) = parse_index(query, offset)?;
+        segments.push(Segment::Index(index));
+        end
+    } else {
+        match Tokenizer::minified(remaining).next() {
+            Some(Ok(token)) => match token.kind {
+                TokenKind::String(key) => {
+                    segments.push(Segment::Field(key));
+                    offset + token.location.end
+                }
+                _ => return Err(error_at(query, offset, QueryErrorKind::ExpectedKey)),
+            },
+            _ => return Err(error_at(query, offset, QueryErrorKind::ExpectedKey)),
+        }
+    };
+
+    let end = skip_whitespace(query, end);
+    if query[end..].starts_with(']') {
+        Ok(end + 1)
+    } else {
+        Err(error_at(query, end, QueryErrorKind::ExpectedClosingBracket))
+    }
+}
+
+fn parse_index(query: &str, offset: usize) -> Result<(usize, usize), QueryError> {
+    let end = query[offset..]
+        .find(|ch: char| !ch.is_ascii_digit())
+        .map_or(query.len(), |end| offset + end);
+    let index = query[offset..end]
+        .parse()
+        .map_err(|_| QueryError::new(offset..end, QueryErrorKind::IndexTooLarge))?;
+    Ok((index, end))
+}
+
+/// Returns the end of the identifier starting at `offset`, if there is one.
+fn identifier_end(query: &str, offset: usize) -> Option<usize> {
+    let mut chars = query[offset..].char_indices();
+    match chars.next() {
+        Some((_, ch)) if is_xid_start(ch) || ch == '_' => {}
+        _ => return None,
+    }
+    Some(
+        chars
+            .find(|(_, ch)| !is_xid_continue(*ch))
+            .map_or(query.len(), |(end, _)| offset + end),
+    )
+}
+
+fn skip_whitespace(query: &str, offset: usize) -> usize {
+    query[offset..]
+        .find(|ch: char| !ch.is_whitespace())
+        .map_or(query.len(), |end| offset + end)
+}
+
+/// Returns an error of `kind` for the character at `offset`.
+fn error_at(query: &str, offset: usize, kind: QueryErrorKind) -> QueryError {
+    let end = query[offset..]
+        .chars()
+        .next()
+        .map_or(offset, |ch| offset + ch.len_utf8());
+    QueryError::new(offset..end, kind)
+}
+
+fn visit<'v, 'a, F>(
+    segments: &[Segment<'_>],
+    value: &'v Value<'a>,
+    location: &mut Vec<usize>,
+    matched: &mut F,
+) -> bool
+where
+    F: FnMut(&'v Value<'a>, &[usize]) -> bool,
+{
+    let Some((segment, remaining)) = segments.split_first() else {
+        return matched(value, location);
+    };
+    let nested = Nested::of(value);
+    match segment {
+        Segment::Field(name) => match nested {
+            Nested::Map(map) => map.index_of_str(name).map_or(true, |position| {
+                visit_nested(remaining, nested, position, location, matched)
+            }),
+            Nested::List(_) | Nested::None => true,
+        },
+        Segment::Index(index) => match nested {
+            Nested::List(_) => visit_nested(remaining, nested, *index, location, matched),
+            Nested::Map(map) => map
+                .index_of(&Value::Integer((*index).into()))
+                .map_or(true, |position| {
+                    visit_nested(remaining, nested, position, location, matched)
+                }),
+            Nested::None => true,
+        },
+        Segment::Wildcard => (0..nested.len())
+            .all(|position| visit_nested(remaining, nested, position, location, matched)),
+        Segment::Name(name) => match value {
+            Value::Named(named) if named.name == *name => {
+                visit(remaining, value, location, matched)
+            }
+            _ => true,
+        },
+        Segment::Descendants => {
+            visit(remaining, value, location, matched)
+                && (0..nested.len())
+                    .all(|position| visit_nested(segments, nested, position, location, matched))
+        }
+    }
+}
+
+fn visit_nested<'v, 'a, F>(
+    segments: &[Segment<'_>],
+    nested: Nested<'v, 'a>,
+    position: usize,
+    location: &mut Vec<usize>,
+    matched: &mut F,
+) -> bool
+where
+    F: FnMut(&'v Value<'a>, &[usize]) -> bool,
+{
+    let Some(value) = nested.get(position) else {
+        return true;
+    };
+    location.push(position);
+    let result = visit(segments, value, location, matched);
+    location.pop();
+    result
+}
+
+/// The values nested directly within a value.
+#[derive(Clone, Copy)]
+enum Nested<'v, 'a> {
+    List(&'v [Value<'a>]),
+    Map(&'v Map<'a>),
+    None,
+}
+
+impl<'v, 'a> Nested<'v, 'a> {
+    fn of(value: &'v Value<'a>) -> Self {
+        match value {
+            Value::Named(Named {
+                contents: StructContents::Tuple(list),
+                ..
+            })
+            | Value::Tuple(list)
+            | Value::Array(list) => Self::List(&list.0),
+            Value::Named(Named {
+                contents: StructContents::Map(map),
+                ..
+            })
+            | Value::Map(map) => Self::Map(map),
+            _ => Self::None,
+        }
+    }
+
+    fn len(self) -> usize {
+        match self {
+            Nested::List(list) => list.len(),
+            Nested::Map(map) => map.len(),
+            Nested::None => 0,
+        }
+    }
+
+    fn get(self, position: usize) -> Option<&'v Value<'a>> {
+        match self {
+            Nested::List(list) => list.get(position),
+            Nested::Map(map) => map.get_index(position).map(|(_, value)| value),
+            Nested::None => None,
+        }
+    }
+}
+
+/// Returns the value nested within `value` at `position`.
+pub(super) fn nested_mut<'v, 'a>(
+    value: &'v mut Value<'a>,
+    position: usize,
+) -> Option<&'v mut Value<'a>> {
+    match value {
+        Value::Named(Named {
+            contents: StructContents::Tuple(list),
+            ..
+        })
+        | Value::Tuple(list)
+        | Value::Array(list) => list.0.get_mut(position),
+        Value::Named(Named {
+            contents: StructContents::Map(map),
+            ..
+        })
+        | Value::Map(map) => map.get_index_mut(position).map(|(_, value)| value),
+        _ => None,
+    }
+}
+
+/// An error that arose while parsing a query.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QueryError {
+    /// The byte range of this error.
+    pub location: Range<usize>,
+    /// The kind of error that occurred.
+    pub kind: QueryErrorKind,
+}
+
+impl QueryError {
+    #[must_use]
+    pub(crate) const fn new(location: Range<usize>, kind: QueryErrorKind) -> Self {
+        Self { location, kind }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QueryError {}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+/// A kind of error that arose while parsing a query.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueryErrorKind {
+    /// A field name, index, or `*` was expected.
+    ExpectedMember,
+    /// An index, string key, or `*` was expected inside of `[]`.
+    ExpectedKey,
+    /// A `]` was expected.
+    ExpectedClosingBracket,
+    /// A name was expected after `::`.
+    ExpectedName,
+    /// A `.`, `..`, `[`, or `::` was expected.
+    ExpectedSegment,
+    /// An index was too large to fit in a `usize`.
+    IndexTooLarge,
+}
+
+impl Display for QueryErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QueryErrorKind::ExpectedMember => f.write_str("expected a field name, index, or `*`"),
+            QueryErrorKind::ExpectedKey => f.write_str("expected an index, string, or `*`"),
+            QueryErrorKind::ExpectedClosingBracket => f.write_str("expected `]`"),
+            QueryErrorKind::ExpectedName => f.write_str("expected a name after `::`"),
+            QueryErrorKind::ExpectedSegment => f.write_str("expected `.`, `..`, `[`, or `::`"),
+            QueryErrorKind::IndexTooLarge => f.write_str("index too large"),
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    use alloc::vec;
+
+    assert_eq!(
+        Query::parse("servers[0].tls.cert").unwrap().0,
+        vec![
+            Segment::Field(Cow::Borrowed("servers")),
+            Segment::Index(0),
+            Segment::Field(Cow::Borrowed("tls")),
+            Segment::Field(Cow::Borrowed("cert")),
+        ]
+    );
+    assert_eq!(
+        Query::parse("..port").unwrap().0,
+        vec![Segment::Descendants, Segment::Field(Cow::Borrowed("port"))]
+    );
+    assert_eq!(
+        Query::parse(r#"::Config.plugins[*].0[ "a b" ]..[1]"#)
+            .unwrap()
+            .0,
+        vec![
+            Segment::Name(Cow::Borrowed("Config")),
+            Segment::Field(Cow::Borrowed("plugins")),
+            Segment::Wildcard,
+            Segment::Index(0),
+            Segment::Field(Cow::Borrowed("a b")),
+            Segment::Descendants,
+            Segment::Index(1),
+        ]
+    );
+    assert_eq!(Query::parse("").unwrap().0, vec![]);
+
+    assert_eq!(Query::parse("a..").unwrap().0.len(), 2);
+
+    for (query, location, kind) in [
+        ("a.", 2..2, QueryErrorKind::ExpectedMember),
+        ("a[b]", 2..3, QueryErrorKind::ExpectedKey),
+        ("a[1", 3..3, QueryErrorKind::ExpectedClosingBracket),
+        ("a]", 1..2, QueryErrorKind::ExpectedSegment),
+        ("a::", 3..3, QueryErrorKind::ExpectedName),
+        (
+            "[99999999999999999999999]",
+            1..24,
+            QueryErrorKind::IndexTooLarge,
+        ),
+    ] {
+        assert_eq!(
+            Query::parse(query).unwrap_err(),
+            QueryError::new(location, kind),
+            "{query}"
+        );
+    }
+}
+
+#[test]
+fn lookups() {
+    use alloc::string::ToString;
+
+    let value = Value::from_str(
+        "servers: [Server { tls: { cert: \"a.pem\" } }], 1: Named(true)",
+        crate::parser::Config::default().allow_implicit_map_at_root(true),
+    )
+    .unwrap();
+    let get = |path| value.get_path(path).unwrap().map(ToString::to_string);
+    assert_eq!(get("servers[0].tls.cert").as_deref(), Some("\"a.pem\""));
+    assert_eq!(get("[1][0]").as_deref(), Some("true"));
+    assert_eq!(get("servers[1]"), None);
+    assert_eq!(get("servers.tls"), None);
+    assert_eq!(get(""), Some(value.to_string()));
+}
@@ -6,6 +6,9 @@ use core::fmt::Write;
 
 use crate::tokenizer::Integer;
 use crate::value::{StructContents, Value};
+use crate::writer::layout::Layout;
+
+mod layout;
 
 /// A low-level writer for the Rsn format.
 #[derive(Debug)]
@@ -13,6 +16,7 @@ pub struct Writer<'config, Output> {
     output: Output,
     nested: Vec<NestedState>,
     config: Cow<'config, Config>,
+    layout: Option<Layout>,
 }
 
 impl Default for Writer<'static, String> {
@@ -31,6 +35,17 @@ where
             output,
             nested: Vec::new(),
             config: Cow::Borrowed(config),
+            layout: match config {
+                Config::Pretty {
+                    max_width,
+                    align_map_values,
+                    ..
+                } if max_width.is_some() || *align_map_values => {
+                    // Without a maximum width, no nested value fits on one line.
+                    Some(Layout::new(max_width.unwrap_or(0)))
+                }
+                _ => None,
+            },
         }
     }
 
@@ -53,11 +68,12 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn begin_named_map(&mut self, name: &str) -> fmt::Result {
         self.prepare_to_write_value()?;
-        self.output.write_str(name)?;
+        self.begin_group();
+        self.sink().write_str(name)?;
         if matches!(self.config.as_ref(), Config::Pretty { .. }) {
-            self.output.write_char(' ')?;
+            self.sink().write_char(' ')?;
         }
-        self.output.write_char('{')?;
+        self.sink().write_char('{')?;
         self.nested.push(NestedState::Map(MapState::Empty));
         Ok(())
     }
@@ -70,9 +86,10 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn begin_named_tuple(&mut self, name: &str) -> fmt::Result {
         self.prepare_to_write_value()?;
+        self.begin_group();
         self.nested.push(NestedState::Tuple(SequenceState::Empty));
-        self.output.write_str(name)?;
-        self.output.write_char('(')
+        self.sink().write_str(name)?;
+        self.sink().write_char('(')
     }
 
     /// Begins a map. A corresponding call to `finish_nested` must be made when
@@ -83,8 +100,9 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn begin_map(&mut self) -> fmt::Result {
         self.prepare_to_write_value()?;
+        self.begin_group();
         self.nested.push(NestedState::Map(MapState::Empty));
-        self.output.write_char('{')
+        self.sink().write_char('{')
     }
 
     /// Begins a tuple. A corresponding call to `finish_nested` must be made when
@@ -95,8 +113,9 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn begin_tuple(&mut self) -> fmt::Result {
         self.prepare_to_write_value()?;
+        self.begin_group();
         self.nested.push(NestedState::Tuple(SequenceState::Empty));
-        self.output.write_char('(')
+        self.sink().write_char('(')
     }
 
     /// Begins a list/array. A corresponding call to `finish_nested` must be
@@ -107,12 +126,16 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn begin_list(&mut self) -> fmt::Result {
         self.prepare_to_write_value()?;
+        self.begin_group();
         self.nested.push(NestedState::List(SequenceState::Empty));
-        self.output.write_char('[')
+        self.sink().write_char('[')
     }
 
     /// Writes a primitive value, formatting it as valid Rsn.
     ///
+    /// When using [`Config::Canonical`], the value is written using
+    /// [`Primitive::render_canonical_to`].
+    ///
     /// # Errors
     ///
     /// Returns any errors that arise while writing to `Output`.
@@ -121,7 +144,11 @@ where
         P: Primitive + ?Sized,
     {
         self.prepare_to_write_value()?;
-        p.render_to(&mut self.output)
+        if matches!(self.config.as_ref(), Config::Canonical) {
+            p.render_canonical_to(&mut self.sink())
+        } else {
+            p.render_to(&mut self.sink())
+        }
     }
 
     /// Writes `ident` without any extra formatting.
@@ -131,37 +158,139 @@ where
     /// Returns any errors that arise while writing to `Output`.
     pub fn write_raw_value(&mut self, ident: &str) -> fmt::Result {
         self.prepare_to_write_value()?;
-        self.output.write_str(ident)
+        self.sink().write_str(ident)
+    }
+
+    /// Writes `comment` before the next value or map entry. Each line of
+    /// `comment` is written as a `//` line comment.
+    ///
+    /// When using [`Config::Compact`] or [`Config::Canonical`], each line
+    /// comment is followed by a newline, which is required to end the comment.
+    ///
+    /// ```rust
+    /// use rsn::writer::{Config, Writer};
+    ///
+    /// let config = Config::Pretty {
+    ///     indentation: "  ".into(),
+    ///     newline: "\n".into(),
+    ///     max_width: None,
+    ///     align_map_values: false,
+    /// };
+    /// let mut writer = Writer::new(String::new(), &config);
+    /// writer.begin_list().unwrap();
+    /// writer.write_comment("The first value.").unwrap();
+    /// writer.write_primitive(&1).unwrap();
+    /// writer.finish_nested().unwrap();
+    /// assert_eq!(writer.finish(), "[\n  // The first value.\n  1\n]");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors that arise while writing to `Output`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called between a map's key and its value.
+    pub fn write_comment(&mut self, comment: &str) -> fmt::Result {
+        match self.nested.last_mut() {
+            Some(
+                NestedState::List(state @ SequenceState::NotEmpty)
+                | NestedState::Tuple(state @ SequenceState::NotEmpty),
+            ) => {
+                *state = SequenceState::AfterComment;
+                self.sink().write_char(',')?;
+            }
+            Some(NestedState::List(state) | NestedState::Tuple(state)) => {
+                *state = SequenceState::AfterComment;
+            }
+            Some(NestedState::Map(state @ MapState::AfterEntry)) => {
+                *state = MapState::AfterComment;
+                self.sink().write_char(',')?;
+            }
+            Some(NestedState::Map(state @ (MapState::Empty | MapState::AfterComment))) => {
+                *state = MapState::AfterComment;
+            }
+            Some(NestedState::Map(MapState::AfterKey)) => {
+                unreachable!("comments can't be written between a key and value")
+            }
+            None => {}
+        }
+
+        let mut lines = comment.lines().peekable();
+        if lines.peek().is_none() {
+            return self.write_comment_line("");
+        }
+        for line in lines {
+            self.write_comment_line(line)?;
+        }
+        Ok(())
+    }
+
+    fn write_comment_line(&mut self, line: &str) -> fmt::Result {
+        if !self.nested.is_empty() {
+            if let Some(layout) = &mut self.layout {
+                // A line comment can't be followed by anything else on its line.
+                layout.force_break();
+            }
+            self.line("")?;
+        }
+        self.sink().write_str("//")?;
+        let line = line.trim_end();
+        if !line.is_empty() {
+            self.sink().write_char(' ')?;
+            self.sink().write_str(line)?;
+        }
+        match self.config.as_ref() {
+            Config::Pretty { newline, .. } if self.nested.is_empty() => Sink {
+                output: &mut self.output,
+                layout: self.layout.as_mut(),
+            }
+            .write_str(newline),
+            Config::Pretty { .. } => Ok(()),
+            Config::Compact | Config::Canonical => self.sink().write_char('\n'),
+        }
+    }
+
+    /// Returns true if a comment can be written before the next value.
+    #[cfg(feature = "serde")]
+    pub(crate) fn can_write_comment(&self) -> bool {
+        !matches!(
+            self.nested.last(),
+            Some(NestedState::Map(MapState::AfterKey))
+        )
     }
 
     fn prepare_to_write_value(&mut self) -> fmt::Result {
         match self.nested.last_mut() {
             Some(
-                NestedState::List(state @ SequenceState::Empty)
-                | NestedState::Tuple(state @ SequenceState::Empty),
+                NestedState::List(state @ (SequenceState::Empty | SequenceState::AfterComment))
+                | NestedState::Tuple(state @ (SequenceState::Empty | SequenceState::AfterComment)),
             ) => {
                 *state = SequenceState::NotEmpty;
-                self.insert_newline()?;
+                self.line("")?;
             }
             Some(NestedState::List(_) | NestedState::Tuple(_)) => {
-                self.output.write_char(',')?;
-                self.insert_newline()?;
+                self.sink().write_char(',')?;
+                self.line(" ")?;
             }
-            Some(NestedState::Map(state @ MapState::Empty)) => {
+            Some(NestedState::Map(state @ (MapState::Empty | MapState::AfterComment))) => {
                 *state = MapState::AfterKey;
-                self.insert_newline()?;
+                self.line(" ")?;
+                self.begin_key();
             }
             Some(NestedState::Map(state @ MapState::AfterEntry)) => {
                 *state = MapState::AfterKey;
-                self.output.write_char(',')?;
-                self.insert_newline()?;
+                self.sink().write_char(',')?;
+                self.line(" ")?;
+                self.begin_key();
             }
             Some(NestedState::Map(state @ MapState::AfterKey)) => {
                 *state = MapState::AfterEntry;
-                if matches!(self.config.as_ref(), Config::Compact) {
-                    self.output.write_char(':')?;
+                self.end_key();
+                if matches!(self.config.as_ref(), Config::Compact | Config::Canonical) {
+                    self.sink().write_char(':')?;
                 } else {
-                    self.output.write_str(": ")?;
+                    self.sink().write_str(": ")?;
                 }
             }
             None => {}
@@ -172,22 +301,99 @@ where
 
     /// Inserts the configured newline character, if needed.
     ///
+    /// When [`Config::Pretty::max_width`] is set, the newline is only written
+    /// if the current nested value does not fit on one line.
+    ///
     /// # Errors
     ///
     /// Returns any errors that arise while writing to `Output`.
     pub fn insert_newline(&mut self) -> fmt::Result {
-        if let Config::Pretty {
+        self.line("")
+    }
+
+    /// Inserts the configured newline character and indentation. If the
+    /// current nested value is written on a single line, `flat` is written
+    /// instead.
+    fn line(&mut self, flat: &'static str) -> fmt::Result {
+        let Config::Pretty {
             indentation,
             newline,
             ..
         } = self.config.as_ref()
-        {
-            self.output.write_str(newline)?;
-            for _ in 0..self.nested.len() {
-                self.output.write_str(indentation)?;
+        else {
+            return Ok(());
+        };
+        let depth = self.nested.len();
+        match &mut self.layout {
+            Some(layout) if layout.in_group() => {
+                layout.line(flat, depth);
+                Ok(())
+            }
+            layout => {
+                let mut sink = Sink {
+                    output: &mut self.output,
+                    layout: layout.as_mut(),
+                };
+                sink.write_str(newline)?;
+                for _ in 0..depth {
+                    sink.write_str(indentation)?;
+                }
+                Ok(())
             }
         }
-        Ok(())
+    }
+
+    fn aligns_map_values(&self) -> bool {
+        matches!(
+            self.config.as_ref(),
+            Config::Pretty {
+                align_map_values: true,
+                ..
+            }
+        )
+    }
+
+    fn begin_key(&mut self) {
+        if self.aligns_map_values() {
+            if let Some(layout) = &mut self.layout {
+                layout.begin_key();
+            }
+        }
+    }
+
+    fn end_key(&mut self) {
+        if self.aligns_map_values() {
+            if let Some(layout) = &mut self.layout {
+                layout.end_key();
+            }
+        }
+    }
+
+    fn begin_group(&mut self) {
+        if let Some(layout) = &mut self.layout {
+            layout.begin();
+        }
+    }
+
+    fn end_group(&mut self) -> fmt::Result {
+        match (&mut self.layout, self.config.as_ref()) {
+            (
+                Some(layout),
+                Config::Pretty {
+                    indentation,
+                    newline,
+                    ..
+                },
+            ) => layout.end(&mut self.output, indentation, newline),
+            _ => Ok(()),
+        }
+    }
+
+    fn sink(&mut self) -> Sink<'_, Output> {
+        Sink {
+            output: &mut self.output,
+            layout: self.layout.as_mut(),
+        }
     }
 
     /// Finishes the current nested value.
@@ -202,25 +408,28 @@ where
     pub fn finish_nested(&mut self) -> fmt::Result {
         match self.nested.pop().expect("not in a nested state") {
             NestedState::Tuple(state) => {
-                if matches!(state, SequenceState::NotEmpty) {
-                    self.insert_newline()?;
+                if !matches!(state, SequenceState::Empty) {
+                    self.line("")?;
                 }
-                self.output.write_char(')')
+                self.sink().write_char(')')?;
             }
             NestedState::List(state) => {
-                if matches!(state, SequenceState::NotEmpty) {
-                    self.insert_newline()?;
+                if !matches!(state, SequenceState::Empty) {
+                    self.line("")?;
                 }
-                self.output.write_char(']')
+                self.sink().write_char(']')?;
             }
-            NestedState::Map(state @ (MapState::AfterEntry | MapState::Empty)) => {
-                if matches!(state, MapState::AfterEntry) {
-                    self.insert_newline()?;
+            NestedState::Map(
+                state @ (MapState::AfterEntry | MapState::AfterComment | MapState::Empty),
+            ) => {
+                if !matches!(state, MapState::Empty) {
+                    self.line(" ")?;
                 }
-                self.output.write_char('}')
+                self.sink().write_char('}')?;
             }
             NestedState::Map(_) => unreachable!("map entry not complete"),
         }
+        self.end_group()
     }
 
     /// Writes a value.
@@ -240,13 +449,14 @@ where
             Value::Bool(value) => self.write_primitive(value),
             Value::Char(value) => self.write_primitive(value),
             Value::Byte(value) => self.write_primitive(value),
-            Value::Identifier(value) | Value::String(value) => self.write_primitive(value.as_ref()),
+            Value::Identifier(value) => self.write_raw_value(value),
+            Value::String(value) => self.write_primitive(value.as_ref()),
             Value::Bytes(value) => self.write_primitive(value.as_ref()),
             Value::Named(value) => {
                 match &value.contents {
                     StructContents::Map(map) => {
                         self.begin_named_map(&value.name)?;
-                        for (key, value) in &map.0 {
+                        for (key, value) in map {
                             self.write_value(key)?;
                             self.write_value(value)?;
                         }
@@ -276,7 +486,7 @@ where
             }
             Value::Map(map) => {
                 self.begin_map()?;
-                for (key, value) in &map.0 {
+                for (key, value) in map {
                     self.write_value(key)?;
                     self.write_value(value)?;
                 }
@@ -286,6 +496,25 @@ where
     }
 }
 
+/// The destination of text written by a [`Writer`]: either its output, or
+/// its [`Layout`] while a nested value is being laid out.
+struct Sink<'a, Output> {
+    output: &'a mut Output,
+    layout: Option<&'a mut Layout>,
+}
+
+impl<Output> Write for Sink<'_, Output>
+where
+    Output: Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match &mut self.layout {
+            Some(layout) => layout.write_str(s, self.output),
+            None => self.output.write_str(s),
+        }
+    }
+}
+
 /// A type that can be written as a primitive.q
 pub trait Primitive {
     /// Renders this type to `buffer`.
@@ -294,6 +523,18 @@ pub trait Primitive {
     ///
     /// Returns any errors that arise while writing to `buffer`.
     fn render_to<W: Write>(&self, buffer: &mut W) -> fmt::Result;
+
+    /// Renders the canonical representation of this type to `buffer`, as
+    /// used by [`Config::Canonical`].
+    ///
+    /// By default, this renders the same output as [`Primitive::render_to`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors that arise while writing to `buffer`.
+    fn render_canonical_to<W: Write>(&self, buffer: &mut W) -> fmt::Result {
+        self.render_to(buffer)
+    }
 }
 
 macro_rules! impl_primitive_using_to_string {
@@ -318,8 +559,83 @@ impl_primitive_using_to_string!(i32);
 impl_primitive_using_to_string!(i64);
 impl_primitive_using_to_string!(i128);
 impl_primitive_using_to_string!(isize);
-impl_primitive_using_to_string!(f64);
-impl_primitive_using_to_string!(f32);
+
+macro_rules! impl_primitive_for_float {
+    ($type:ty) => {
+        impl Primitive for $type {
+            fn render_to<W: Write>(&self, buffer: &mut W) -> fmt::Result {
+                write!(buffer, "{self}")
+            }
+
+            fn render_canonical_to<W: Write>(&self, buffer: &mut W) -> fmt::Result {
+                if self.is_nan() {
+                    buffer.write_str("NaN")
+                } else if *self == 0.0 {
+                    // Negative zero is written as zero.
+                    buffer.write_str("0.0")
+                } else if self.is_infinite() {
+                    write!(buffer, "{self}")
+                } else {
+                    let mut buffer = DecimalPointInserter {
+                        buffer,
+                        found: false,
+                    };
+                    let magnitude = self.abs();
+                    if (1e-4..1e16).contains(&magnitude) {
+                        write!(buffer, "{self}")?;
+                    } else {
+                        write!(buffer, "{self:e}")?;
+                    }
+                    buffer.finish()
+                }
+            }
+        }
+    };
+}
+
+/// Forwards writes of a number to `buffer`, inserting `.0` before its
+/// exponent or at its end if it has no decimal point.
+struct DecimalPointInserter<'a, W> {
+    buffer: &'a mut W,
+    found: bool,
+}
+
+impl<W> DecimalPointInserter<'_, W>
+where
+    W: Write,
+{
+    fn finish(self) -> fmt::Result {
+        if self.found {
+            Ok(())
+        } else {
+            self.buffer.write_str(".0")
+        }
+    }
+}
+
+impl<W> Write for DecimalPointInserter<'_, W>
+where
+    W: Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.found {
+            if let Some(exponent) = s.find('e') {
+                let (mantissa, exponent) = s.split_at(exponent);
+                self.buffer.write_str(mantissa)?;
+                if !mantissa.contains('.') {
+                    self.buffer.write_str(".0")?;
+                }
+                self.found = true;
+                return self.buffer.write_str(exponent);
+            }
+            self.found = s.contains('.');
+        }
+        self.buffer.write_str(s)
+    }
+}
+
+impl_primitive_for_float!(f64);
+impl_primitive_for_float!(f32);
 
 impl Primitive for str {
     fn render_to<W: Write>(&self, buffer: &mut W) -> fmt::Result {
@@ -418,6 +734,8 @@ enum NestedState {
 enum SequenceState {
     Empty,
     NotEmpty,
+    /// A comment was written, and the next value doesn't need a separator.
+    AfterComment,
 }
 
 #[derive(Debug)]
@@ -425,6 +743,8 @@ enum MapState {
     Empty,
     AfterEntry,
     AfterKey,
+    /// A comment was written, and the next key doesn't need a separator.
+    AfterComment,
 }
 
 /// A writer configuration.
@@ -434,12 +754,62 @@ pub enum Config {
     /// Renders Rsn in its most compact representation.
     #[default]
     Compact,
+    /// Renders a canonical representation of Rsn, where equal values are
+    /// always written identically. This is useful when the output is hashed
+    /// or signed.
+    ///
+    /// The output uses the same syntax as [`Config::Compact`], without any
+    /// whitespace and without trailing commas. Primitives are written using
+    /// these rules:
+    ///
+    /// - Integers are written in decimal without separators, and a `-` sign
+    ///   only when negative.
+    /// - Floating point numbers are written using the fewest digits that
+    ///   parse as the same value, with at least one digit after the decimal
+    ///   point. Numbers whose magnitude is at least 10<sup>16</sup> or less
+    ///   than 10<sup>-4</sup> are written in exponent notation with a single
+    ///   digit before the decimal point, such as `1.0e16` or `2.5e-8`, and
+    ///   all others in decimal notation. Negative zero is written as `0.0`,
+    ///   and non-finite numbers as `inf`, `-inf`, or `NaN`.
+    /// - Strings and characters escape `"`, `\`, and ASCII control
+    ///   characters. `\0`, `\t`, `\n`, and `\r` use their short escapes,
+    ///   and the remaining control characters use lowercase `\xNN` escapes.
+    ///   All other characters are written as-is.
+    /// - Byte strings are written as `b"..."` using the same escapes, with
+    ///   bytes above `0x7f` written as lowercase `\xNN` escapes.
+    ///
+    /// The writer writes values in the order it receives them. To sort map
+    /// entries, use [`Value::canonicalize`] before writing a value, or
+    /// serialize using `ser::Config::canonical`.
+    Canonical,
     /// Renders Rsn with indentation and line endings.
     Pretty {
         /// The indentation to include for each level of nested data type.
         indentation: Cow<'static, str>,
         /// The newline character(s) to include when wrapping to a new line.
         newline: Cow<'static, str>,
+        /// The maximum width of a line, if any.
+        ///
+        /// When `None`, every value inside of a nested value is written on
+        /// its own line. Otherwise, nested values that fit within the
+        /// remaining width of the line are written on a single line, such as
+        /// `[1, 2, 3]` or `Point { x: 1, y: 2 }`. Nested values that contain
+        /// comments are always written on multiple lines.
+        ///
+        /// The width is measured in `char`s, including indentation.
+        max_width: Option<usize>,
+        /// When true, the keys of each map written on multiple lines are
+        /// padded to the width of the map's widest key, aligning the `:`
+        /// separators and values in a column.
+        ///
+        /// ```text
+        /// Server {
+        ///   host   : "localhost",
+        ///   port   : 80,
+        ///   timeout: 30
+        /// }
+        /// ```
+        align_map_values: bool,
     },
 }
 
@@ -489,3 +859,174 @@ fn byte_rendering() {
     };
     assert_eq!(parsed, to_encode);
 }
+
+#[test]
+fn comments() {
+    fn write(config: &Config) -> String {
+        let mut writer = Writer::new(String::new(), config);
+        writer.write_comment("Header\n\nSecond line ").unwrap();
+        writer.begin_named_map("Named").unwrap();
+        writer.write_comment("First").unwrap();
+        writer.write_raw_value("a").unwrap();
+        writer.write_primitive(&1).unwrap();
+        writer.write_comment("").unwrap();
+        writer.write_comment("Second").unwrap();
+        writer.write_raw_value("b").unwrap();
+        writer.begin_list().unwrap();
+        writer.write_primitive(&2).unwrap();
+        writer.write_comment("Trailing").unwrap();
+        writer.finish_nested().unwrap();
+        writer.finish_nested().unwrap();
+        writer.finish()
+    }
+
+    let pretty = write(&Config::Pretty {
+        indentation: Cow::Borrowed("  "),
+        newline: Cow::Borrowed("\n"),
+        max_width: None,
+        align_map_values: false,
+    });
+    assert_eq!(
+        pretty,
+        "// Header\n//\n// Second line\nNamed {\n  // First\n  a: 1,\n  //\n  // Second\n  b: [\n    2,\n    // Trailing\n  ]\n}"
+    );
+    let compact = write(&Config::Compact);
+    assert_eq!(
+        compact,
+        "// Header\n//\n// Second line\nNamed{// First\na:1,//\n// Second\nb:[2,// Trailing\n]}"
+    );
+    for rendered in [pretty, compact] {
+        assert!(crate::parser::Parser::validate(
+            &rendered,
+            crate::parser::Config::default()
+        ));
+    }
+}
+
+#[test]
+fn max_width() {
+    fn write(source: &str, max_width: usize) -> String {
+        let config = Config::Pretty {
+            indentation: Cow::Borrowed("  "),
+            newline: Cow::Borrowed("\n"),
+            max_width: Some(max_width),
+            align_map_values: false,
+        };
+        let value = Value::from_str(source, crate::parser::Config::default()).unwrap();
+        let mut writer = Writer::new(String::new(), &config);
+        writer.write_value(&value).unwrap();
+        let rendered = writer.finish();
+        assert!(
+            crate::parser::Parser::validate(&rendered, crate::parser::Config::default()),
+            "{rendered}"
+        );
+        rendered
+    }
+
+    assert_eq!(write("[1,2,3]", 9), "[1, 2, 3]");
+    assert_eq!(write("[1,2,3]", 8), "[\n  1,\n  2,\n  3\n]");
+    assert_eq!(write("Point(1.5,2.5)", 80), "Point(1.5, 2.5)");
+    assert_eq!(write("{1:2}", 80), "{ 1: 2 }");
+    assert_eq!(write("{}", 1), "{}");
+    // Only the nested values that overflow are broken.
+    assert_eq!(
+        write("{\"from\":(0,0),\"to\":(10,10),\"label\":\"diagonal\"}", 20),
+        "{\n  \"from\": (0, 0),\n  \"to\": (10, 10),\n  \"label\": \"diagonal\"\n}"
+    );
+    // The separator following a nested value must fit on its line too.
+    assert_eq!(write("[[1,2],[3,4]]", 9), "[\n  [1, 2],\n  [3, 4]\n]");
+    assert_eq!(
+        write("[[1,2],[3,4]]", 8),
+        "[\n  [\n    1,\n    2\n  ],\n  [3, 4]\n]"
+    );
+
+    // Comments always break the nested values they're in.
+    let config = Config::Pretty {
+        indentation: Cow::Borrowed("  "),
+        newline: Cow::Borrowed("\n"),
+        max_width: Some(80),
+        align_map_values: false,
+    };
+    let mut writer = Writer::new(String::new(), &config);
+    writer.begin_named_map("Point").unwrap();
+    writer.write_raw_value("x").unwrap();
+    writer.write_primitive(&1).unwrap();
+    writer.finish_nested().unwrap();
+    assert_eq!(writer.finish(), "Point { x: 1 }");
+
+    let mut writer = Writer::new(String::new(), &config);
+    writer.write_comment("Header").unwrap();
+    writer.begin_list().unwrap();
+    writer.begin_list().unwrap();
+    writer.write_comment("First").unwrap();
+    writer.write_primitive(&1).unwrap();
+    writer.finish_nested().unwrap();
+    writer.begin_tuple().unwrap();
+    writer.write_primitive(&2).unwrap();
+    writer.finish_nested().unwrap();
+    writer.finish_nested().unwrap();
+    assert_eq!(
+        writer.finish(),
+        "// Header\n[\n  [\n    // First\n    1\n  ],\n  (2)\n]"
+    );
+}
+
+#[test]
+fn align_map_values() {
+    fn write(max_width: Option<usize>) -> String {
+        let config = Config::Pretty {
+            indentation: Cow::Borrowed("  "),
+            newline: Cow::Borrowed("\n"),
+            max_width,
+            align_map_values: true,
+        };
+        let mut writer = Writer::new(String::new(), &config);
+        writer.begin_named_map("Table").unwrap();
+        writer.write_raw_value("a").unwrap();
+        writer.write_primitive(&1).unwrap();
+        writer.write_comment("Comment").unwrap();
+        writer.write_raw_value("longer").unwrap();
+        writer.begin_map().unwrap();
+        writer.write_primitive("x").unwrap();
+        writer.write_primitive(&true).unwrap();
+        writer.write_primitive("xyz").unwrap();
+        writer.write_primitive(&false).unwrap();
+        writer.finish_nested().unwrap();
+        writer.write_raw_value("list").unwrap();
+        writer.begin_list().unwrap();
+        writer.write_primitive(&2).unwrap();
+        writer.finish_nested().unwrap();
+        writer.finish_nested().unwrap();
+        let rendered = writer.finish();
+        assert!(
+            crate::parser::Parser::validate(&rendered, crate::parser::Config::default()),
+            "{rendered}"
+        );
+        rendered
+    }
+
+    assert_eq!(
+        write(None),
+        r#"Table {
+  a     : 1,
+  // Comment
+  longer: {
+    "x"  : true,
+    "xyz": false
+  },
+  list  : [
+    2
+  ]
+}"#
+    );
+    // Maps written on a single line are not padded.
+    assert_eq!(
+        write(Some(80)),
+        r#"Table {
+  a     : 1,
+  // Comment
+  longer: { "x": true, "xyz": false },
+  list  : [2]
+}"#
+    );
+}
@@ -0,0 +1,290 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// Lays out nested values so that they are only broken onto multiple lines
+/// when they don't fit within a maximum width.
+///
+/// While a nested value is being written, its text is buffered along with
+/// marks recording where groups begin and end, and where lines may be broken.
+/// Once the outermost group is finished, each group is written on a single
+/// line if it fits in the remaining width, otherwise every line in the group
+/// is broken.
+#[derive(Debug)]
+pub(super) struct Layout {
+    max_width: usize,
+    /// The column the next character written to the output will be at.
+    column: usize,
+    text: String,
+    marks: Vec<Mark>,
+    /// The indices into `marks` of the groups that have not been ended.
+    open: Vec<usize>,
+    /// The text offsets and mark indices of the map keys being written.
+    keys: Vec<(usize, usize)>,
+}
+
+#[derive(Debug)]
+enum Mark {
+    Begin {
+        offset: usize,
+        /// When true, this group must be broken regardless of its width.
+        broken: bool,
+    },
+    Line {
+        offset: usize,
+        /// The text to write instead when the line is not broken.
+        flat: &'static str,
+        depth: usize,
+    },
+    End {
+        offset: usize,
+    },
+    /// The end of a map key that is `width` wide. If the group is broken,
+    /// the key is padded to the width of the widest key in the group.
+    Align {
+        offset: usize,
+        width: usize,
+    },
+}
+
+impl Mark {
+    const fn offset(&self) -> usize {
+        match self {
+            Mark::Begin { offset, .. }
+            | Mark::Line { offset, .. }
+            | Mark::End { offset }
+            | Mark::Align { offset, .. } => *offset,
+        }
+    }
+}
+
+impl Layout {
+    pub const fn new(max_width: usize) -> Self {
+        Self {
+            max_width,
+            column: 0,
+            text: String::new(),
+            marks: Vec::new(),
+            open: Vec::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Returns true if a group has been begun and not yet ended.
+    pub fn in_group(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    /// Writes `text` to the buffer if a group is open, otherwise to `output`.
+    pub fn write_str<Output: Write>(&mut self, text: &str, output: &mut Output) -> fmt::Result {
+        if self.in_group() {
+            self.text.push_str(text);
+            Ok(())
+        } else {
+            self.column = advance(self.column, text);
+            output.write_str(text)
+        }
+    }
+
+    pub fn begin(&mut self) {
+        self.open.push(self.marks.len());
+        self.marks.push(Mark::Begin {
+            offset: self.text.len(),
+            broken: false,
+        });
+    }
+
+    /// Records a point in the current group where a line can be broken. If
+    /// the group is not broken, `flat` is written instead.
+    pub fn line(&mut self, flat: &'static str, depth: usize) {
+        self.marks.push(Mark::Line {
+            offset: self.text.len(),
+            flat,
+            depth,
+        });
+    }
+
+    /// Records the start of a map key in the current group.
+    pub fn begin_key(&mut self) {
+        self.keys.push((self.text.len(), self.marks.len()));
+    }
+
+    /// Records the end of the current map key, allowing it to be aligned
+    /// with the other keys in the current group.
+    pub fn end_key(&mut self) {
+        let (offset, first_mark) = self.keys.pop().expect("no key begun");
+        let lines = self.marks[first_mark..]
+            .iter()
+            .map(|mark| match mark {
+                Mark::Line { flat, .. } => flat.len(),
+                _ => 0,
+            })
+            .sum::<usize>();
+        self.marks.push(Mark::Align {
+            offset: self.text.len(),
+            width: self.text[offset..].chars().count() + lines,
+        });
+    }
+
+    /// Forces all open groups to be broken.
+    pub fn force_break(&mut self) {
+        for index in &self.open {
+            if let Mark::Begin { broken, .. } = &mut self.marks[*index] {
+                *broken = true;
+            }
+        }
+    }
+
+    /// Ends the current group. If it is the outermost group, the buffered
+    /// text is laid out and written to `output`.
+    pub fn end<Output: Write>(
+        &mut self,
+        output: &mut Output,
+        indentation: &str,
+        newline: &str,
+    ) -> fmt::Result {
+        self.open.pop().expect("no open group");
+        self.marks.push(Mark::End {
+            offset: self.text.len(),
+        });
+        if self.in_group() {
+            Ok(())
+        } else {
+            let result = self.render(output, indentation, newline);
+            self.text.clear();
+            self.marks.clear();
+            result
+        }
+    }
+
+    /// Returns the width each group requires to be written on one line,
+    /// indexed by the group's `Begin` mark. This includes any text following
+    /// the group up until the next possible line break.
+    fn flat_widths(&self) -> Vec<usize> {
+        let mut widths = vec![0; self.marks.len()];
+        let mut starts = Vec::new();
+        let mut offset = 0;
+        let mut width = 0;
+        for (index, mark) in self.marks.iter().enumerate() {
+            width += self.text[offset..mark.offset()].chars().count();
+            offset = mark.offset();
+            match mark {
+                Mark::Begin { .. } => starts.push((index, width)),
+                Mark::Line { flat, .. } => width += flat.len(),
+                Mark::End { .. } => {
+                    let (begin, start) = starts.pop().expect("unbalanced groups");
+                    widths[begin] = width - start;
+                }
+                Mark::Align { .. } => {}
+            }
+        }
+
+        let mut trailing_end = self.text.len();
+        let mut open_ends = Vec::new();
+        for (index, mark) in self.marks.iter().enumerate().rev() {
+            match mark {
+                Mark::Line { offset, .. } => trailing_end = *offset,
+                Mark::End { offset } => {
+                    open_ends.push(self.text[*offset..trailing_end].chars().count());
+                }
+                Mark::Begin { .. } => {
+                    widths[index] += open_ends.pop().expect("unbalanced groups");
+                }
+                Mark::Align { .. } => {}
+            }
+        }
+        widths
+    }
+
+    /// Returns the width of the widest key in each group, indexed by the
+    /// group's `Begin` mark.
+    fn key_widths(&self) -> Vec<usize> {
+        let mut widths = vec![0; self.marks.len()];
+        let mut open = Vec::new();
+        for (index, mark) in self.marks.iter().enumerate() {
+            match mark {
+                Mark::Begin { .. } => open.push(index),
+                Mark::End { .. } => {
+                    open.pop();
+                }
+                Mark::Align { width, .. } => {
+                    let group = *open.last().expect("key outside of group");
+                    widths[group] = widths[group].max(*width);
+                }
+                Mark::Line { .. } => {}
+            }
+        }
+        widths
+    }
+
+    fn render<Output: Write>(
+        &mut self,
+        output: &mut Output,
+        indentation: &str,
+        newline: &str,
+    ) -> fmt::Result {
+        let widths = self.flat_widths();
+        let key_widths = self.key_widths();
+        let indentation_width = indentation.chars().count();
+        // Whether each open group is written on a single line, and the width
+        // of its widest key.
+        let mut flat = Vec::new();
+        let mut key_width = Vec::new();
+        let mut written = 0;
+        for (index, mark) in self.marks.iter().enumerate() {
+            let text = &self.text[written..mark.offset()];
+            output.write_str(text)?;
+            self.column = advance(self.column, text);
+            written = mark.offset();
+            match mark {
+                Mark::Begin { broken, .. } => {
+                    let fits = flat.last().copied().unwrap_or(false)
+                        || (!broken && self.column + widths[index] <= self.max_width);
+                    flat.push(fits);
+                    key_width.push(key_widths[index]);
+                }
+                Mark::Line {
+                    flat: flat_text,
+                    depth,
+                    ..
+                } => {
+                    if flat.last().copied().unwrap_or(false) {
+                        output.write_str(flat_text)?;
+                        self.column += flat_text.len();
+                    } else {
+                        output.write_str(newline)?;
+                        for _ in 0..*depth {
+                            output.write_str(indentation)?;
+                        }
+                        self.column = indentation_width * depth;
+                    }
+                }
+                Mark::End { .. } => {
+                    flat.pop();
+                    key_width.pop();
+                }
+                Mark::Align { width, .. } => {
+                    if !flat.last().copied().unwrap_or(false) {
+                        let widest = key_width.last().copied().unwrap_or(0);
+                        for _ in *width..widest {
+                            output.write_char(' ')?;
+                        }
+                        self.column += widest.saturating_sub(*width);
+                    }
+                }
+            }
+        }
+        let text = &self.text[written..];
+        self.column = advance(self.column, text);
+        output.write_str(text)
+    }
+}
+
+/// Returns the column after writing `text` starting at `column`.
+fn advance(column: usize, text: &str) -> usize {
+    match text.rfind('\n') {
+        Some(index) => text[index + 1..].chars().count(),
+        None => column + text.chars().count(),
+    }
+}